@@ -14,10 +14,7 @@ fn fontdue_layout_benchmark(c: &mut Criterion) {
     let font = include_bytes!("../resources/fonts/Roboto-Regular.ttf") as &[u8];
     let roboto_regular = fontdue::Font::from_bytes(font, fontdue::FontSettings::default()).unwrap();
     let mut layout = Layout::new(CoordinateSystem::PositiveYUp);
-    layout.reset(&LayoutSettings {
-        max_width: Some(200.0),
-        ..LayoutSettings::default()
-    });
+    layout.reset(&LayoutSettings::builder().max_width(Some(200.0)).build());
     let fonts = &[roboto_regular];
 
     let mut group = c.benchmark_group("layout/fontdue");
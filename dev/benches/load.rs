@@ -76,10 +76,7 @@ fn setup_fontdue(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font: &
     let parameter = format!("fontdue {}", font_label);
     group.bench_function(BenchmarkId::from_parameter(parameter), |b| {
         b.iter(|| {
-            let settings = FontSettings {
-                scale: SIZE,
-                ..FontSettings::default()
-            };
+            let settings = FontSettings::builder().scale(SIZE).build();
             let font = Font::from_bytes(font, settings).unwrap();
             let (_, bitmap) = font.rasterize(CHARACTER, SIZE);
             bitmap
@@ -12,7 +12,8 @@ const FONTS: [(&str, &[u8]); 2] = [
     ("opentype", include_bytes!("../resources/fonts/Exo2-Regular.otf")),
 ];
 const SIZES: [f32; 6] = [10.0, 20.0, 40.0, 80.0, 160.0, 200.0];
-const FUNCTIONS: [SetupFunction; 4] = [setup_rusttype, setup_ab_glyph, setup_fontdue, setup_freetype];
+const FUNCTIONS: [SetupFunction; 5] =
+    [setup_rusttype, setup_ab_glyph, setup_fontdue, setup_fontdue_scratch, setup_freetype];
 
 fn setup(c: &mut Criterion) {
     let mut group = c.benchmark_group("rasterize");
@@ -81,10 +82,7 @@ fn setup_ab_glyph(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font:
 
 fn setup_fontdue(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font: &[u8], size: f32) {
     use fontdue::{Font, FontSettings};
-    let settings = FontSettings {
-        scale: size,
-        ..FontSettings::default()
-    };
+    let settings = FontSettings::builder().scale(size).build();
     let font = Font::from_bytes(font, settings).unwrap();
     let parameter = format!("fontdue {} {}px", font_label, size);
     group.bench_function(BenchmarkId::from_parameter(parameter), |b| {
@@ -99,6 +97,26 @@ fn setup_fontdue(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font: &
     });
 }
 
+fn setup_fontdue_scratch(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font: &[u8], size: f32) {
+    use fontdue::{Font, FontSettings, RasterScratch};
+    let settings = FontSettings::builder().scale(size).build();
+    let font = Font::from_bytes(font, settings).unwrap();
+    let parameter = format!("fontdue scratch {} {}px", font_label, size);
+    let mut scratch = RasterScratch::new();
+    let mut bitmap = Vec::new();
+    group.bench_function(BenchmarkId::from_parameter(parameter), |b| {
+        b.iter(|| {
+            let mut len = 0;
+            for character in MESSAGE.chars() {
+                let index = font.lookup_glyph_index(character);
+                font.rasterize_into_scratch(index, size, &mut scratch, &mut bitmap);
+                len += bitmap.len();
+            }
+            len
+        })
+    });
+}
+
 #[cfg(feature = "freetype_benchmark")]
 fn setup_freetype(group: &mut BenchmarkGroup<WallTime>, font_label: &str, font: &[u8], size: f32) {
     use freetype::Library;
@@ -16,9 +16,7 @@ pub fn main() {
     let mut layout = Layout::new(CoordinateSystem::PositiveYUp);
     // By default, layout is initialized with the default layout settings. This call is redundant, but
     // demonstrates setting the value with your custom settings.
-    layout.reset(&LayoutSettings {
-        ..LayoutSettings::default()
-    });
+    layout.reset(&LayoutSettings::builder().build());
     // The text that will be laid out, its size, and the index of the font in the font list to use for
     // that section of text.
     layout.append(fonts, &TextStyle::new("Hello ", 35.0, 0));
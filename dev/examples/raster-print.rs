@@ -6,10 +6,7 @@ const SIZE: f32 = 20.0;
 // cargo run --example raster-print --release
 pub fn main() {
     let font = include_bytes!("../resources/fonts/Roboto-Regular.ttf") as &[u8];
-    let settings = fontdue::FontSettings {
-        scale: SIZE,
-        ..fontdue::FontSettings::default()
-    };
+    let settings = fontdue::FontSettings::builder().scale(SIZE).build();
     let font = fontdue::Font::from_bytes(font, settings).unwrap();
 
     println!("Normal:");
@@ -11,10 +11,7 @@ const SIZE: f32 = 12.0;
 pub fn main() {
     // Loading and rasterization
     let font = include_bytes!("../resources/fonts/Roboto-Regular.ttf") as &[u8];
-    let settings = fontdue::FontSettings {
-        scale: SIZE,
-        ..fontdue::FontSettings::default()
-    };
+    let settings = fontdue::FontSettings::builder().scale(SIZE).build();
     let font = fontdue::Font::from_bytes(font, settings).unwrap();
     let (metrics, bitmap) = font.rasterize_subpixel(CHARACTER, SIZE);
 
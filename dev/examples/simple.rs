@@ -18,10 +18,7 @@ pub fn generate_fontdue() {
     // Read the font data.
     let font = PATH;
     // Setup the configuration for how the font will be parsed.
-    let settings = fontdue::FontSettings {
-        scale: SIZE,
-        ..fontdue::FontSettings::default()
-    };
+    let settings = fontdue::FontSettings::builder().scale(SIZE).build();
     // Parse it into the font type.
     let font = fontdue::Font::from_bytes(font, settings).unwrap();
     // Rasterize and get the layout metrics for the character at a size.
@@ -0,0 +1,1364 @@
+use fontdue::layout::CoordinateSystem;
+use fontdue::{
+    collection_face_count, BGRA8Color, CmapEncoding, Font, FontSettings, GridFit, KerningSource,
+    KerningTable, LineMetricsSource, Metrics, OutlineCommand, OutlinePoint, OutlineSink, RasterScratch,
+    SubpixelLayout,
+};
+
+use crate::modules::FONTS;
+
+#[test]
+fn lazy_matches_eager() {
+    for bytes in &FONTS {
+        let eager = Font::from_bytes(*bytes, FontSettings::default()).unwrap();
+        let lazy = Font::from_bytes_lazy(*bytes, FontSettings::default()).unwrap();
+        assert_eq!(eager.glyph_count(), lazy.glyph_count());
+        for (&character, _) in eager.chars() {
+            assert_eq!(eager.rasterize(character, 12.0), lazy.rasterize(character, 12.0));
+        }
+    }
+}
+
+#[test]
+fn lazy_setting_defers_glyph_generation() {
+    let settings = FontSettings::builder().lazy(true).build();
+    let font = Font::from_bytes(FONTS[0], settings).unwrap();
+    let eager = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('a');
+    assert_eq!(font.glyph_memory(index), 0);
+    assert_eq!(font.metrics('a', 20.0), eager.metrics('a', 20.0));
+    assert_eq!(font.glyph_memory(index), eager.glyph_memory(index));
+}
+
+#[test]
+fn keep_data_is_opt_in() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let kept = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let index = font.lookup_glyph_index('o');
+    assert!(font.outline(index, 20.0).is_none());
+    assert!(kept.outline(index, 20.0).is_some());
+    assert_eq!(font.rasterize_indexed(index, 20.0), kept.rasterize_indexed(index, 20.0));
+    assert!(kept.memory_usage() >= font.memory_usage() + FONTS[0].len());
+    assert_ne!(font.file_hash(), kept.file_hash());
+
+    // Fonts without their data serialize without it, with a flag for each glyph that was loaded.
+    let bytes = font.serialize();
+    assert!(kept.serialize().len() + font.glyph_count() as usize >= bytes.len() + FONTS[0].len());
+    let restored = Font::deserialize(&bytes).unwrap();
+    assert!(restored.outline(index, 20.0).is_none());
+    assert_eq!(restored.rasterize_indexed(index, 20.0), font.rasterize_indexed(index, 20.0));
+}
+
+#[test]
+fn rasterize_at_whole_pen_matches_rasterize() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('g');
+    let (metrics, bitmap) = font.rasterize_indexed(index, 17.0);
+    let (x, y, pen_bitmap, width, height) = font.rasterize_at_pen(index, 17.0, 10.0, 20.0);
+    assert_eq!((width, height), (metrics.width, metrics.height));
+    assert_eq!(pen_bitmap, bitmap);
+    assert_eq!(x, 10 + metrics.xmin);
+    assert_eq!(y, 20 - metrics.ymin - metrics.height as i32);
+}
+
+#[test]
+fn coarse_geometry_matches_small_scale() {
+    let large = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let small = Font::from_bytes(FONTS[0], FontSettings::builder().scale(10.0).build()).unwrap();
+    for character in "aegoS@&".chars() {
+        let (large_metrics, large_bitmap) = large.rasterize(character, 10.0);
+        let (small_metrics, small_bitmap) = small.rasterize(character, 10.0);
+        assert_eq!(large_metrics.width, small_metrics.width);
+        assert_eq!(large_metrics.height, small_metrics.height);
+        for (a, b) in large_bitmap.iter().zip(small_bitmap.iter()) {
+            assert!((*a as i32 - *b as i32).abs() <= 16, "{} differs: {} vs {}", character, a, b);
+        }
+    }
+}
+
+#[test]
+fn override_glyph_remaps_character() {
+    let mut font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let hash = font.file_hash();
+    let index = font.lookup_glyph_index('A');
+    assert!(!font.has_glyph('\u{E000}'));
+    font.override_glyph('\u{E000}', index).unwrap();
+    assert_eq!(font.lookup_glyph_index('\u{E000}'), index);
+    assert_eq!(font.rasterize('\u{E000}', 16.0), font.rasterize('A', 16.0));
+    assert_ne!(font.file_hash(), hash);
+    font.override_glyph('\u{E000}', 0).unwrap();
+    assert!(!font.has_glyph('\u{E000}'));
+    assert!(font.override_glyph('B', font.glyph_count()).is_err());
+
+    // Glyphs the character map can't reach are generated when a character is mapped to them.
+    // Fonts that don't keep their data can't generate them.
+    let settings = FontSettings::builder().load_substitutions(false).build();
+    let lazy = Font::from_bytes_lazy(FONTS[0], settings.clone()).unwrap();
+    let unmapped = (1..lazy.glyph_count())
+        .find(|&index| lazy.glyph_to_char(index).is_none() && lazy.outline(index, 20.0).is_some())
+        .unwrap();
+    let mut font = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert!(font.override_glyph('\u{E000}', unmapped).is_err());
+    assert_eq!(font.lookup_glyph_index('\u{E000}'), 0);
+    let settings = FontSettings::builder().load_substitutions(false).keep_data(true).build();
+    let mut font = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert!(font.rasterize_indexed(unmapped, 20.0).1.is_empty());
+    font.override_glyph('\u{E000}', unmapped).unwrap();
+    assert!(!font.rasterize('\u{E000}', 20.0).1.is_empty());
+    assert_eq!(font.rasterize('\u{E000}', 20.0), lazy.rasterize_indexed(unmapped, 20.0));
+}
+
+#[test]
+fn outline_matches_bounds() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    assert!(font.outline(font.lookup_glyph_index(' '), 20.0).is_none());
+
+    let index = font.lookup_glyph_index('o');
+    let outline = font.outline(index, 20.0).unwrap();
+    assert!(matches!(outline.first(), Some(OutlineCommand::MoveTo(_))));
+    assert_eq!(outline.last(), Some(&OutlineCommand::Close));
+    let (mut xmin, mut xmax) = (f32::MAX, f32::MIN);
+    for command in &outline {
+        let point = match command {
+            OutlineCommand::MoveTo(p) | OutlineCommand::LineTo(p) => *p,
+            OutlineCommand::QuadTo(_, p) => *p,
+            OutlineCommand::CubicTo(_, _, p) => *p,
+            OutlineCommand::Close => continue,
+        };
+        xmin = xmin.min(point.x);
+        xmax = xmax.max(point.x);
+    }
+    let bounds = font.metrics_indexed(index, 20.0).bounds;
+    assert!((xmin - bounds.xmin).abs() < 0.5);
+    assert!((xmax - (bounds.xmin + bounds.width)).abs() < 0.5);
+}
+
+#[test]
+fn build_outline_matches_outline() {
+    struct Recorder(Vec<OutlineCommand>);
+
+    impl OutlineSink for Recorder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0.push(OutlineCommand::MoveTo(OutlinePoint::new(x, y)));
+        }
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0.push(OutlineCommand::LineTo(OutlinePoint::new(x, y)));
+        }
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0.push(OutlineCommand::QuadTo(OutlinePoint::new(x1, y1), OutlinePoint::new(x, y)));
+        }
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            let (c0, c1) = (OutlinePoint::new(x1, y1), OutlinePoint::new(x2, y2));
+            self.0.push(OutlineCommand::CubicTo(c0, c1, OutlinePoint::new(x, y)));
+        }
+        fn close(&mut self) {
+            self.0.push(OutlineCommand::Close);
+        }
+    }
+
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let mut recorder = Recorder(Vec::new());
+    font.build_outline(font.lookup_glyph_index(' '), 20.0, &mut recorder);
+    assert!(recorder.0.is_empty());
+
+    let index = font.lookup_glyph_index('o');
+    font.build_outline(index, 20.0, &mut recorder);
+    assert!(recorder.0.iter().any(|command| matches!(command, OutlineCommand::QuadTo(..))));
+    assert_eq!(Some(recorder.0), font.outline(index, 20.0));
+
+    // Synthetic styles are applied the same way.
+    for settings in [
+        FontSettings::builder().keep_data(true).synthetic_oblique(0.2).build(),
+        FontSettings::builder().keep_data(true).synthetic_bold(0.02).synthetic_oblique(0.2).build(),
+    ] {
+        let font = Font::from_bytes(FONTS[0], settings).unwrap();
+        let mut recorder = Recorder(Vec::new());
+        font.build_outline(index, 20.0, &mut recorder);
+        assert_eq!(Some(recorder.0), font.outline(index, 20.0));
+    }
+}
+
+#[test]
+fn rasterize_into_reuses_buffers() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let mut scratch = RasterScratch::new();
+    let mut buffer = Vec::new();
+    for character in "Wg i.".chars() {
+        let index = font.lookup_glyph_index(character);
+        let expected = font.rasterize_indexed(index, 24.0);
+        assert_eq!((font.rasterize_into(index, 24.0, &mut buffer), &buffer), (expected.0, &expected.1));
+        let metrics = font.rasterize_into_scratch(index, 24.0, &mut scratch, &mut buffer);
+        assert_eq!((metrics, &buffer), (expected.0, &expected.1));
+        assert_eq!(font.rasterize_indexed_with(index, 24.0, &mut scratch), expected);
+    }
+    // Smaller glyphs fit in the existing allocation.
+    let index = font.lookup_glyph_index('W');
+    font.rasterize_into_scratch(index, 24.0, &mut scratch, &mut buffer);
+    let pointer = buffer.as_ptr();
+    font.rasterize_into_scratch(font.lookup_glyph_index('.'), 24.0, &mut scratch, &mut buffer);
+    assert_eq!(pointer, buffer.as_ptr());
+}
+
+#[test]
+fn rasterize_color_falls_back_to_grayscale() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let (metrics, coverage) = font.rasterize('&', 18.0);
+    let (color_metrics, color) = font.rasterize_color('&', 18.0);
+    assert_eq!(metrics, color_metrics);
+    assert_eq!(coverage.len(), color.len());
+    for (&c, pixel) in coverage.iter().zip(color.iter()) {
+        assert_eq!(*pixel, BGRA8Color::new(c, c, c, 255));
+    }
+}
+
+#[test]
+fn rasterize_sdf_matches_coverage() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let index = font.lookup_glyph_index('B');
+    let (metrics, coverage) = font.rasterize_indexed(index, 32.0);
+    let (sdf_metrics, field) = font.rasterize_sdf(index, 32.0, 4.0);
+    assert_eq!(sdf_metrics.width, metrics.width + 8);
+    assert_eq!(sdf_metrics.height, metrics.height + 8);
+    assert_eq!(sdf_metrics.xmin, metrics.xmin - 4);
+    assert_eq!(sdf_metrics.ymin, metrics.ymin - 4);
+    assert_eq!(field[0], 0);
+
+    // Pixels well inside or outside of the outline agree with the coverage.
+    for y in 0..metrics.height {
+        for x in 0..metrics.width {
+            let c = coverage[y * metrics.width + x];
+            let d = field[(y + 4) * sdf_metrics.width + x + 4];
+            if c == 255 {
+                assert!(d >= 128, "({}, {}) coverage {} distance {}", x, y, c, d);
+            } else if c == 0 {
+                assert!(d <= 128, "({}, {}) coverage {} distance {}", x, y, c, d);
+            }
+        }
+    }
+    assert_eq!(font.rasterize_sdf(font.lookup_glyph_index(' '), 32.0, 4.0).1, Vec::<u8>::new());
+}
+
+#[test]
+fn advance_width_matches_metrics() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    for character in "Hello, World! \u{E000}".chars() {
+        assert_eq!(font.advance_width(character, 13.0), font.metrics(character, 13.0).advance_width);
+    }
+}
+
+#[test]
+fn gpos_kerning_is_loaded() {
+    let roboto = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert!(roboto.horizontal_kern('A', 'V', 32.0).unwrap() < 0.0);
+    assert!(roboto.horizontal_kern('T', 'o', 32.0).unwrap() < 0.0);
+    assert_eq!(roboto.horizontal_kern('l', 'l', 32.0), None);
+    let lazy = Font::from_bytes_lazy(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(lazy.horizontal_kern('A', 'V', 32.0), roboto.horizontal_kern('A', 'V', 32.0));
+}
+
+#[test]
+fn monospace_detection() {
+    let roboto = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert!(!roboto.is_monospaced());
+    assert_eq!(roboto.monospace_advance(16.0), None);
+    for font in [FONTS[1], FONTS[3]] {
+        let font = Font::from_bytes(font, FontSettings::default()).unwrap();
+        assert!(font.is_monospaced());
+        let advance = font.monospace_advance(16.0).unwrap();
+        assert!((advance - font.advance_width('i', 16.0)).abs() < 0.1);
+        assert!((advance - font.advance_width('W', 16.0)).abs() < 0.1);
+    }
+}
+
+#[test]
+fn measure_sums_advances_and_kerning() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(font.measure("", 20.0), (0.0, 0.0));
+    let expected = font.advance_width('A', 20.0)
+        + font.advance_width('V', 20.0)
+        + font.horizontal_kern('A', 'V', 20.0).unwrap();
+    let (width, height) = font.measure("AV", 20.0);
+    assert!((width - expected).abs() < 0.001);
+    assert!(height > 0.0 && height <= font.metrics('A', 20.0).height as f32);
+    assert!(font.measure("Ag", 20.0).1 > height);
+}
+
+#[test]
+fn variations_on_static_font() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert!(font.variation_axes().is_empty());
+    let settings = FontSettings::builder().variations(vec![(*b"wght", 700.0)]).build();
+    let varied = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert_eq!(varied.rasterize('a', 20.0), font.rasterize('a', 20.0));
+    assert_ne!(varied.file_hash(), font.file_hash());
+}
+
+#[test]
+fn rasterize_stroked_outlines_glyph() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let index = font.lookup_glyph_index('O');
+    let filled = font.rasterize_indexed(index, 60.0).0;
+    let (metrics, bitmap) = font.rasterize_stroked(index, 60.0, 2.0);
+    assert_eq!(bitmap.len(), metrics.width * metrics.height);
+    assert!((metrics.bounds.width - (filled.bounds.width + 2.0)).abs() < 0.01);
+    assert!((metrics.bounds.height - (filled.bounds.height + 2.0)).abs() < 0.01);
+    assert_eq!(metrics.advance_width, filled.advance_width);
+    // The counter of the O stays empty, and the stroke is drawn around the edge.
+    assert_eq!(bitmap[metrics.height / 2 * metrics.width + metrics.width / 2], 0);
+    assert_eq!(bitmap[metrics.height / 2 * metrics.width + 1], 255);
+
+    let space = font.rasterize_stroked(font.lookup_glyph_index(' '), 60.0, 2.0);
+    assert_eq!((space.0.width, space.1.len()), (0, 0));
+    assert_eq!(font.rasterize_stroked(index, 60.0, 0.0).1, Vec::<u8>::new());
+}
+
+#[test]
+fn synthetic_bold_thickens_glyphs() {
+    let regular = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let settings = FontSettings::builder().keep_data(true).synthetic_bold(0.02).build();
+    let bold = Font::from_bytes(FONTS[0], settings.clone()).unwrap();
+    let lazy = Font::from_bytes_lazy(FONTS[0], settings).unwrap();
+    assert_ne!(bold.file_hash(), regular.file_hash());
+
+    let (metrics, bitmap) = regular.rasterize('l', 50.0);
+    let (bold_metrics, bold_bitmap) = bold.rasterize('l', 50.0);
+    assert_eq!(lazy.rasterize('l', 50.0), (bold_metrics, bold_bitmap.clone()));
+    assert!((bold_metrics.bounds.xmin - metrics.bounds.xmin).abs() < 0.01);
+    assert!((bold_metrics.bounds.width - (metrics.bounds.width + 2.0)).abs() < 0.01);
+    assert!((bold_metrics.bounds.height - (metrics.bounds.height + 2.0)).abs() < 0.01);
+    assert!((bold_metrics.advance_width - (metrics.advance_width + 2.0)).abs() < 0.01);
+    let coverage = |bitmap: &[u8]| bitmap.iter().map(|&c| c as u32).sum::<u32>();
+    assert!(coverage(&bold_bitmap) > coverage(&bitmap));
+
+    // The outline is emboldened too.
+    let index = bold.lookup_glyph_index('l');
+    let xmin = bold.outline(index, 50.0).unwrap().iter().fold(f32::MAX, |xmin, command| match command {
+        OutlineCommand::MoveTo(p) | OutlineCommand::LineTo(p) => xmin.min(p.x),
+        _ => xmin,
+    });
+    assert!((xmin - bold_metrics.bounds.xmin).abs() < 0.01);
+}
+
+#[test]
+fn synthetic_oblique_slants_glyphs() {
+    let regular = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let upright = regular.metrics('l', 50.0);
+    for factor in [0.25, -0.25] {
+        let settings = FontSettings::builder().synthetic_oblique(factor).build();
+        let oblique = Font::from_bytes(FONTS[0], settings.clone()).unwrap();
+        let lazy = Font::from_bytes_lazy(FONTS[0], settings).unwrap();
+        assert_eq!(lazy.rasterize('l', 50.0), oblique.rasterize('l', 50.0));
+        assert_ne!(oblique.file_hash(), regular.file_hash());
+
+        let metrics = oblique.metrics('l', 50.0);
+        assert_eq!(metrics.advance_width, upright.advance_width);
+        assert!((metrics.bounds.height - upright.bounds.height).abs() < 0.01);
+        let slant = upright.bounds.height * factor.abs();
+        assert!((metrics.bounds.width - (upright.bounds.width + slant)).abs() < 0.01);
+        // The l sits on the baseline, so its left edge only moves when slanted backwards.
+        let xmin = upright.bounds.xmin + (upright.bounds.ymin + upright.bounds.height) * factor.min(0.0);
+        assert!((metrics.bounds.xmin - xmin).abs() < 0.01);
+    }
+}
+
+#[test]
+fn try_rasterize_skips_missing_characters() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(font.try_rasterize('a', 20.0), Some(font.rasterize('a', 20.0)));
+    assert_eq!(font.try_rasterize('\u{E000}', 20.0), None);
+}
+
+#[test]
+fn name_table_entries() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(font.name(), Some("Roboto"));
+    assert_eq!(font.family_name(), Some("Roboto"));
+    assert_eq!(font.style_name(), Some("Regular"));
+    assert_eq!(font.postscript_name(), Some("Roboto-Regular"));
+}
+
+#[test]
+fn regular_fonts_are_not_bold_or_italic() {
+    for data in FONTS.iter() {
+        let font = Font::from_bytes(*data, FontSettings::default()).unwrap();
+        assert!(!font.is_bold() && !font.is_italic(), "{:?}", font.name());
+    }
+}
+
+#[test]
+fn serialize_round_trips() {
+    let settings = FontSettings::builder().synthetic_bold(0.02).build();
+    for font in [
+        Font::from_bytes(FONTS[0], settings.clone()).unwrap(),
+        Font::from_bytes_lazy(FONTS[0], settings.clone()).unwrap(),
+    ] {
+        // Generate a lazy glyph before serializing, so both generated and pending glyphs are covered.
+        font.rasterize('a', 20.0);
+        let bytes = font.serialize();
+        let loaded = Font::deserialize(&bytes).unwrap();
+        assert_eq!(loaded.file_hash(), font.file_hash());
+        assert_eq!(loaded.name(), font.name());
+        assert_eq!(loaded.chars(), font.chars());
+        assert_eq!(loaded.horizontal_line_metrics(20.0), font.horizontal_line_metrics(20.0));
+        assert_eq!(loaded.horizontal_kern('A', 'V', 20.0), font.horizontal_kern('A', 'V', 20.0));
+        assert_eq!(loaded.monospace_advance(20.0), font.monospace_advance(20.0));
+        for c in ['a', 'g', 'W', '@'] {
+            for px in [4.0, 20.0] {
+                assert_eq!(loaded.rasterize(c, px), font.rasterize(c, px));
+            }
+        }
+
+        assert!(Font::deserialize(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Font::deserialize(&bytes[4..]).is_err());
+    }
+}
+
+#[test]
+fn deserialize_rejects_lines_outside_glyph_bounds() {
+    let font = Font::from_bytes_lazy(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('I');
+    // At a px of the units per em, the metrics are in font units, the same as the stored glyph.
+    let metrics = font.metrics_indexed(index, font.units_per_em());
+    let bounds = metrics.bounds;
+    let header: Vec<u8> = [
+        metrics.advance_width,
+        metrics.advance_height,
+        bounds.xmin,
+        bounds.ymin,
+        bounds.width,
+        bounds.height,
+    ]
+    .iter()
+    .flat_map(|value| value.to_le_bytes())
+    .collect();
+    let bytes = font.serialize();
+    let glyph = bytes.windows(header.len()).position(|window| window == header.as_slice()).unwrap();
+    // The bounds are followed by the number of vertical lines, then the first line's x0, y0, x1, y1.
+    let lines = glyph + header.len();
+    assert!(u32::from_le_bytes(bytes[lines..lines + 4].try_into().unwrap()) > 0);
+    assert!(Font::deserialize(&bytes).is_ok());
+    // Vertical lines keep the same x at both ends, so only the bounds can reject these.
+    for (x0, valid) in [(0.0, true), (bounds.width + 1.0, false), (-1.0, false), (f32::NAN, false)] {
+        let mut tampered = bytes.clone();
+        tampered[lines + 4..lines + 8].copy_from_slice(&x0.to_le_bytes());
+        tampered[lines + 12..lines + 16].copy_from_slice(&x0.to_le_bytes());
+        assert_eq!(Font::deserialize(&tampered).is_ok(), valid);
+    }
+    let mut tampered = bytes.clone();
+    tampered[glyph + 16..glyph + 20].copy_from_slice(&(-1.0f32).to_le_bytes());
+    assert!(Font::deserialize(&tampered).is_err());
+}
+
+#[test]
+fn deserialize_rejects_glyph_counts_that_differ_from_the_font() {
+    let font = Font::from_bytes_lazy(FONTS[0], FontSettings::default()).unwrap();
+    let count = font.glyph_count() as usize;
+    let bytes = font.serialize();
+    // Without generated glyphs, the units per em is followed by no eager glyphs, the lazy glyph
+    // count, an empty slot per glyph, no loaded flags, then the character map and the default
+    // glyph.
+    let mut glyphs = font.units_per_em().to_le_bytes().to_vec();
+    glyphs.extend_from_slice(&[0, 0, 0, 0, 1]);
+    glyphs.extend_from_slice(&(count as u32).to_le_bytes());
+    let start = bytes.windows(glyphs.len()).position(|window| window == glyphs.as_slice()).unwrap() + 4;
+    let rest = start + 4 + 1 + 4 + count + 4 + 4 + 6 * font.chars().len();
+    // Rebuilds the blob with the given lazy glyph count and an empty character map.
+    let craft = |lazy: Option<usize>| {
+        let mut crafted = bytes[..start].to_vec();
+        crafted.extend_from_slice(&0u32.to_le_bytes());
+        crafted.push(lazy.is_some() as u8);
+        if let Some(lazy) = lazy {
+            crafted.extend_from_slice(&(lazy as u32).to_le_bytes());
+            crafted.resize(crafted.len() + lazy, 0);
+        }
+        crafted.extend_from_slice(&0u32.to_le_bytes());
+        crafted.extend_from_slice(&0u32.to_le_bytes());
+        crafted.extend_from_slice(&bytes[rest..]);
+        crafted
+    };
+    assert!(Font::deserialize(&craft(Some(count))).is_ok());
+    assert!(Font::deserialize(&craft(Some(count - 1))).is_err());
+    assert!(Font::deserialize(&craft(None)).is_err());
+}
+
+#[test]
+fn rasterize_subpixel_offset_shifts_glyph() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('o');
+    assert_eq!(font.rasterize_subpixel_offset(index, 20.0, 0.0, 0.0), font.rasterize_indexed(index, 20.0));
+
+    // Whole pixel offsets move the bitmap without changing it.
+    let (metrics, bitmap) = font.rasterize_indexed(index, 20.0);
+    let (shifted, shifted_bitmap) = font.rasterize_subpixel_offset(index, 20.0, 2.0, -1.0);
+    assert_eq!((shifted.xmin, shifted.ymin), (metrics.xmin + 2, metrics.ymin - 1));
+    assert_eq!(shifted_bitmap, bitmap);
+
+    // Fractional offsets change the coverage, but keep the total about the same.
+    let (_, half) = font.rasterize_subpixel_offset(index, 20.0, 0.5, 0.0);
+    assert_ne!(half, bitmap);
+    let sum = |bitmap: &[u8]| bitmap.iter().map(|&x| x as f32).sum::<f32>();
+    assert!((sum(&half) - sum(&bitmap)).abs() < sum(&bitmap) * 0.02);
+}
+
+#[test]
+fn curve_tolerance_controls_flattening() {
+    let load = |curve_tolerance: f32| {
+        let settings = FontSettings::builder().curve_tolerance(curve_tolerance).build();
+        Font::from_bytes(FONTS[0], settings)
+    };
+    let default = load(FontSettings::default().curve_tolerance).unwrap();
+    let fine = load(0.1).unwrap();
+    let index = default.lookup_glyph_index('o');
+    assert!(fine.glyph_memory(index) > default.glyph_memory(index));
+    assert_eq!(fine.metrics('o', 20.0), default.metrics('o', 20.0));
+    assert_ne!(fine.file_hash(), default.file_hash());
+    assert!(load(0.0).is_err());
+    assert!(load(f32::NAN).is_err());
+}
+
+#[test]
+fn rasterize_indexed_scaled_stretches_glyph() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('H');
+    assert_eq!(font.rasterize_indexed_scaled(index, 20.0, 20.0), font.rasterize_indexed(index, 20.0));
+
+    let (metrics, bitmap) = font.rasterize_indexed_scaled(index, 40.0, 20.0);
+    let wide = font.metrics_indexed(index, 40.0);
+    let tall = font.metrics_indexed(index, 20.0);
+    assert_eq!(metrics.advance_width, wide.advance_width);
+    assert_eq!((metrics.xmin, metrics.width), (wide.xmin, wide.width));
+    assert_eq!((metrics.ymin, metrics.height), (tall.ymin, tall.height));
+    assert_eq!(bitmap.len(), metrics.width * metrics.height);
+}
+
+#[test]
+fn shape_simple_matches_measure() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let text = "AVé To";
+    let glyphs = font.shape_simple(text, 20.0);
+    let clusters: Vec<usize> = glyphs.iter().map(|glyph| glyph.cluster).collect();
+    assert_eq!(clusters, vec![0, 1, 2, 4, 5, 6]);
+    assert_eq!(glyphs[1].glyph_index, font.lookup_glyph_index('V'));
+    assert_eq!(glyphs[0].x_offset, 0.0);
+    assert_eq!(glyphs[1].x_offset, font.horizontal_kern('A', 'V', 20.0).unwrap());
+    assert_eq!(glyphs[2].advance, font.advance_width('é', 20.0));
+
+    let width: f32 = glyphs.iter().map(|glyph| glyph.x_offset + glyph.advance).sum();
+    assert!((width - font.measure(text, 20.0).0).abs() < 0.001);
+}
+
+#[test]
+fn kerning_can_be_left_out_of_measurements() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let unkerned = font.advance_width('A', 20.0) + font.advance_width('V', 20.0);
+    let (width, height) = font.measure_with_kerning("AV", 20.0, false);
+    assert!((width - unkerned).abs() < 0.001);
+    assert_eq!(height, font.measure("AV", 20.0).1);
+    assert!(font.measure("AV", 20.0).0 < width);
+
+    let glyphs = font.shape_simple_with_kerning("AV", 20.0, false);
+    assert!(glyphs.iter().all(|glyph| glyph.x_offset == 0.0));
+    assert_eq!(font.shape_simple_with_kerning("AV", 20.0, true), font.shape_simple("AV", 20.0));
+}
+
+#[test]
+fn preload_generates_lazy_glyphs() {
+    let font = Font::from_bytes_lazy(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('b');
+    assert_eq!(font.glyph_memory(index), 0);
+    font.preload("abc".chars());
+    assert_ne!(font.glyph_memory(index), 0);
+    assert_eq!(font.glyph_memory(font.lookup_glyph_index('d')), 0);
+
+    let rasterized = font.rasterize_all("abca".chars(), 20.0);
+    assert_eq!(rasterized.len(), 3);
+    assert_eq!(rasterized[&'c'], font.rasterize('c', 20.0));
+}
+
+#[test]
+fn unscaled_line_metrics_are_in_font_units() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let unscaled = font.horizontal_line_metrics_unscaled().unwrap();
+    assert_eq!(font.horizontal_line_metrics(font.units_per_em()), Some(unscaled));
+    assert_eq!(unscaled.new_line_size, unscaled.ascent - unscaled.descent + unscaled.line_gap);
+    assert_eq!(unscaled.ascent.fract(), 0.0);
+    assert_eq!(font.vertical_line_metrics_unscaled(), font.vertical_line_metrics(font.units_per_em()));
+}
+
+#[test]
+fn cap_and_x_heights() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let top = |character: char| {
+        let bounds = font.metrics(character, 100.0).bounds;
+        bounds.ymin + bounds.height
+    };
+    assert!((font.cap_height(100.0).unwrap() - top('H')).abs() < 0.01);
+    assert!((font.x_height(100.0).unwrap() - top('x')).abs() < 0.01);
+    assert_eq!(font.x_height(50.0).unwrap(), font.x_height(100.0).unwrap() / 2.0);
+}
+
+#[test]
+fn try_rasterize_indexed_checks_bounds() {
+    for font in [
+        Font::from_bytes(FONTS[0], FontSettings::default()).unwrap(),
+        Font::from_bytes_lazy(FONTS[0], FontSettings::default()).unwrap(),
+    ] {
+        let last = font.glyph_count() - 1;
+        assert_eq!(font.try_rasterize_indexed(last, 20.0), Ok(font.rasterize_indexed(last, 20.0)));
+        assert!(font.try_rasterize_indexed(last + 1, 20.0).is_err());
+        assert!(font.try_rasterize_indexed(u16::MAX, 20.0).is_err());
+    }
+}
+
+#[test]
+fn cff_fonts_load_without_glyf_table() {
+    let has_table = |data: &[u8], tag: &[u8]| {
+        let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+        (0..count).any(|index| &data[12 + index * 16..16 + index * 16] == tag)
+    };
+    for data in &FONTS[5..] {
+        assert!(has_table(data, b"CFF ") && !has_table(data, b"glyf"));
+        for font in [
+            Font::from_bytes(*data, FontSettings::builder().keep_data(true).build()).unwrap(),
+            Font::from_bytes_lazy(*data, FontSettings::builder().keep_data(true).build()).unwrap(),
+        ] {
+            let character = *font.chars().keys().filter(|c| c.is_ascii_alphanumeric()).min().unwrap();
+            let (_, bitmap) = font.rasterize(character, 20.0);
+            assert!(bitmap.iter().any(|&coverage| coverage > 0));
+            assert!(font.outline(font.lookup_glyph_index(character), 20.0).is_some());
+        }
+    }
+}
+
+#[test]
+fn zero_units_per_em_is_an_error() {
+    let mut data = FONTS[0].to_vec();
+    let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let record = (0..count).map(|index| 12 + index * 16).find(|&record| &data[record..record + 4] == b"head");
+    let record = record.unwrap();
+    let head = u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()) as usize;
+    // unitsPerEm is at offset 18 in the head table.
+    data[head + 18..head + 20].copy_from_slice(&0u16.to_be_bytes());
+    let error = "The head table is missing or malformed, or its units per em is out of range.";
+    assert_eq!(Font::from_bytes(data.as_slice(), FontSettings::default()).err(), Some(error));
+    assert_eq!(Font::from_bytes_lazy(data.as_slice(), FontSettings::default()).err(), Some(error));
+}
+
+#[test]
+fn rasterize_atlas_packs_glyphs() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let mut requested: Vec<(u16, f32)> = ('!'..='~').map(|c| (font.lookup_glyph_index(c), 20.0)).collect();
+    requested.push((font.lookup_glyph_index('W'), 48.0));
+    requested.push((font.lookup_glyph_index('a'), 20.0));
+    let atlas = font.rasterize_atlas(&requested, 1);
+    assert_eq!(atlas.glyphs.len(), requested.len() - 1);
+    assert!(atlas.width.is_power_of_two());
+    assert_eq!(atlas.bitmap.len(), atlas.width * atlas.height);
+
+    let mut rects = Vec::new();
+    for &(index, px) in &requested {
+        let glyph = atlas.get(index, px).unwrap();
+        let (metrics, bitmap) = font.rasterize_indexed(index, px);
+        assert_eq!(glyph.metrics, metrics);
+        let rect = glyph.rect;
+        assert_eq!((rect.width, rect.height), (metrics.width, metrics.height));
+        assert!(rect.x >= 1 && rect.y >= 1);
+        assert!(rect.x + rect.width < atlas.width && rect.y + rect.height < atlas.height);
+        for row in 0..rect.height {
+            let start = (rect.y + row) * atlas.width + rect.x;
+            assert_eq!(atlas.bitmap[start..start + rect.width], bitmap[row * rect.width..][..rect.width]);
+        }
+        rects.push(rect);
+    }
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            if a == b {
+                continue;
+            }
+            let apart =
+                a.x + a.width < b.x || b.x + b.width < a.x || a.y + a.height < b.y || b.y + b.height < a.y;
+            assert!(apart, "{:?} and {:?} are not padded apart", a, b);
+        }
+    }
+    assert!(font.rasterize_atlas(&[], 1).bitmap.is_empty());
+}
+
+#[test]
+fn bounding_box_contains_every_glyph() {
+    let synthetic = FontSettings::builder().synthetic_bold(0.02).synthetic_oblique(0.2).build();
+    for settings in [FontSettings::default(), synthetic] {
+        let font = Font::from_bytes(FONTS[0], settings).unwrap();
+        let (xmin, ymin, xmax, ymax) = font.bounding_box(20.0);
+        assert!(xmin < xmax && ymin < 0.0 && ymax > 0.0);
+        for index in 0..font.glyph_count() {
+            let bounds = font.metrics_indexed(index, 20.0).bounds;
+            if bounds.width == 0.0 {
+                continue;
+            }
+            assert!(bounds.xmin >= xmin - 0.01 && bounds.ymin >= ymin - 0.01);
+            assert!(bounds.xmin + bounds.width <= xmax + 0.01 && bounds.ymin + bounds.height <= ymax + 0.01);
+        }
+    }
+}
+
+#[test]
+fn glyph_to_char_reverses_the_character_map() {
+    let mut font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    for (&character, &index) in font.chars() {
+        let reverse = font.glyph_to_char(index.get()).unwrap();
+        assert_eq!(font.lookup_glyph_index(reverse), index.get());
+        assert!(reverse <= character);
+    }
+    assert_eq!(font.glyph_to_char(0), None);
+    assert_eq!(font.glyph_to_char(font.lookup_glyph_index('a')), Some('a'));
+
+    let index = font.lookup_glyph_index('b');
+    font.override_glyph('b', 0).unwrap();
+    assert_eq!(font.glyph_to_char(index), None);
+}
+
+/// Builds a TrueType collection from the given fonts. Table offsets in a collection are from the
+/// start of the file, so each font's table records are moved past the fonts before it.
+fn build_collection(fonts: &[&[u8]]) -> Vec<u8> {
+    let mut data = b"ttcf".to_vec();
+    data.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    data.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+    let mut base = 12 + 4 * fonts.len();
+    for font in fonts {
+        data.extend_from_slice(&(base as u32).to_be_bytes());
+        base += font.len();
+    }
+    for font in fonts {
+        let base = data.len() as u32;
+        let mut font = font.to_vec();
+        let count = u16::from_be_bytes([font[4], font[5]]) as usize;
+        for record in (0..count).map(|index| 12 + index * 16) {
+            let offset = u32::from_be_bytes(font[record + 8..record + 12].try_into().unwrap());
+            font[record + 8..record + 12].copy_from_slice(&(offset + base).to_be_bytes());
+        }
+        data.extend_from_slice(&font);
+    }
+    data
+}
+
+#[test]
+fn from_collection_loads_every_face() {
+    let collection = build_collection(&[FONTS[0], FONTS[1]]);
+    assert_eq!(collection_face_count(&collection), Ok(2));
+    assert_eq!(collection_face_count(FONTS[0]), Ok(1));
+    assert!(collection_face_count(&[0; 16]).is_err());
+
+    let faces = Font::from_collection(collection.as_slice(), FontSettings::default()).unwrap();
+    assert_eq!(faces.len(), 2);
+    for (face, data) in faces.iter().zip([FONTS[0], FONTS[1]]) {
+        let single = Font::from_bytes(data, FontSettings::default()).unwrap();
+        assert_eq!(face.name(), single.name());
+        assert_eq!(face.rasterize('g', 20.0), single.rasterize('g', 20.0));
+    }
+    assert_ne!(faces[0].file_hash(), faces[1].file_hash());
+    assert_eq!(Font::from_collection(FONTS[0], FontSettings::default()).unwrap().len(), 1);
+}
+
+#[test]
+fn coverage_floor_drops_faint_pixels() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let floored = Font::from_bytes(FONTS[0], FontSettings::builder().coverage_floor(64).build()).unwrap();
+    assert_ne!(font.file_hash(), floored.file_hash());
+    let (metrics, bitmap) = font.rasterize('e', 9.0);
+    let (floored_metrics, floored_bitmap) = floored.rasterize('e', 9.0);
+    assert_eq!(metrics, floored_metrics);
+    assert!(bitmap.iter().any(|&coverage| coverage > 0 && coverage < 64));
+    for (&coverage, &floored) in bitmap.iter().zip(&floored_bitmap) {
+        assert_eq!(
+            floored,
+            if coverage < 64 {
+                0
+            } else {
+                coverage
+            }
+        );
+    }
+    let (_, subpixel) = floored.rasterize_subpixel('e', 9.0);
+    assert!(subpixel.iter().all(|&coverage| coverage == 0 || coverage >= 64));
+}
+
+#[test]
+fn side_bearings_match_bounds() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    for character in ['A', 'j', 'f', ' '] {
+        let metrics = font.metrics(character, 20.0);
+        assert_eq!(metrics.left_side_bearing, metrics.bounds.xmin);
+        let right = metrics.advance_width - metrics.bounds.xmin - metrics.bounds.width;
+        assert!((metrics.right_side_bearing - right).abs() < 0.001);
+    }
+    assert!(font.metrics('j', 20.0).left_side_bearing < 0.0);
+    assert_eq!(font.metrics(' ', 20.0).right_side_bearing, font.metrics(' ', 20.0).advance_width);
+
+    // Bearings stay relative to the pen when glyphs are rasterized at a subpixel offset.
+    let index = font.lookup_glyph_index('A');
+    let (offset, _) = font.rasterize_subpixel_offset(index, 20.0, 0.5, 0.0);
+    let metrics = font.metrics('A', 20.0);
+    assert_eq!(
+        (offset.left_side_bearing, offset.right_side_bearing),
+        (metrics.left_side_bearing, metrics.right_side_bearing)
+    );
+}
+
+#[test]
+fn device_advance_reads_hdmx() {
+    // Roboto has a single hdmx record, at 9 pixels per em.
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    assert_eq!(font.device_advance(0, 9), Some(4));
+    let index = font.lookup_glyph_index('a');
+    let advance = font.device_advance(index, 9).unwrap();
+    assert!((advance as f32 - font.metrics_indexed(index, 9.0).advance_width).abs() <= 1.0);
+    assert_eq!(font.device_advance(index, 10), None);
+    assert_eq!(font.device_advance(index, 1000), None);
+    assert_eq!(font.device_advance(font.glyph_count(), 9), None);
+
+    // Exo 2 doesn't have an hdmx table.
+    let font = Font::from_bytes(FONTS[5], FontSettings::builder().keep_data(true).build()).unwrap();
+    assert_eq!(font.device_advance(font.lookup_glyph_index('a'), 9), None);
+}
+
+#[test]
+fn rasterize_indexed_rotated_turns_glyph() {
+    use core::f32::consts::{FRAC_PI_2, PI};
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let index = font.lookup_glyph_index('l');
+    let upright = font.rasterize_indexed(index, 40.0);
+    let (metrics, bitmap) = font.rasterize_indexed_rotated(index, 40.0, 0.0);
+    assert_eq!((metrics, bitmap), upright);
+
+    // A quarter turn counterclockwise lays the tall glyph on its side, to the left of the origin.
+    let (metrics, bitmap) = font.rasterize_indexed_rotated(index, 40.0, FRAC_PI_2);
+    assert!(metrics.width > metrics.height * 3);
+    assert!(metrics.xmin < 0 && metrics.bounds.xmin + metrics.bounds.width <= 0.5);
+    assert_eq!(bitmap.len(), metrics.width * metrics.height);
+    assert_eq!(metrics.advance_width, upright.0.advance_width);
+    let upright_coverage: u32 = upright.1.iter().map(|&c| c as u32).sum();
+    let coverage: u32 = bitmap.iter().map(|&c| c as u32).sum();
+    assert!((coverage as f32 / upright_coverage as f32 - 1.0).abs() < 0.02);
+
+    // A full turn is upright again.
+    let (metrics, _) = font.rasterize_indexed_rotated(index, 40.0, 2.0 * PI);
+    assert!((metrics.bounds.xmin - upright.0.bounds.xmin).abs() < 0.01);
+    assert!((metrics.bounds.width - upright.0.bounds.width).abs() < 0.01);
+    assert!((metrics.bounds.height - upright.0.bounds.height).abs() < 0.01);
+
+    // Every angle stays within the bitmap, including ones that round lines onto the axes.
+    for character in ['a', 'g', 'W', '@'] {
+        let index = font.lookup_glyph_index(character);
+        for step in 0..64 {
+            let (metrics, bitmap) = font.rasterize_indexed_rotated(index, 17.0, step as f32 * PI / 32.0);
+            assert_eq!(bitmap.len(), metrics.width * metrics.height);
+        }
+    }
+
+    // Blank glyphs keep their advance.
+    let space = font.lookup_glyph_index(' ');
+    let (metrics, bitmap) = font.rasterize_indexed_rotated(space, 40.0, 1.0);
+    assert!(bitmap.is_empty() && metrics.advance_width > 0.0);
+}
+
+#[test]
+fn metrics_origin_places_bitmaps() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    for character in ['A', 'g', '-'] {
+        let index = font.lookup_glyph_index(character);
+        let metrics = font.metrics_indexed(index, 20.0);
+        let (dst_x, dst_y, _, _, _) = font.rasterize_at_pen(index, 20.0, 30.0, 40.0);
+        assert_eq!(metrics.origin(30.0, 40.0, CoordinateSystem::PositiveYDown), (dst_x as f32, dst_y as f32));
+        let top = 40.0 + metrics.ymin as f32 + metrics.height as f32;
+        assert_eq!(metrics.origin(30.0, 40.0, CoordinateSystem::PositiveYUp), (dst_x as f32, top));
+    }
+}
+
+#[test]
+fn fonts_without_cmap_work_by_index() {
+    // Renaming the cmap table hides it, and keeps the table directory sorted.
+    let mut data = FONTS[0].to_vec();
+    let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let record = (0..count).map(|index| 12 + index * 16).find(|&record| &data[record..record + 4] == b"cmap");
+    data[record.unwrap() + 3] = b'q';
+
+    // Without the setting, the font still loads, but only the glyphs it can reach have geometry.
+    let unmapped = Font::from_bytes(data.as_slice(), FontSettings::default()).unwrap();
+    let settings = FontSettings::builder().allow_no_cmap(true).build();
+    let stripped = Font::from_bytes(data.as_slice(), settings.clone()).unwrap();
+    let lazy = Font::from_bytes_lazy(data.as_slice(), settings).unwrap();
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert!(stripped.chars().is_empty());
+    assert_eq!(stripped.glyph_count(), font.glyph_count());
+    assert_eq!(stripped.lookup_glyph_index('a'), 0);
+    assert_eq!(stripped.rasterize('a', 20.0), stripped.rasterize_indexed(0, 20.0));
+
+    // Every glyph is loaded, including ones the original font's character map reaches.
+    let a = font.lookup_glyph_index('a');
+    let v = font.lookup_glyph_index('V');
+    assert_eq!(stripped.rasterize_indexed(a, 20.0), font.rasterize_indexed(a, 20.0));
+    assert!(unmapped.rasterize_indexed(a, 20.0).1.is_empty());
+    assert_ne!(unmapped.file_hash(), stripped.file_hash());
+    assert_eq!(lazy.rasterize_indexed(a, 20.0), font.rasterize_indexed(a, 20.0));
+    assert_eq!(stripped.metrics_indexed(v, 20.0), font.metrics_indexed(v, 20.0));
+    assert_eq!(stripped.horizontal_kern_indexed(a, v, 20.0), font.horizontal_kern_indexed(a, v, 20.0));
+}
+
+#[test]
+fn set_default_glyph_replaces_missing_glyph() {
+    let mut font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(font.default_glyph(), 0);
+    let question = font.lookup_glyph_index('?');
+    font.set_default_glyph(question).unwrap();
+    assert_eq!(font.default_glyph(), question);
+    assert_eq!(font.lookup_glyph_index('\u{E000}'), question);
+    assert_eq!(font.rasterize('\u{E000}', 20.0), font.rasterize('?', 20.0));
+    assert!(!font.has_glyph('\u{E000}'));
+    assert_eq!(font.try_rasterize('\u{E000}', 20.0), None);
+    assert!(font.set_default_glyph(font.glyph_count()).is_err());
+    assert_eq!(font.default_glyph(), question);
+
+    let restored = Font::deserialize(&font.serialize()).unwrap();
+    assert_eq!(restored.lookup_glyph_index('\u{E000}'), question);
+
+    // Glyphs the character map can't reach are generated when they're set.
+    // Fonts that don't keep their data can't generate them.
+    let settings = FontSettings::builder().load_substitutions(false).build();
+    let lazy = Font::from_bytes_lazy(FONTS[0], settings.clone()).unwrap();
+    let unmapped = (1..lazy.glyph_count())
+        .find(|&index| lazy.glyph_to_char(index).is_none() && lazy.outline(index, 20.0).is_some())
+        .unwrap();
+    let mut font = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert!(font.set_default_glyph(unmapped).is_err());
+    assert_eq!(font.lookup_glyph_index('\u{E000}'), 0);
+    let settings = FontSettings::builder().load_substitutions(false).keep_data(true).build();
+    let mut font = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert!(font.rasterize_indexed(unmapped, 20.0).1.is_empty());
+    font.set_default_glyph(unmapped).unwrap();
+    assert!(!font.rasterize('\u{E000}', 20.0).1.is_empty());
+    assert_eq!(font.rasterize('\u{E000}', 20.0), lazy.rasterize_indexed(unmapped, 20.0));
+}
+
+#[test]
+fn has_outline_detects_blank_glyphs() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert!(font.has_outline(font.lookup_glyph_index('a')));
+    assert!(!font.has_outline(font.lookup_glyph_index(' ')));
+    assert!(!font.has_outline(font.glyph_count()));
+    // No-break and em spaces aren't ASCII whitespace, but are still blank.
+    for character in ['\u{A0}', '\u{2003}'] {
+        let index = font.lookup_glyph_index(character);
+        assert_ne!(index, 0);
+        assert!(!font.has_outline(index));
+        assert!(font.rasterize_indexed(index, 20.0).1.is_empty());
+    }
+}
+
+#[test]
+fn rasterize_line_composites_glyphs() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let line_metrics = font.horizontal_line_metrics(20.0).unwrap();
+    let baseline = line_metrics.ascent.ceil();
+
+    // A single glyph is copied into place below the baseline.
+    let (width, height, line) = font.rasterize_line("A", 20.0);
+    let (metrics, bitmap) = font.rasterize('A', 20.0);
+    assert_eq!(width, (font.advance_width('A', 20.0).ceil() as usize).max(metrics.width));
+    assert_eq!(height, (baseline - line_metrics.descent.floor()) as usize);
+    let top = (baseline as i32 - metrics.ymin - metrics.height as i32) as usize;
+    for y in 0..height {
+        for x in 0..width {
+            let inside = (metrics.xmin as usize..metrics.xmin as usize + metrics.width).contains(&x)
+                && (top..top + metrics.height).contains(&y);
+            let expected = if inside {
+                bitmap[(y - top) * metrics.width + x - metrics.xmin as usize]
+            } else {
+                0
+            };
+            assert_eq!(line[y * width + x], expected);
+        }
+    }
+
+    // Kerned glyphs overlap, and keep the greater coverage where they do.
+    let (width, height, line) = font.rasterize_line("AV", 20.0);
+    assert_eq!(line.len(), width * height);
+    assert!(width as f32 >= font.measure("AV", 20.0).0);
+    let total = |bitmap: &[u8]| bitmap.iter().map(|&c| c as u32).sum::<u32>();
+    let separate = total(&bitmap) + total(&font.rasterize('V', 20.0).1);
+    assert!(total(&line) <= separate && total(&line) > separate * 9 / 10);
+    assert_eq!(font.rasterize_line("", 20.0).2, Vec::<u8>::new());
+}
+
+#[test]
+fn antialias_off_thresholds_coverage() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let settings = FontSettings::builder().antialias(false).build();
+    let aliased = Font::from_bytes(FONTS[0], settings.clone()).unwrap();
+    assert_ne!(font.file_hash(), aliased.file_hash());
+    let (metrics, bitmap) = font.rasterize('e', 12.0);
+    let (aliased_metrics, aliased_bitmap) = aliased.rasterize('e', 12.0);
+    assert_eq!(metrics, aliased_metrics);
+    for (&coverage, &aliased) in bitmap.iter().zip(&aliased_bitmap) {
+        assert_eq!(
+            aliased,
+            if coverage >= 128 {
+                255
+            } else {
+                0
+            }
+        );
+    }
+    let (_, subpixel) = aliased.rasterize_subpixel('e', 12.0);
+    assert!(subpixel.iter().all(|&coverage| coverage == 0 || coverage == 255));
+
+    let restored = Font::deserialize(&aliased.serialize()).unwrap();
+    assert_eq!(restored.rasterize('e', 12.0).1, aliased_bitmap);
+}
+
+#[test]
+fn pixel_center_offsets_by_half_a_pixel() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let settings = FontSettings::builder().pixel_center(true).build();
+    let centered = Font::from_bytes(FONTS[0], settings).unwrap();
+    assert_ne!(font.file_hash(), centered.file_hash());
+    let index = font.lookup_glyph_index('e');
+    let (metrics, bitmap) = font.rasterize_subpixel_offset(index, 12.0, 0.5, 0.5);
+    let (centered_metrics, centered_bitmap) = centered.rasterize_indexed(index, 12.0);
+    assert_eq!(metrics, centered_metrics);
+    assert_eq!(bitmap, centered_bitmap);
+    assert_eq!(centered_bitmap.len(), centered_metrics.width * centered_metrics.height);
+
+    let restored = Font::deserialize(&centered.serialize()).unwrap();
+    assert_eq!(restored.rasterize_indexed(index, 12.0).1, centered_bitmap);
+}
+
+#[test]
+fn flattening_segments_follow_the_optimized_scale() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let detailed = Font::from_bytes(FONTS[0], FontSettings::builder().scale(200.0).build()).unwrap();
+    assert_eq!(font.optimized_scale(), 40.0);
+    assert_eq!(detailed.optimized_scale(), 200.0);
+    let index = font.lookup_glyph_index('o');
+    assert!(font.flattening_segments(index) > 0);
+    assert!(detailed.flattening_segments(index) > font.flattening_segments(index));
+    assert_eq!(font.flattening_segments(font.lookup_glyph_index(' ')), 0);
+    assert_eq!(font.flattening_segments(font.glyph_count()), 0);
+}
+
+#[test]
+fn total_kern_sums_adjacent_pairs() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    assert_eq!(font.total_kern("", 20.0), 0.0);
+    assert_eq!(font.total_kern("A", 20.0), 0.0);
+    let text = "AVATAR To";
+    let chars: Vec<char> = text.chars().collect();
+    let expected: f32 =
+        chars.windows(2).map(|pair| font.horizontal_kern(pair[0], pair[1], 20.0).unwrap_or(0.0)).sum();
+    assert!(expected < 0.0);
+    assert!((font.total_kern(text, 20.0) - expected).abs() < 0.001);
+    let advances: f32 = chars.iter().map(|&c| font.advance_width(c, 20.0)).sum();
+    assert!((font.measure(text, 20.0).0 - advances - expected).abs() < 0.001);
+}
+
+#[test]
+fn kerning_source_selects_tables() {
+    let with = |bytes: &[u8], kerning_source| {
+        let settings = FontSettings::builder().kerning_source(kerning_source).build();
+        Font::from_bytes(bytes, settings).unwrap()
+    };
+    // Roboto only kerns through GPOS, and Great Vibes only through the kern table.
+    let gpos = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let kern = Font::from_bytes(FONTS[6], FontSettings::default()).unwrap();
+    let gpos_pair = gpos.horizontal_kern('A', 'V', 32.0);
+    let kern_pair = kern.horizontal_kern('P', 'o', 32.0);
+    assert!(gpos_pair.is_some());
+    assert!(kern_pair.is_some());
+
+    assert_eq!(with(FONTS[0], KerningSource::KernOnly).horizontal_kern('A', 'V', 32.0), None);
+    assert_eq!(with(FONTS[0], KerningSource::GposOnly).horizontal_kern('A', 'V', 32.0), gpos_pair);
+    assert_eq!(with(FONTS[6], KerningSource::GposOnly).horizontal_kern('P', 'o', 32.0), None);
+    assert_eq!(with(FONTS[6], KerningSource::KernOnly).horizontal_kern('P', 'o', 32.0), kern_pair);
+    let prefer_kern = KerningSource::Prefer(KerningTable::Kern);
+    assert_eq!(with(FONTS[0], prefer_kern).horizontal_kern('A', 'V', 32.0), gpos_pair);
+    assert_eq!(with(FONTS[6], prefer_kern).horizontal_kern('P', 'o', 32.0), kern_pair);
+
+    let kern_only = with(FONTS[0], KerningSource::KernOnly);
+    let restored = Font::deserialize(&kern_only.serialize()).unwrap();
+    assert_eq!(restored.horizontal_kern('A', 'V', 32.0), None);
+
+    // Kerning and shaping caches keyed on the hash need it to differ between sources.
+    let default = with(FONTS[0], KerningSource::default()).file_hash();
+    assert_eq!(gpos.file_hash(), default);
+    assert_ne!(kern_only.file_hash(), default);
+    assert_ne!(with(FONTS[0], prefer_kern).file_hash(), default);
+    assert_ne!(with(FONTS[0], KerningSource::GposOnly).file_hash(), default);
+    assert_ne!(with(FONTS[0], KerningSource::GposOnly).file_hash(), kern_only.file_hash());
+}
+
+#[test]
+fn metrics_batch_matches_metrics_indexed() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let indices: Vec<u16> = "Batched metrics, 1234!".chars().map(|c| font.lookup_glyph_index(c)).collect();
+    let expected: Vec<_> = indices.iter().map(|&index| font.metrics_indexed(index, 15.5)).collect();
+    assert_eq!(font.metrics_batch(&indices, 15.5), expected);
+    assert!(font.metrics_batch(&[], 15.5).is_empty());
+}
+
+#[test]
+fn font_settings_builder_sets_fields() {
+    assert_eq!(FontSettings::builder().build(), FontSettings::default());
+    let settings = FontSettings::builder().scale(20.0).collection_index(1).load_substitutions(false).build();
+    let mut expected = FontSettings::default();
+    expected.scale = 20.0;
+    expected.collection_index = 1;
+    expected.load_substitutions = false;
+    assert_eq!(settings, expected);
+}
+
+#[test]
+fn rasterize_indexed_packed_matches_aliased() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let aliased = Font::from_bytes(FONTS[0], FontSettings::builder().antialias(false).build()).unwrap();
+    for character in "Wg.".chars() {
+        let index = font.lookup_glyph_index(character);
+        let (metrics, stride, packed) = font.rasterize_indexed_packed(index, 13.0);
+        let (aliased_metrics, bitmap) = aliased.rasterize_indexed(index, 13.0);
+        assert_eq!(metrics, aliased_metrics);
+        assert_eq!(stride, (metrics.width + 7) / 8);
+        assert_eq!(packed.len(), stride * metrics.height);
+        for y in 0..metrics.height {
+            for x in 0..stride * 8 {
+                let bit = packed[y * stride + x / 8] & (0x80 >> (x % 8)) != 0;
+                let expected = x < metrics.width && bitmap[y * metrics.width + x] == 255;
+                assert_eq!(bit, expected, "{} at ({}, {})", character, x, y);
+            }
+        }
+    }
+    let (_, stride, packed) = font.rasterize_indexed_packed(font.lookup_glyph_index(' '), 13.0);
+    assert_eq!((stride, packed), (0, Vec::new()));
+}
+
+#[test]
+fn line_metrics_source_selects_table() {
+    let metrics = |source| {
+        let settings = FontSettings::builder().line_metrics_source(source).build();
+        let font = Font::from_bytes(FONTS[0], settings).unwrap();
+        let metrics = font.horizontal_line_metrics_unscaled().unwrap();
+        let restored = Font::deserialize(&font.serialize()).unwrap();
+        assert_eq!(restored.horizontal_line_metrics_unscaled(), Some(metrics));
+        (metrics.ascent, metrics.descent, metrics.line_gap, metrics.new_line_size)
+    };
+    assert_eq!(metrics(LineMetricsSource::Hhea), (1900.0, -500.0, 0.0, 2400.0));
+    assert_eq!(metrics(LineMetricsSource::Os2Typo), (1536.0, -512.0, 102.0, 2150.0));
+    assert_eq!(metrics(LineMetricsSource::Os2Win), (1946.0, -512.0, 0.0, 2458.0));
+
+    // Layouts depend on the line metrics, so layout caches keyed on the hash need it to differ.
+    let hash = |source| {
+        let settings = FontSettings::builder().line_metrics_source(source).build();
+        Font::from_bytes(FONTS[0], settings).unwrap().file_hash()
+    };
+    let default = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap().file_hash();
+    assert_eq!(hash(LineMetricsSource::Hhea), default);
+    assert_ne!(hash(LineMetricsSource::Os2Typo), default);
+    assert_ne!(hash(LineMetricsSource::Os2Win), default);
+    assert_ne!(hash(LineMetricsSource::Os2Typo), hash(LineMetricsSource::Os2Win));
+}
+
+#[test]
+fn rasterize_indexed_trimmed_crops_blank_edges() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    for character in "Wg.-|o".chars() {
+        let index = font.lookup_glyph_index(character);
+        let (metrics, bitmap) = font.rasterize_indexed(index, 11.0);
+        let (trimmed, trimmed_bitmap) = font.rasterize_indexed_trimmed(index, 11.0);
+        assert_eq!(trimmed_bitmap.len(), trimmed.width * trimmed.height);
+        let row = |y: usize| &trimmed_bitmap[y * trimmed.width..(y + 1) * trimmed.width];
+        let inked_column = |x: usize| (0..trimmed.height).any(|y| row(y)[x] != 0);
+        assert!(row(0).iter().any(|&c| c != 0));
+        assert!(row(trimmed.height - 1).iter().any(|&c| c != 0));
+        assert!(inked_column(0));
+        assert!(inked_column(trimmed.width - 1));
+        // Every pixel matches the same pixel of the untrimmed bitmap.
+        let top = (metrics.ymin + metrics.height as i32) - (trimmed.ymin + trimmed.height as i32);
+        for y in 0..trimmed.height {
+            for x in 0..trimmed.width {
+                let source_x = (trimmed.xmin - metrics.xmin) as usize + x;
+                let source_y = top as usize + y;
+                assert_eq!(row(y)[x], bitmap[source_y * metrics.width + source_x]);
+            }
+        }
+        assert_eq!(
+            bitmap.iter().map(|&c| c as u32).sum::<u32>(),
+            trimmed_bitmap.iter().map(|&c| c as u32).sum()
+        );
+    }
+    let (blank, bitmap) = font.rasterize_indexed_trimmed(font.lookup_glyph_index(' '), 11.0);
+    assert_eq!((blank.width, blank.height, bitmap), (0, 0, Vec::new()));
+}
+
+#[test]
+fn grid_fit_snaps_glyph_bottoms() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let fitted = Font::from_bytes(
+        FONTS[0],
+        FontSettings::builder().keep_data(true).grid_fit(GridFit::Vertical).build(),
+    )
+    .unwrap();
+    assert_ne!(font.file_hash(), fitted.file_hash());
+    for character in "xgHo_".chars() {
+        let index = font.lookup_glyph_index(character);
+        let ymin = font.metrics_indexed(index, 13.0).bounds.ymin;
+        let (metrics, bitmap) = fitted.rasterize_indexed(index, 13.0);
+        assert_eq!(metrics.bounds.ymin, metrics.bounds.ymin.round());
+        assert!((metrics.bounds.ymin - ymin).abs() <= 0.5);
+        let expected = font.rasterize_subpixel_offset(index, 13.0, 0.0, ymin.round() - ymin);
+        assert_eq!((metrics, bitmap), expected);
+    }
+
+    // Distance fields move with the coverage.
+    let index = font.lookup_glyph_index('o');
+    let (metrics, coverage) = fitted.rasterize_indexed(index, 13.0);
+    let (sdf_metrics, field) = fitted.rasterize_sdf(index, 13.0, 2.0);
+    for y in 0..metrics.height {
+        for x in 0..metrics.width {
+            let (c, d) = (coverage[y * metrics.width + x], field[(y + 2) * sdf_metrics.width + x + 2]);
+            assert!(c != 255 || d >= 128);
+            assert!(c != 0 || d <= 128);
+        }
+    }
+    let restored = Font::deserialize(&fitted.serialize()).unwrap();
+    assert_eq!(restored.rasterize_indexed(index, 13.0).1, coverage);
+}
+
+#[test]
+fn cmap_encodings_lists_subtables() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let encodings: Vec<(u16, u16)> =
+        font.cmap_encodings().iter().map(|encoding| (encoding.platform_id, encoding.encoding_id)).collect();
+    assert_eq!(encodings, [(0, 3), (3, 1), (3, 10)]);
+    assert!(!font.cmap_encodings().iter().any(CmapEncoding::is_symbol));
+    assert!(CmapEncoding {
+        platform_id: 3,
+        encoding_id: 0
+    }
+    .is_symbol());
+}
+
+#[test]
+fn symbol_cmaps_map_the_private_use_area() {
+    // Relabeling every character map subtable as Windows Symbol makes a symbol font.
+    let mut data = FONTS[0].to_vec();
+    let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let record = (0..count).map(|index| 12 + index * 16).find(|&record| &data[record..record + 4] == b"cmap");
+    let record = record.unwrap();
+    let cmap = u32::from_be_bytes([data[record + 8], data[record + 9], data[record + 10], data[record + 11]]);
+    let cmap = cmap as usize;
+    let subtables = u16::from_be_bytes([data[cmap + 2], data[cmap + 3]]) as usize;
+    for subtable in 0..subtables {
+        let encoding = cmap + 4 + subtable * 8;
+        data[encoding..encoding + 4].copy_from_slice(&[0, 3, 0, 0]);
+    }
+
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let symbol = Font::from_bytes(data.as_slice(), FontSettings::default()).unwrap();
+    assert!(symbol.cmap_encodings().iter().all(CmapEncoding::is_symbol));
+    let a = font.lookup_glyph_index('a');
+    assert_eq!(symbol.lookup_glyph_index('a'), a);
+    assert_eq!(symbol.lookup_glyph_index('\u{F061}'), a);
+    assert_eq!(font.lookup_glyph_index('\u{F061}'), 0);
+    // Codepoints outside of the first 256 keep their mappings.
+    let euro = font.lookup_glyph_index('\u{20AC}');
+    assert_eq!(symbol.lookup_glyph_index('\u{20AC}'), euro);
+}
+
+#[test]
+fn sorted_chars_orders_by_codepoint() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let sorted = font.sorted_chars();
+    assert_eq!(sorted.len(), font.chars().len());
+    assert!(sorted.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    for &(character, index) in &sorted {
+        assert_eq!(font.chars()[&character].get(), index);
+    }
+    assert_eq!(sorted, Font::deserialize(&font.serialize()).unwrap().sorted_chars());
+}
+
+#[test]
+fn subpixel_layout_orders_and_filters() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::default()).unwrap();
+    let index = font.lookup_glyph_index('W');
+    let (metrics, rgb) = font.rasterize_indexed_subpixel(index, 16.0);
+    assert_eq!(
+        font.rasterize_indexed_subpixel_layout(index, 16.0, SubpixelLayout::Rgb, false),
+        (metrics, rgb.clone())
+    );
+    let (_, bgr) = font.rasterize_indexed_subpixel_layout(index, 16.0, SubpixelLayout::Bgr, false);
+    for (rgb, bgr) in rgb.chunks_exact(3).zip(bgr.chunks_exact(3)) {
+        assert_eq!(rgb, [bgr[2], bgr[1], bgr[0]]);
+    }
+
+    let (filtered_metrics, filtered) =
+        font.rasterize_indexed_subpixel_layout(index, 16.0, SubpixelLayout::Rgb, true);
+    assert_eq!((filtered_metrics.xmin, filtered_metrics.width), (metrics.xmin - 1, metrics.width + 2));
+    assert_eq!(filtered_metrics.height, metrics.height);
+    let row_length = metrics.width * 3;
+    let filtered_length = row_length + 6;
+    assert_eq!(filtered.len(), filtered_length * metrics.height);
+    for (row, filtered_row) in rgb.chunks_exact(row_length).zip(filtered.chunks_exact(filtered_length)) {
+        // The filtered row starts a pixel, three subpixels, left of the unfiltered row.
+        for x in 0..filtered_length {
+            let sample = |offset: isize| {
+                let x = x as isize - 3 + offset;
+                if x < 0 {
+                    0
+                } else {
+                    row.get(x as usize).map_or(0, |&c| c as u32)
+                }
+            };
+            let sum = sample(-2) + 2 * sample(-1) + 3 * sample(0) + 2 * sample(1) + sample(2);
+            assert_eq!(filtered_row[x] as u32, (sum + 4) / 9);
+        }
+        // No coverage is lost off the edges, beyond rounding.
+        let ink = |row: &[u8]| row.iter().map(|&c| c as i32).sum::<i32>();
+        assert!((ink(filtered_row) - ink(row)).abs() <= filtered_length as i32);
+    }
+
+    // Blank glyphs have no pixels, so the bitmap still matches the metrics.
+    let (metrics, bitmap) = font.rasterize_indexed_subpixel(font.lookup_glyph_index(' '), 16.0);
+    assert_eq!((metrics.width, metrics.height, bitmap.len()), (0, 0, 0));
+}
+
+#[test]
+fn max_bitmap_pixels_bounds_bitmaps() {
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    let index = font.lookup_glyph_index('W');
+    assert_eq!(font.rasterize_indexed(index, 1.0e6), (Metrics::default(), Vec::new()));
+    assert_eq!(font.rasterize_indexed_scaled(index, 1.0e6, 1.0e6), (Metrics::default(), Vec::new()));
+
+    let (metrics, _) = font.rasterize_indexed(index, 20.0);
+    let pixels = metrics.width * metrics.height;
+    let limited = |max_bitmap_pixels| {
+        let settings = FontSettings::builder().keep_data(true).max_bitmap_pixels(max_bitmap_pixels).build();
+        Font::from_bytes(FONTS[0], settings).unwrap()
+    };
+    assert_eq!(limited(pixels).rasterize_indexed(index, 20.0), font.rasterize_indexed(index, 20.0));
+    let font = limited(pixels - 1);
+    assert_eq!(font.rasterize_indexed(index, 20.0), (Metrics::default(), Vec::new()));
+    assert_eq!(font.rasterize_indexed_subpixel(index, 20.0), (Metrics::default(), Vec::new()));
+    assert_eq!(font.rasterize_stroked(index, 20.0, 1.0), (Metrics::default(), Vec::new()));
+    assert_eq!(font.rasterize_sdf(index, 20.0, 2.0), (Metrics::default(), Vec::new()));
+
+    // Bitmaps made of several glyphs are bounded too, even when each glyph fits.
+    let font = Font::from_bytes(FONTS[0], FontSettings::builder().keep_data(true).build()).unwrap();
+    assert_eq!(font.rasterize_line("W", 1.0e7), (0, 0, Vec::new()));
+    let atlas = font.rasterize_atlas(&[(index, 20.0)], usize::MAX / 4);
+    assert_eq!((atlas.width, atlas.height, atlas.bitmap.len(), atlas.glyphs.len()), (0, 0, 0, 0));
+    let (width, height, _) = font.rasterize_line("WW", 20.0);
+    let font = limited(width * height - 1);
+    assert!(!font.rasterize_indexed(index, 20.0).1.is_empty());
+    assert_eq!(font.rasterize_line("WW", 20.0), (0, 0, Vec::new()));
+    // Atlases are at least 64 pixels wide, wider than the glyph.
+    let font = limited(pixels);
+    assert!(!font.rasterize_indexed(index, 20.0).1.is_empty());
+    assert!(font.rasterize_atlas(&[(index, 20.0)], 0).glyphs.is_empty());
+}
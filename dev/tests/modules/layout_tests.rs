@@ -0,0 +1,849 @@
+use fontdue::layout::{
+    line_break_opportunities, mirror, ControlHandling, CoordinateSystem, GlyphCache, GlyphCacheKey,
+    GlyphPosition, HorizontalAlign, Layout, LayoutSettings, LineHeight, LinebreakKind, MissingGlyph,
+    TextDirection, TextStyle, VerticalAlign, WrapStyle, WritingMode,
+};
+use fontdue::{Font, FontSettings, FxBuildHasher, FxHasher, ShapedGlyph};
+
+use crate::modules::FONTS;
+
+fn roboto() -> Font {
+    Font::from_bytes(FONTS[0], FontSettings::default()).unwrap()
+}
+
+#[test]
+fn render_grayscale_single_glyph() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("g", 20.0, 0));
+    let (metrics, bitmap) = fonts[0].rasterize('g', 20.0);
+    assert_eq!(layout.render_grayscale(&fonts), (metrics.width, metrics.height, bitmap));
+}
+
+#[test]
+fn render_grayscale_matches_coordinate_systems() {
+    let fonts = [roboto()];
+    let settings = LayoutSettings::builder().max_width(Some(60.0)).build();
+    let mut down = Layout::new(CoordinateSystem::PositiveYDown);
+    let mut up = Layout::new(CoordinateSystem::PositiveYUp);
+    down.reset(&settings);
+    up.reset(&settings);
+    down.append(&fonts, &TextStyle::new("Hello world, wrapped", 18.0, 0));
+    up.append(&fonts, &TextStyle::new("Hello world, wrapped", 18.0, 0));
+    let (width, height, image) = down.render_grayscale(&fonts);
+    assert!(down.lines().unwrap().len() > 1);
+    assert_eq!(image.len(), width * height);
+    assert!(image.iter().any(|&coverage| coverage > 0));
+    assert_eq!(up.render_grayscale(&fonts), (width, height, image));
+}
+
+#[test]
+fn render_grayscale_empty() {
+    let fonts = [roboto()];
+    let mut layout = Layout::<()>::new(CoordinateSystem::PositiveYDown);
+    assert_eq!(layout.render_grayscale(&fonts), (0, 0, Vec::new()));
+    layout.append(&fonts, &TextStyle::new("   ", 20.0, 0));
+    assert_eq!(layout.render_grayscale(&fonts), (0, 0, Vec::new()));
+}
+
+#[test]
+fn render_grayscale_bounds_image_size() {
+    let fonts = [roboto()];
+    let mut layout = Layout::<()>::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("W W", 1.0e7, 0));
+    assert_eq!(layout.render_grayscale(&fonts), (0, 0, Vec::new()));
+
+    // Each glyph fits, but the image of both doesn't.
+    layout.clear();
+    layout.append(&fonts, &TextStyle::new("WW", 20.0, 0));
+    let (width, height, _) = layout.render_grayscale(&fonts);
+    let settings = FontSettings::builder().max_bitmap_pixels(width * height - 1).build();
+    let limited = [Font::from_bytes(FONTS[0], settings).unwrap()];
+    assert_eq!(layout.render_grayscale(&limited), (0, 0, Vec::new()));
+}
+
+#[test]
+fn input_hash_tracks_inputs() {
+    let fonts = [roboto()];
+    let layout = Layout::<()>::new(CoordinateSystem::PositiveYDown);
+    let settings = LayoutSettings::default();
+    let style = TextStyle::new("Hello", 20.0, 0);
+    let hash = layout.input_hash(&fonts, &style, &settings);
+    assert_eq!(hash, layout.input_hash(&fonts, &TextStyle::new("Hello", 20.0, 0), &settings));
+    assert_ne!(hash, layout.input_hash(&fonts, &TextStyle::new("Hellp", 20.0, 0), &settings));
+    assert_ne!(hash, layout.input_hash(&fonts, &TextStyle::new("Hello", 21.0, 0), &settings));
+    let mut wrapped = settings;
+    wrapped.max_width = Some(100.0);
+    assert_ne!(hash, layout.input_hash(&fonts, &style, &wrapped));
+    let up = Layout::<()>::new(CoordinateSystem::PositiveYUp);
+    assert_ne!(hash, up.input_hash(&fonts, &style, &settings));
+    let mut overridden = roboto();
+    overridden.override_glyph('H', overridden.lookup_glyph_index('e')).unwrap();
+    assert_ne!(hash, layout.input_hash(&[overridden], &style, &settings));
+}
+
+#[test]
+fn selection_rects_per_line() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("Hello\nworld", 20.0, 0));
+    let lines = layout.lines().unwrap().clone();
+    assert_eq!(lines.len(), 2);
+    let hello: f32 = layout.glyphs()[..5].iter().map(|glyph| glyph.advance).sum();
+
+    let rects = layout.selection_rects(0, 5);
+    assert_eq!(rects, vec![(0.0, 0.0, hello, lines[0].max_new_line_size)]);
+
+    let rects = layout.selection_rects(3, 8);
+    assert_eq!(rects.len(), 2);
+    assert_eq!(rects[0].0, layout.glyphs()[3].pen_x);
+    assert_eq!(rects[1].0, 0.0);
+    assert_eq!(rects[1].1, rects[0].1 + rects[0].3);
+    assert!(layout.selection_rects(4, 4).is_empty());
+}
+
+#[test]
+fn right_to_left_mirrors_lines() {
+    let fonts = [roboto()];
+    let text = "abc def";
+    let mut ltr = Layout::new(CoordinateSystem::PositiveYDown);
+    ltr.append(&fonts, &TextStyle::new(text, 20.0, 0));
+    let mut rtl = Layout::new(CoordinateSystem::PositiveYDown);
+    rtl.reset(
+        &LayoutSettings::builder().max_width(Some(200.0)).direction(TextDirection::RightToLeft).build(),
+    );
+    rtl.append(&fonts, &TextStyle::new(text, 20.0, 0));
+
+    assert_eq!(ltr.glyphs().len(), rtl.glyphs().len());
+    for (l, r) in ltr.glyphs().iter().zip(rtl.glyphs()) {
+        assert_eq!(l.byte_offset, r.byte_offset);
+        assert_eq!(r.pen_x, 200.0 - l.pen_x - l.advance);
+        assert_eq!(r.x - r.pen_x, l.x - l.pen_x);
+    }
+}
+
+#[test]
+fn vertical_columns_stack_right_to_left() {
+    let fonts = [roboto()];
+    let metrics = fonts[0].horizontal_line_metrics(20.0).unwrap();
+    let cell = (metrics.ascent - metrics.descent).ceil();
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(
+        &LayoutSettings::builder()
+            .max_height(Some(cell * 2.0))
+            .writing_mode(WritingMode::VerticalRL)
+            .wrap_style(WrapStyle::Letter)
+            .build(),
+    );
+    layout.append(&fonts, &TextStyle::new("abcd", 20.0, 0));
+
+    let lines = layout.lines().unwrap();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].baseline_y > lines[1].baseline_y);
+    let glyphs = layout.glyphs();
+    let pens: Vec<f32> = glyphs.iter().map(|glyph| glyph.pen_x).collect();
+    assert_eq!(pens, vec![0.0, cell, 0.0, cell]);
+    for glyph in glyphs {
+        assert_eq!(glyph.advance, cell);
+        assert!(glyph.y >= glyph.pen_x);
+    }
+    assert!(glyphs[0].x > glyphs[2].x);
+}
+
+#[test]
+fn tabs_advance_to_tab_stops() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&LayoutSettings::builder().tab_width(40.0).build());
+    layout.append(&fonts, &TextStyle::new("a\tb\t\tc", 20.0, 0));
+    let pen_x: Vec<f32> = layout.glyphs().iter().map(|glyph| glyph.pen_x).collect();
+    assert_eq!(pen_x[2], 40.0);
+    assert_eq!(pen_x[4], 80.0);
+    assert_eq!(pen_x[5], 120.0);
+
+    // The second tab wraps, taking the word before it to the new line, and is measured from the
+    // start of that line.
+    layout.reset(&LayoutSettings::builder().max_width(Some(50.0)).tab_width(30.0).build());
+    layout.append(&fonts, &TextStyle::new("\tx\ty", 20.0, 0));
+    let glyphs = layout.glyphs();
+    assert_eq!(layout.lines().unwrap().len(), 2);
+    assert_eq!(glyphs[1].pen_x, 0.0);
+    assert_eq!(glyphs[3].pen_x, 30.0);
+    assert_eq!(glyphs[3].y, glyphs[1].y);
+
+    // Without a tab width, tabs keep the font's advance.
+    layout.reset(&LayoutSettings::default());
+    layout.append(&fonts, &TextStyle::new("a\tb", 20.0, 0));
+    assert_eq!(layout.glyphs()[2].pen_x, layout.glyphs()[1].pen_x);
+}
+
+#[test]
+fn letter_and_word_spacing() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("ab cd", 20.0, 0));
+    let plain: Vec<f32> = layout.glyphs().iter().map(|glyph| glyph.advance).collect();
+    let width: f32 = plain.iter().sum();
+
+    layout.reset(&LayoutSettings::builder().letter_spacing(2.0).word_spacing(5.0).build());
+    layout.append(&fonts, &TextStyle::new("ab cd", 20.0, 0));
+    let glyphs = layout.glyphs();
+    assert_eq!(glyphs[1].pen_x, plain[0] + 2.0);
+    assert_eq!(glyphs[2].advance, plain[2] + 7.0);
+    assert_eq!(glyphs[3].pen_x, plain[0] + plain[1] + plain[2] + 11.0);
+
+    // Negative spacing tightens the text enough to fit on one line.
+    let settings = LayoutSettings::builder().max_width(Some(width - 1.0)).build();
+    layout.reset(&settings);
+    layout.append(&fonts, &TextStyle::new("ab cd", 20.0, 0));
+    assert_eq!(layout.lines().unwrap().len(), 2);
+    let mut tightened = settings;
+    tightened.letter_spacing = -1.0;
+    layout.reset(&tightened);
+    layout.append(&fonts, &TextStyle::new("ab cd", 20.0, 0));
+    assert_eq!(layout.lines().unwrap().len(), 1);
+}
+
+#[test]
+fn fallback_fonts_fill_missing_characters() {
+    let inconsolata = Font::from_bytes(FONTS[3], FontSettings::default()).unwrap();
+    let fonts = [roboto(), inconsolata];
+    assert!(!fonts[0].has_glyph('Ǧ') && fonts[1].has_glyph('Ǧ'));
+
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("aǦb\u{E000}", 20.0, 0).with_fallbacks(&[1]));
+    let glyphs = layout.glyphs();
+    let used: Vec<usize> = glyphs.iter().map(|glyph| glyph.font_index).collect();
+    assert_eq!(used, vec![0, 1, 0, 0]);
+    assert_eq!(glyphs[1].key.glyph_index, fonts[1].lookup_glyph_index('Ǧ'));
+    assert_eq!(glyphs[1].key.font_hash, fonts[1].file_hash());
+    assert_eq!(glyphs[1].advance, fonts[1].metrics('Ǧ', 20.0).advance_width.ceil());
+    assert_eq!(glyphs[3].key.glyph_index, 0);
+
+    // The line grows to fit the fallback font's metrics.
+    let ascent = |font: &Font| font.horizontal_line_metrics(20.0).unwrap().ascent.ceil();
+    let max_ascent = layout.lines().unwrap()[0].max_ascent;
+    assert_eq!(max_ascent, ascent(&fonts[0]).max(ascent(&fonts[1])));
+
+    let style = TextStyle::new("aǦb", 20.0, 0);
+    let settings = LayoutSettings::default();
+    assert_ne!(
+        layout.input_hash(&fonts, &style, &settings),
+        layout.input_hash(&fonts, &style.with_fallbacks(&[1]), &settings)
+    );
+}
+
+#[test]
+fn combining_marks_join_their_base() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("e\u{301}x", 20.0, 0));
+    let glyphs = layout.glyphs();
+    assert_eq!(glyphs.len(), 3);
+    assert!(glyphs[1].char_data.is_mark() && glyphs[1].char_data.rasterize());
+    assert_eq!(glyphs[1].advance, 0.0);
+    assert_eq!(glyphs[1].byte_offset, 0);
+    assert_eq!(glyphs[2].byte_offset, 3);
+    assert_eq!(glyphs[2].pen_x, glyphs[0].advance);
+
+    // The mark is drawn over its base rather than after it.
+    assert!(glyphs[1].x < glyphs[0].x + glyphs[0].width as f32);
+    assert!(glyphs[1].x + glyphs[1].width as f32 > glyphs[0].x);
+}
+
+#[test]
+fn layout_batch_matches_append() {
+    let fonts = [roboto()];
+    let wrapped = LayoutSettings::builder().max_width(Some(50.0)).build();
+    let blocks = vec![
+        (
+            LayoutSettings::default(),
+            vec![TextStyle::new("Hello ", 20.0, 0), TextStyle::new("world", 14.0, 0)],
+        ),
+        (wrapped, vec![TextStyle::new("Some wrapped text", 16.0, 0)]),
+        (LayoutSettings::default(), vec![]),
+    ];
+    let batch = Layout::layout_batch(CoordinateSystem::PositiveYDown, &fonts, &blocks);
+    assert_eq!(batch.len(), blocks.len());
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    for ((settings, styles), glyphs) in blocks.iter().zip(&batch) {
+        layout.reset(settings);
+        for style in styles {
+            layout.append(&fonts, style);
+        }
+        let expected: Vec<(char, f32, f32)> = layout.glyphs().iter().map(|g| (g.parent, g.x, g.y)).collect();
+        let actual: Vec<(char, f32, f32)> = glyphs.iter().map(|g| (g.parent, g.x, g.y)).collect();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn subpixel_positioning_keeps_fractional_advances() {
+    let fonts = [roboto()];
+    let settings = LayoutSettings::builder().subpixel_positioning(true).build();
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&settings);
+    layout.append(&fonts, &TextStyle::new("ill", 17.0, 0));
+    let glyphs = layout.glyphs();
+    let advance = fonts[0].metrics('i', 17.0).advance_width;
+    assert_ne!(advance.fract(), 0.0);
+    assert_eq!(glyphs[0].advance, advance);
+    assert_eq!(glyphs[1].pen_x, advance);
+
+    // Rasterizing at the pen's fractional offset lines the bitmap up with the glyph's position.
+    let (metrics, _) =
+        fonts[0].rasterize_subpixel_offset(glyphs[1].key.glyph_index, 17.0, advance.fract(), 0.0);
+    assert_eq!(glyphs[1].x, glyphs[1].pen_x.floor() + metrics.xmin as f32);
+
+    layout.reset(&LayoutSettings::default());
+    layout.append(&fonts, &TextStyle::new("ill", 17.0, 0));
+    assert_eq!(layout.glyphs()[1].pen_x, advance.ceil());
+}
+
+#[test]
+fn line_break_opportunities_reports_breaks() {
+    use LinebreakKind::*;
+    assert_eq!(line_break_opportunities(""), vec![]);
+    assert_eq!(line_break_opportunities("word"), vec![]);
+    assert_eq!(line_break_opportunities("ab cd\nef"), vec![(3, Soft), (6, Hard)]);
+    assert_eq!(line_break_opportunities("é x\r\ny"), vec![(3, Soft), (6, Hard)]);
+}
+
+#[test]
+fn line_bounds_and_glyph_ranges() {
+    let fonts = [roboto()];
+    let settings = LayoutSettings::builder().max_width(Some(5.0)).build();
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&settings);
+    // Every glyph is wider than the max width, so each gets a line of its own.
+    layout.append(&fonts, &TextStyle::new("WW", 20.0, 0));
+    let ranges: Vec<(usize, usize)> =
+        layout.lines().unwrap().iter().map(|line| (line.glyph_start, line.glyph_end)).collect();
+    assert_eq!(ranges, vec![(0, 0), (1, 1)]);
+
+    for (index, glyph) in layout.glyphs().iter().enumerate() {
+        let (xmin, ymin, xmax, ymax) = layout.line_bounds(index).unwrap();
+        assert_eq!((xmin, ymin), (glyph.x, glyph.y));
+        assert_eq!((xmax, ymax), (glyph.x + glyph.width as f32, glyph.y + glyph.height as f32));
+    }
+    assert_eq!(layout.line_bounds(2), None);
+
+    layout.reset(&LayoutSettings::default());
+    layout.append(&fonts, &TextStyle::new("ag \n ", 20.0, 0));
+    let glyphs = layout.glyphs();
+    let (xmin, ymin, xmax, ymax) = layout.line_bounds(0).unwrap();
+    assert_eq!((xmin, xmax), (glyphs[0].x, glyphs[1].x + glyphs[1].width as f32));
+    assert_eq!(ymin, glyphs[0].y.min(glyphs[1].y));
+    assert_eq!(ymax, glyphs[1].y + glyphs[1].height as f32);
+    assert_eq!(layout.line_bounds(1), None);
+}
+
+#[test]
+fn control_handling_shows_control_characters() {
+    let fonts = [roboto()];
+    let text = "a\u{1}\nb";
+    let layout_with = |control_handling| {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(&LayoutSettings::builder().control_handling(control_handling).build());
+        layout.append(&fonts, &TextStyle::new(text, 20.0, 0));
+        let lines = layout.lines().unwrap().len();
+        let glyphs: Vec<(char, u16, usize)> = layout
+            .glyphs()
+            .iter()
+            .map(|glyph| (glyph.parent, glyph.key.glyph_index, glyph.byte_offset))
+            .collect();
+        (lines, glyphs)
+    };
+    let index = |character| fonts[0].lookup_glyph_index(character);
+
+    let (lines, ignored) = layout_with(ControlHandling::Ignore);
+    assert_eq!(lines, 2);
+    assert_eq!(ignored, vec![('a', index('a'), 0), ('\u{1}', 0, 1), ('\n', 0, 2), ('b', index('b'), 3)]);
+
+    let (lines, replaced) = layout_with(ControlHandling::Replace);
+    assert_eq!(lines, 2);
+    assert_ne!(index('\u{FFFD}'), 0);
+    assert_eq!(replaced[1], ('\u{1}', index('\u{FFFD}'), 1));
+    assert_eq!(replaced[2], ignored[2]);
+
+    let (lines, visible) = layout_with(ControlHandling::Visible);
+    assert_eq!(lines, 2);
+    assert_eq!(
+        visible,
+        vec![
+            ('a', index('a'), 0),
+            ('\u{1}', index('^'), 1),
+            ('\u{1}', index('A'), 1),
+            ('\n', index('^'), 2),
+            ('\n', index('J'), 2),
+            ('b', index('b'), 3)
+        ]
+    );
+}
+
+#[test]
+fn set_coordinate_system_applies_on_reset() {
+    let fonts = [roboto()];
+    let positions =
+        |layout: &Layout| layout.glyphs().iter().map(|glyph| (glyph.x, glyph.y)).collect::<Vec<_>>();
+    let lay_out = |layout: &mut Layout| {
+        layout.reset(&LayoutSettings::default());
+        layout.append(&fonts, &TextStyle::new("Hello\nworld", 20.0, 0));
+    };
+    let mut up = Layout::new(CoordinateSystem::PositiveYUp);
+    let mut down = Layout::new(CoordinateSystem::PositiveYDown);
+    lay_out(&mut up);
+    lay_out(&mut down);
+    assert_ne!(positions(&up), positions(&down));
+
+    let mut layout = Layout::new(CoordinateSystem::PositiveYUp);
+    lay_out(&mut layout);
+    layout.set_coordinate_system(CoordinateSystem::PositiveYDown);
+    assert_eq!(positions(&layout), positions(&up));
+    lay_out(&mut layout);
+    assert_eq!(positions(&layout), positions(&down));
+}
+
+#[test]
+fn missing_glyph_handling() {
+    let fonts = [roboto()];
+    let font = &fonts[0];
+    assert!(!font.has_glyph('\u{4E00}'));
+    let layout_with = |missing_glyph| {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(&LayoutSettings::builder().missing_glyph(missing_glyph).build());
+        layout.append(&fonts, &TextStyle::new("a\u{4E00}b", 20.0, 0));
+        layout.glyphs().clone()
+    };
+    let a_advance = font.metrics('a', 20.0).advance_width.ceil();
+
+    let tofu = layout_with(MissingGlyph::Tofu);
+    assert_eq!(tofu.len(), 3);
+    assert_eq!(tofu[1].key.glyph_index, 0);
+    assert!(tofu[1].width > 0 && tofu[1].char_data.is_missing());
+
+    let skip = layout_with(MissingGlyph::Skip);
+    assert_eq!(skip.iter().map(|glyph| glyph.parent).collect::<String>(), "ab");
+    assert_eq!(skip[1].pen_x, a_advance);
+    assert_eq!(skip[1].byte_offset, 4);
+
+    let space = layout_with(MissingGlyph::Space);
+    assert_eq!(space.len(), 3);
+    assert_eq!((space[1].width, space[1].height), (0, 0));
+    assert!(!space[1].char_data.rasterize());
+    assert_eq!(space[1].advance, font.metrics(' ', 20.0).advance_width.ceil());
+    assert_eq!(space[2].pen_x, a_advance + space[1].advance);
+}
+
+#[test]
+fn default_glyph_is_still_missing() {
+    let mut font = roboto();
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    let style = TextStyle::new("a\u{4E00}", 20.0, 0);
+    let settings = LayoutSettings::default();
+    let hash = layout.input_hash(&[&font], &style, &settings);
+    let question = font.lookup_glyph_index('?');
+    font.set_default_glyph(question).unwrap();
+    // Cached layouts of text with missing characters go stale when the default glyph changes.
+    assert_ne!(layout.input_hash(&[&font], &style, &settings), hash);
+    let fonts = [font];
+    layout.append(&fonts, &style);
+    let glyph = layout.glyphs()[1];
+    assert_eq!(glyph.key.glyph_index, question);
+    assert!(glyph.char_data.is_missing());
+    assert_eq!(glyph.width, fonts[0].metrics('?', 20.0).width);
+}
+
+#[test]
+fn bounds_cover_every_line() {
+    let fonts = [roboto()];
+    for coordinate_system in [CoordinateSystem::PositiveYUp, CoordinateSystem::PositiveYDown] {
+        let mut layout = Layout::new(coordinate_system);
+        assert_eq!(layout.bounds(), None);
+        layout.append(&fonts, &TextStyle::new("Hello\nlonger world\n ", 20.0, 0));
+        let (xmin, ymin, xmax, ymax) = layout.bounds().unwrap();
+        let lines = (0..layout.lines().unwrap().len()).filter_map(|index| layout.line_bounds(index));
+        let merged = lines.reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))).unwrap();
+        assert_eq!((xmin, ymin, xmax, ymax), merged);
+        assert!(xmin < xmax && ymin < ymax);
+        assert!(ymax - ymin > layout.lines().unwrap()[0].max_new_line_size);
+    }
+}
+
+#[test]
+fn append_returning_streams_lines() {
+    let fonts = [roboto()];
+    let lines: Vec<String> = (0..20).map(|index| format!("line {} of the streamed text\n", index)).collect();
+    let wrapped = LayoutSettings::builder().max_width(Some(120.0)).build();
+    let centered = LayoutSettings::builder()
+        .max_width(Some(300.0))
+        .max_height(Some(400.0))
+        .horizontal_align(HorizontalAlign::Center)
+        .vertical_align(VerticalAlign::Middle)
+        .build();
+    let right_to_left = LayoutSettings::builder().direction(TextDirection::RightToLeft).build();
+    let vertical = LayoutSettings::builder().writing_mode(WritingMode::VerticalRL).build();
+    let positions = |glyphs: &[GlyphPosition]| {
+        glyphs.iter().map(|glyph| (glyph.parent, glyph.x, glyph.y, glyph.pen_x)).collect::<Vec<_>>()
+    };
+    for settings in [LayoutSettings::default(), wrapped, centered, right_to_left, vertical] {
+        for coordinate_system in [CoordinateSystem::PositiveYUp, CoordinateSystem::PositiveYDown] {
+            let mut expected = Layout::new(coordinate_system);
+            expected.reset(&settings);
+            expected.append(&fonts, &TextStyle::new(&lines.concat(), 16.0, 0));
+
+            let mut layout = Layout::new(coordinate_system);
+            layout.reset(&settings);
+            for line in &lines {
+                let before = layout.glyphs().clone();
+                let changed = layout.append_returning(&fonts, &TextStyle::new(line, 16.0, 0)).len();
+                let glyphs = layout.glyphs();
+                let unchanged = glyphs.len() - changed;
+                assert!(changed >= line.chars().count() && unchanged <= before.len());
+                assert_eq!(positions(&glyphs[..unchanged]), positions(&before[..unchanged]));
+            }
+            assert_eq!(positions(layout.glyphs()), positions(expected.glyphs()));
+            assert_eq!(layout.height(), expected.height());
+        }
+    }
+
+    // Unaligned streams only reposition the new text and the last line before it.
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new(&lines[0], 16.0, 0));
+    layout.append(&fonts, &TextStyle::new(&lines[1], 16.0, 0));
+    let changed = layout.append_returning(&fonts, &TextStyle::new(&lines[2], 16.0, 0)).len();
+    assert_eq!(changed, lines[1].len() + lines[2].len());
+    assert!(layout.append_returning(&fonts, &TextStyle::new("", 16.0, 0)).is_empty());
+}
+
+#[test]
+fn right_to_left_mirrors_brackets() {
+    assert_eq!(mirror('('), ')');
+    assert_eq!(mirror(')'), '(');
+    assert_eq!(mirror('\u{AB}'), '\u{BB}');
+    assert_eq!(mirror('\u{FF62}'), '\u{FF63}');
+    assert_eq!(mirror('a'), 'a');
+
+    let fonts = [roboto()];
+    let font = &fonts[0];
+    for (direction, open, close) in
+        [(TextDirection::LeftToRight, '(', ')'), (TextDirection::RightToLeft, ')', '(')]
+    {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(&LayoutSettings::builder().direction(direction).build());
+        layout.append(&fonts, &TextStyle::new("(a)", 20.0, 0));
+        let glyphs = layout.glyphs();
+        assert_eq!(glyphs.iter().map(|glyph| glyph.parent).collect::<String>(), "(a)");
+        assert_eq!(glyphs[0].key.glyph_index, font.lookup_glyph_index(open));
+        assert_eq!(glyphs[2].key.glyph_index, font.lookup_glyph_index(close));
+    }
+}
+
+#[test]
+fn glyph_cache_is_keyed_by_raster_config() {
+    use std::hash::{BuildHasher, Hasher};
+
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("hello", 20.0, 0));
+    let mut cache = GlyphCache::default();
+    for glyph in layout.glyphs() {
+        cache.entry(glyph.key).or_insert_with(|| fonts[0].rasterize_config(glyph.key));
+    }
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cache[&layout.glyphs()[2].key], fonts[0].rasterize('l', 20.0));
+
+    let hash = |key: &fontdue::layout::GlyphRasterConfig| FxBuildHasher::default().hash_one(key);
+    let glyphs = layout.glyphs();
+    assert_eq!(hash(&glyphs[2].key), hash(&glyphs[3].key));
+    assert_ne!(hash(&glyphs[0].key), hash(&glyphs[1].key));
+    assert_eq!(FxHasher::default().finish(), 0);
+}
+
+#[test]
+fn lines_record_how_they_ended() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&LayoutSettings::builder().max_width(Some(60.0)).build());
+    layout.append(&fonts, &TextStyle::new("one two three\nfour", 20.0, 0));
+    let ends: Vec<Option<LinebreakKind>> =
+        layout.lines().unwrap().iter().map(|line| line.terminated_by).collect();
+    assert!(ends.len() > 2);
+    assert_eq!(ends[ends.len() - 2], Some(LinebreakKind::Hard));
+    assert_eq!(ends.last(), Some(&None));
+    assert!(ends[..ends.len() - 2].iter().all(|&end| end == Some(LinebreakKind::Soft)));
+}
+
+#[test]
+fn trim_trailing_whitespace_centers_visible_text() {
+    let fonts = [roboto()];
+    let first_x = |text: &str, trim_trailing_whitespace: bool| {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(
+            &LayoutSettings::builder()
+                .max_width(Some(200.0))
+                .horizontal_align(HorizontalAlign::Center)
+                .trim_trailing_whitespace(trim_trailing_whitespace)
+                .build(),
+        );
+        layout.append(&fonts, &TextStyle::new(text, 20.0, 0));
+        (layout.glyphs().len(), layout.glyphs()[0].x)
+    };
+    assert_eq!(first_x("ab   ", true), (5, first_x("ab", true).1));
+    assert!(first_x("ab   ", false).1 < first_x("ab", false).1);
+    assert_eq!(first_x("ab", true), first_x("ab", false));
+    assert_eq!(first_x("ab  \ncd", true).1, first_x("ab", true).1);
+
+    // Lines wrapped after a space are centered without it.
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(
+        &LayoutSettings::builder()
+            .max_width(Some(60.0))
+            .horizontal_align(HorizontalAlign::Right)
+            .trim_trailing_whitespace(true)
+            .build(),
+    );
+    layout.append(&fonts, &TextStyle::new("abc def", 20.0, 0));
+    let lines = layout.lines().unwrap();
+    assert_eq!(lines.len(), 2);
+    let c = &layout.glyphs()[2];
+    let f = &layout.glyphs()[6];
+    assert_eq!(c.pen_x + c.advance, f.pen_x + f.advance);
+}
+
+#[test]
+fn glyphs_carry_their_style_color() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    let red = [255, 0, 0, 255];
+    layout.append(&fonts, &TextStyle::new("let ", 20.0, 0).with_color(red));
+    layout.append(&fonts, &TextStyle::new("x", 20.0, 0));
+    let colors: Vec<[u8; 4]> = layout.glyphs().iter().map(|glyph| glyph.color).collect();
+    assert_eq!(colors, vec![red, red, red, red, [255; 4]]);
+
+    let style = TextStyle::new("x", 20.0, 0);
+    let settings = LayoutSettings::default();
+    assert_ne!(
+        layout.input_hash(&fonts, &style, &settings),
+        layout.input_hash(&fonts, &style.with_color(red), &settings)
+    );
+}
+
+#[test]
+fn visible_glyphs_match_overlapping_lines() {
+    let fonts = [roboto()];
+    let text: String = (0..40).map(|line| format!("line {}\n", line)).collect();
+    for coordinate_system in [CoordinateSystem::PositiveYDown, CoordinateSystem::PositiveYUp] {
+        let mut layout = Layout::new(coordinate_system);
+        layout.append(&fonts, &TextStyle::new(&text, 20.0, 0));
+        let lines = layout.lines().unwrap().clone();
+        let sign = if coordinate_system == CoordinateSystem::PositiveYDown {
+            -1.0
+        } else {
+            1.0
+        };
+        for (y_min, y_max) in [(100.0, 180.0), (-180.0, -100.0), (-1e9, 1e9), (1e8, 1e9), (250.5, 250.5)] {
+            let expected: Vec<_> = lines
+                .iter()
+                .filter(|line| {
+                    let (a, b) =
+                        (line.baseline_y + sign * line.max_ascent, line.baseline_y + sign * line.min_descent);
+                    a.min(b) <= y_max && a.max(b) >= y_min
+                })
+                .flat_map(|line| line.glyph_start..=line.glyph_end)
+                .map(|index| (layout.glyphs()[index].byte_offset, layout.glyphs()[index].y))
+                .collect();
+            let visible: Vec<_> = layout
+                .visible_glyphs(y_min, y_max)
+                .iter()
+                .map(|glyph| (glyph.byte_offset, glyph.y))
+                .collect();
+            assert_eq!(visible, expected);
+        }
+        assert_eq!(layout.visible_glyphs(-1e9, 1e9).len(), layout.glyphs().len());
+        assert!(layout.visible_glyphs(1e8, 1e9).is_empty());
+    }
+}
+
+#[test]
+fn line_height_can_be_absolute() {
+    let fonts = [roboto()];
+    let baselines = |coordinate_system, line_height, px| {
+        let mut layout = Layout::new(coordinate_system);
+        layout.reset(&LayoutSettings::builder().line_height(line_height).build());
+        layout.append(&fonts, &TextStyle::new("a\nb\nc", px, 0));
+        layout.lines().unwrap().iter().map(|line| line.baseline_y).collect::<Vec<f32>>()
+    };
+    for px in [12.0, 20.0, 33.0] {
+        let down = baselines(CoordinateSystem::PositiveYDown, LineHeight::Absolute(24.0), px);
+        assert_eq!((down[1] - down[0], down[2] - down[1]), (24.0, 24.0));
+        let up = baselines(CoordinateSystem::PositiveYUp, LineHeight::Absolute(24.0), px);
+        assert_eq!((up[0] - up[1], up[1] - up[2]), (24.0, 24.0));
+    }
+
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("a", 20.0, 0));
+    let size = layout.lines().unwrap()[0].max_new_line_size;
+    let relative = baselines(CoordinateSystem::PositiveYDown, LineHeight::Relative(2.0), 20.0);
+    assert!((relative[1] - relative[0] - 2.0 * size).abs() < 0.001);
+    assert_eq!(
+        baselines(CoordinateSystem::PositiveYDown, LineHeight::Relative(1.0), 20.0),
+        baselines(CoordinateSystem::PositiveYDown, LayoutSettings::default().line_height, 20.0)
+    );
+}
+
+#[test]
+fn min_advance_spreads_inked_glyphs() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&LayoutSettings::builder().min_advance(30.0).build());
+    layout.append(&fonts, &TextStyle::new("ab e\u{301}f", 20.0, 0));
+    let space = fonts[0].metrics(' ', 20.0).advance_width.ceil();
+    // Glyphs with ink advance by at least the minimum, the space and the mark don't.
+    let advances: Vec<f32> = layout.glyphs().iter().map(|glyph| glyph.advance).collect();
+    assert_eq!(advances, [30.0, 30.0, space, 30.0, 0.0, 30.0]);
+    let pens: Vec<f32> = layout.glyphs().iter().map(|glyph| glyph.pen_x).collect();
+    assert_eq!(pens[5], 90.0 + space);
+}
+
+#[test]
+fn glyphs_know_their_baseline() {
+    let fonts = [roboto()];
+    for writing_mode in [WritingMode::HorizontalTB, WritingMode::VerticalRL] {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(
+            &LayoutSettings::builder()
+                .max_width(Some(60.0))
+                .max_height(Some(60.0))
+                .writing_mode(writing_mode)
+                .build(),
+        );
+        layout.append(&fonts, &TextStyle::new("wrapped onto a few lines", 20.0, 0));
+        let lines = layout.lines().unwrap();
+        assert!(lines.len() > 1);
+        for line in lines {
+            for glyph in &layout.glyphs()[line.glyph_start..=line.glyph_end] {
+                assert_eq!(glyph.baseline_y, line.baseline_y);
+            }
+        }
+    }
+}
+
+#[test]
+fn append_shaped_matches_append() {
+    let fonts = [roboto()];
+    let text = "Shaped glyphs wrap\nlike\tcharacters do";
+    let shaped = fonts[0].shape_simple_with_kerning(text, 18.0, false);
+    for max_width in [None, Some(90.0)] {
+        let settings = LayoutSettings::builder().max_width(max_width).tab_width(40.0).build();
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(&settings);
+        layout.append(&fonts, &TextStyle::new(text, 18.0, 0));
+        let mut shaped_layout = Layout::new(CoordinateSystem::PositiveYDown);
+        shaped_layout.reset(&settings);
+        shaped_layout.append_shaped(&fonts, &TextStyle::new(text, 18.0, 0), &shaped);
+        let positions = |layout: &Layout| -> Vec<_> {
+            layout.glyphs().iter().map(|g| (g.key.glyph_index, g.x, g.y, g.byte_offset, g.parent)).collect()
+        };
+        assert_eq!(positions(&shaped_layout), positions(&layout));
+        assert_eq!(shaped_layout.lines().unwrap().len(), layout.lines().unwrap().len());
+    }
+}
+
+#[test]
+fn append_shaped_keeps_clusters_together() {
+    let fonts = [roboto()];
+    let font = &fonts[0];
+    let glyph = |character, cluster| ShapedGlyph {
+        glyph_index: font.lookup_glyph_index(character),
+        advance: 10.0,
+        x_offset: 0.0,
+        cluster,
+    };
+    // A ligature covering "fi", and a cluster of two glyphs at "ab" that's wider than a line.
+    let text = "fi ab";
+    let wide = ShapedGlyph {
+        advance: 30.0,
+        ..glyph('b', 3)
+    };
+    let glyphs = [glyph('f', 0), glyph(' ', 2), glyph('a', 3), wide];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&LayoutSettings::builder().max_width(Some(25.0)).build());
+    layout.append_shaped(&fonts, &TextStyle::new(text, 20.0, 0), &glyphs);
+    let lines = layout.lines().unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!((lines[1].glyph_start, lines[1].glyph_end), (2, 3));
+    let pens: Vec<f32> = layout.glyphs().iter().map(|glyph| glyph.pen_x).collect();
+    assert_eq!(pens, [0.0, 10.0, 0.0, 10.0]);
+    let offsets: Vec<usize> = layout.glyphs().iter().map(|glyph| glyph.byte_offset).collect();
+    assert_eq!(offsets, [0, 2, 3, 3]);
+}
+
+#[test]
+fn glyphs_visual_orders_right_to_left_lines() {
+    let fonts = [roboto()];
+    let text = "ab\u{301}c\nde";
+    for direction in [TextDirection::LeftToRight, TextDirection::RightToLeft] {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        layout.reset(&LayoutSettings::builder().direction(direction).build());
+        layout.append(&fonts, &TextStyle::new(text, 20.0, 0));
+        let visual = layout.glyphs_visual();
+        let offsets: Vec<usize> = visual.iter().map(|glyph| glyph.byte_offset).collect();
+        let parents: String = visual.iter().map(|glyph| glyph.parent).collect();
+        if direction == TextDirection::LeftToRight {
+            let logical: Vec<usize> = layout.glyphs().iter().map(|glyph| glyph.byte_offset).collect();
+            assert_eq!(offsets, logical);
+        } else {
+            // The mark stays after its base, and the new line ends the first line.
+            assert_eq!(parents, "\ncb\u{301}aed");
+            let bases: Vec<f32> =
+                visual.iter().filter(|glyph| !glyph.char_data.is_mark()).map(|glyph| glyph.pen_x).collect();
+            assert!(bases[..4].windows(2).all(|pair| pair[0] <= pair[1]));
+            assert!(bases[4..].windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+}
+
+#[test]
+fn glyphs_visual_reverses_separate_appends() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.reset(&LayoutSettings::builder().direction(TextDirection::RightToLeft).build());
+    // Every append starts its byte offsets at 0, so only the mark joins the glyph before it.
+    for text in ["a", "b", "c\u{301}", "d"] {
+        layout.append(&fonts, &TextStyle::new(text, 20.0, 0));
+    }
+    let parents: String = layout.glyphs_visual().iter().map(|glyph| glyph.parent).collect();
+    assert_eq!(parents, "dc\u{301}ba");
+}
+
+#[test]
+fn glyph_cache_key_ignores_size() {
+    let fonts = [roboto()];
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&fonts, &TextStyle::new("a", 12.0, 0));
+    layout.append(&fonts, &TextStyle::new("ab", 30.0, 0));
+    let keys: Vec<GlyphCacheKey> = layout.glyphs().iter().map(|glyph| glyph.key.cache_key()).collect();
+    assert_ne!(layout.glyphs()[0].key, layout.glyphs()[1].key);
+    assert_eq!(keys[0], keys[1]);
+    assert_ne!(keys[1], keys[2]);
+    assert_eq!(GlyphCacheKey::from(layout.glyphs()[2].key), keys[2]);
+    assert_eq!(keys[0].font_hash, fonts[0].file_hash());
+
+    let mut cache = std::collections::HashMap::new();
+    for key in keys {
+        *cache.entry(key).or_insert(0) += 1;
+    }
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn layout_settings_builder_sets_fields() {
+    assert!(LayoutSettings::builder().build() == LayoutSettings::default());
+    let settings = LayoutSettings::builder().max_width(Some(100.0)).tab_width(40.0).min_advance(2.0).build();
+    let mut expected = LayoutSettings::default();
+    expected.max_width = Some(100.0);
+    expected.tab_width = 40.0;
+    expected.min_advance = 2.0;
+    assert!(settings == expected);
+}
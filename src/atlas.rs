@@ -0,0 +1,159 @@
+use crate::layout::GlyphRasterConfig;
+use crate::{HashMap, Metrics};
+use alloc::vec;
+use alloc::vec::*;
+
+/// The smallest width an atlas is packed into.
+const MIN_ATLAS_WIDTH: usize = 64;
+
+/// The region of an atlas a glyph was packed into, in pixels from the top left corner of the
+/// atlas.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct AtlasRect {
+    /// The horizontal offset of the left edge of the glyph.
+    pub x: usize,
+    /// The vertical offset of the top edge of the glyph.
+    pub y: usize,
+    /// The width of the glyph, the same as its `Metrics::width`.
+    pub width: usize,
+    /// The height of the glyph, the same as its `Metrics::height`.
+    pub height: usize,
+}
+
+/// A glyph packed into an atlas.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AtlasGlyph {
+    /// Where the glyph's bitmap is in the atlas.
+    pub rect: AtlasRect,
+    /// Sizing and positioning metadata for the rasterized glyph.
+    pub metrics: Metrics,
+}
+
+/// A set of rasterized glyphs packed into a single coverage bitmap.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    /// The width of the atlas in pixels. This is always a power of two, or 0 if the atlas is empty
+    /// because it would have been too large.
+    pub width: usize,
+    /// The height of the atlas in pixels.
+    pub height: usize,
+    /// Coverage vector for the atlas, `width * height` in length. Coverage is a linear scale where
+    /// 0 represents 0% coverage of that pixel and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the atlas.
+    pub bitmap: Vec<u8>,
+    /// The packed glyphs, keyed by the glyph index and px they were requested at. These are the
+    /// same keys as `GlyphPosition::key` from layout with the font.
+    pub glyphs: HashMap<GlyphRasterConfig, AtlasGlyph>,
+    /// The hash of the font the atlas was made from, from `Font::file_hash`.
+    pub font_hash: usize,
+}
+
+impl Atlas {
+    /// Packs rasterized glyphs into an atlas with shelf packing. Glyphs are sorted by height and
+    /// placed left to right in rows, starting a new row when a glyph doesn't fit in the current one.
+    /// Atlases with more than the max pixels are returned empty.
+    pub(crate) fn pack(
+        glyphs: Vec<(GlyphRasterConfig, Metrics, Vec<u8>)>,
+        padding: usize,
+        font_hash: usize,
+        max_pixels: usize,
+    ) -> Atlas {
+        let empty = Atlas {
+            width: 0,
+            height: 0,
+            bitmap: Vec::new(),
+            glyphs: HashMap::new(),
+            font_hash,
+        };
+        let mut order: Vec<usize> = (0..glyphs.len()).collect();
+        order.sort_by(|&a, &b| glyphs[b].1.height.cmp(&glyphs[a].1.height));
+
+        // Pick the narrowest power of two width that fits the widest glyph, and that would make the
+        // atlas roughly square if the glyphs packed perfectly.
+        let mut area: usize = 0;
+        let mut widest = 0;
+        for (_, metrics, _) in &glyphs {
+            let padded =
+                metrics.width.saturating_add(padding).saturating_mul(metrics.height.saturating_add(padding));
+            area = area.saturating_add(padded);
+            widest = widest.max(metrics.width.saturating_add(padding.saturating_mul(2)));
+        }
+        let mut width = MIN_ATLAS_WIDTH;
+        while width < widest || width.saturating_mul(width) < area {
+            width = match width.checked_mul(2) {
+                Some(width) if width <= max_pixels => width,
+                _ => return empty,
+            };
+        }
+
+        let mut rects = vec![AtlasRect::default(); glyphs.len()];
+        let (mut x, mut y, mut shelf_height) = (padding, padding, 0);
+        for &index in &order {
+            let metrics = &glyphs[index].1;
+            if metrics.width == 0 || metrics.height == 0 {
+                continue;
+            }
+            if x + metrics.width + padding > width {
+                x = padding;
+                y += shelf_height + padding;
+                shelf_height = 0;
+            }
+            rects[index] = AtlasRect {
+                x,
+                y,
+                width: metrics.width,
+                height: metrics.height,
+            };
+            x += metrics.width + padding;
+            shelf_height = shelf_height.max(metrics.height);
+        }
+        let height = if shelf_height == 0 {
+            0
+        } else {
+            y + shelf_height + padding
+        };
+
+        if width.checked_mul(height).map_or(true, |pixels| pixels > max_pixels) {
+            return empty;
+        }
+        let mut bitmap = vec![0; width * height];
+        let mut packed = HashMap::with_capacity(glyphs.len());
+        for ((config, metrics, coverage), rect) in glyphs.into_iter().zip(rects) {
+            for row in 0..rect.height {
+                let start = (rect.y + row) * width + rect.x;
+                bitmap[start..start + rect.width]
+                    .copy_from_slice(&coverage[row * rect.width..][..rect.width]);
+            }
+            packed.insert(
+                config,
+                AtlasGlyph {
+                    rect,
+                    metrics,
+                },
+            );
+        }
+        Atlas {
+            width,
+            height,
+            bitmap,
+            glyphs: packed,
+            font_hash,
+        }
+    }
+
+    /// Gets a glyph packed into the atlas.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index the glyph was requested with.
+    /// * `px` - The size the glyph was requested at.
+    /// # Returns
+    ///
+    /// * `Option<&AtlasGlyph>` - The packed glyph, or None if it wasn't requested.
+    pub fn get(&self, index: u16, px: f32) -> Option<&AtlasGlyph> {
+        self.glyphs.get(&GlyphRasterConfig {
+            glyph_index: index,
+            px,
+            font_hash: self.font_hash,
+        })
+    }
+}
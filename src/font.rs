@@ -1,19 +1,27 @@
-use crate::layout::GlyphRasterConfig;
-use crate::math::{Geometry, Line};
-use crate::platform::{as_i32, ceil, floor, fract, is_negative};
+use crate::atlas::Atlas;
+use crate::layout::{CoordinateSystem, GlyphRasterConfig, SubpixelGlyphKey};
+use crate::math::{
+    embolden, rotate, shear, Geometry, Line, Point, COARSE_SCALE_RATIO, DEFAULT_CURVE_TOLERANCE,
+};
+use crate::once::OnceBox;
+use crate::platform::{as_i32, ceil, floor, fract, is_negative, sin_cos};
 use crate::raster::Raster;
-use crate::table::{load_gsub, TableKern};
+use crate::serialize::{Reader, Writer};
+use crate::table::parse::Stream;
+use crate::table::{color_layers, device_advance, load_colr, load_gsub, TableGpos, TableKern};
 use crate::unicode;
 use crate::FontResult;
 use crate::{HashMap, HashSet};
+use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::*;
 use core::hash::{Hash, Hasher};
 use core::mem;
 use core::num::NonZeroU16;
 use core::ops::Deref;
-use ttf_parser::{Face, FaceParsingError, GlyphId, Tag};
+use ttf_parser::{name_id, Face, FaceParsingError, GlyphId, PlatformId, RgbaColor, Tag};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -32,6 +40,190 @@ pub struct OutlineBounds {
     pub height: f32,
 }
 
+/// A color in BGRA order, with 8 bits per channel and straight (not premultiplied) alpha.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct BGRA8Color {
+    pub b: u8,
+    pub g: u8,
+    pub r: u8,
+    pub a: u8,
+}
+
+impl BGRA8Color {
+    #[inline(always)]
+    pub const fn new(b: u8, g: u8, r: u8, a: u8) -> BGRA8Color {
+        BGRA8Color {
+            b,
+            g,
+            r,
+            a,
+        }
+    }
+
+    /// Composites the color over this color, with the color's alpha multiplied by the given
+    /// coverage.
+    #[inline]
+    fn blend(&mut self, color: RgbaColor, coverage: u8) {
+        let src_a = (color.alpha as f32 / 255.0) * (coverage as f32 / 255.0);
+        if src_a <= 0.0 {
+            return;
+        }
+        let dst_a = (self.a as f32 / 255.0) * (1.0 - src_a);
+        let out_a = src_a + dst_a;
+        let channel = |src: u8, dst: u8| ((src as f32 * src_a + dst as f32 * dst_a) / out_a + 0.5) as u8;
+        self.b = channel(color.blue, self.b);
+        self.g = channel(color.green, self.g);
+        self.r = channel(color.red, self.r);
+        self.a = (out_a * 255.0 + 0.5) as u8;
+    }
+}
+
+/// A point in a glyph's outline, in pixels. The Y axis increases upwards, relative to the
+/// baseline.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct OutlinePoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl OutlinePoint {
+    #[inline(always)]
+    pub const fn new(x: f32, y: f32) -> OutlinePoint {
+        OutlinePoint {
+            x,
+            y,
+        }
+    }
+}
+
+/// A single drawing command in a glyph's outline. Outlines are made of closed contours, each
+/// starting with a `MoveTo` and ending with a `Close`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OutlineCommand {
+    /// Starts a new contour at the given point.
+    MoveTo(OutlinePoint),
+    /// Draws a straight line to the given point.
+    LineTo(OutlinePoint),
+    /// Draws a quadratic curve with the given control point, ending at the second point.
+    QuadTo(OutlinePoint, OutlinePoint),
+    /// Draws a cubic curve with the given control points, ending at the third point.
+    CubicTo(OutlinePoint, OutlinePoint, OutlinePoint),
+    /// Closes the current contour.
+    Close,
+}
+
+/// Receives the drawing commands of a glyph's outline from `Font::build_outline`, like for
+/// feeding a tessellator or a vector renderer. Coordinates are in pixels with the Y axis increasing
+/// upwards from the baseline. Outlines are made of closed contours, each starting with `move_to`
+/// and ending with `close`.
+pub trait OutlineSink {
+    /// Starts a new contour at the given point.
+    fn move_to(&mut self, x: f32, y: f32);
+    /// Draws a straight line to the given point.
+    fn line_to(&mut self, x: f32, y: f32);
+    /// Draws a quadratic curve with the control point (x1, y1), ending at (x, y).
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32);
+    /// Draws a cubic curve with the control points (x1, y1) and (x2, y2), ending at (x, y).
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32);
+    /// Closes the current contour.
+    fn close(&mut self);
+}
+
+/// Draws a glyph's outline into an `OutlineSink`, scaled to pixels and slanted by the synthetic
+/// oblique.
+struct SinkBuilder<'a> {
+    scale: f32,
+    oblique: f32,
+    sink: &'a mut dyn OutlineSink,
+}
+
+impl SinkBuilder<'_> {
+    #[inline(always)]
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = (x * self.scale, y * self.scale);
+        (x + y * self.oblique, y)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for SinkBuilder<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.sink.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.sink.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let ((x1, y1), (x, y)) = (self.point(x1, y1), self.point(x, y));
+        self.sink.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let ((x1, y1), (x2, y2), (x, y)) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.sink.curve_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.sink.close();
+    }
+}
+
+/// Collects a glyph's outline into scaled commands.
+struct OutlineCommands {
+    scale: f32,
+    commands: Vec<OutlineCommand>,
+}
+
+impl OutlineCommands {
+    #[inline(always)]
+    fn point(&self, x: f32, y: f32) -> OutlinePoint {
+        OutlinePoint::new(x * self.scale, y * self.scale)
+    }
+}
+
+/// Draws outline commands with the given builder.
+fn draw_outline<B: ttf_parser::OutlineBuilder>(commands: &[OutlineCommand], builder: &mut B) {
+    for command in commands {
+        match *command {
+            OutlineCommand::MoveTo(p) => builder.move_to(p.x, p.y),
+            OutlineCommand::LineTo(p) => builder.line_to(p.x, p.y),
+            OutlineCommand::QuadTo(c, p) => builder.quad_to(c.x, c.y, p.x, p.y),
+            OutlineCommand::CubicTo(c0, c1, p) => builder.curve_to(c0.x, c0.y, c1.x, c1.y, p.x, p.y),
+            OutlineCommand::Close => builder.close(),
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineCommands {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.commands.push(OutlineCommand::MoveTo(point));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.commands.push(OutlineCommand::LineTo(point));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let command = OutlineCommand::QuadTo(self.point(x1, y1), self.point(x, y));
+        self.commands.push(command);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let command = OutlineCommand::CubicTo(self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.commands.push(command);
+    }
+
+    fn close(&mut self) {
+        self.commands.push(OutlineCommand::Close);
+    }
+}
+
 impl Default for OutlineBounds {
     fn default() -> Self {
         Self {
@@ -73,6 +265,14 @@ pub struct Metrics {
     pub advance_width: f32,
     /// Advance height of the glyph in subpixels. Used in vertical fonts.
     pub advance_height: f32,
+    /// Left side bearing of the glyph in subpixels. This is the distance from the pen position to
+    /// the left edge of the glyph's outline, and may be negative if the glyph extends to the left
+    /// of the pen.
+    pub left_side_bearing: f32,
+    /// Right side bearing of the glyph in subpixels. This is the distance from the right edge of
+    /// the glyph's outline to the pen position after advancing, and may be negative if the glyph
+    /// extends past its advance.
+    pub right_side_bearing: f32,
     /// The bounding box that contains the glyph's outline at the offsets specified by the font.
     /// This is always a smaller box than the bitmap bounds.
     pub bounds: OutlineBounds,
@@ -87,11 +287,78 @@ impl Default for Metrics {
             height: 0,
             advance_width: 0.0,
             advance_height: 0.0,
+            left_side_bearing: 0.0,
+            right_side_bearing: 0.0,
             bounds: OutlineBounds::default(),
         }
     }
 }
 
+impl Metrics {
+    /// Gets where to place the glyph's bitmap for a pen on the baseline. This is the same placement
+    /// layout uses for `GlyphPosition`, except it's always the top left corner of the bitmap.
+    /// # Arguments
+    ///
+    /// * `pen_x` - The x coordinate of the pen on the baseline, in pixels.
+    /// * `pen_y` - The y coordinate of the pen on the baseline, in pixels.
+    /// * `coordinate_system` - The direction the Y coordinate increases in.
+    /// # Returns
+    ///
+    /// * `(f32, f32)` - The coordinates of the top left corner of the bitmap, in whole pixels. With
+    /// PositiveYUp, the bitmap's rows extend down from this corner towards smaller Y coordinates.
+    pub fn origin(&self, pen_x: f32, pen_y: f32, coordinate_system: CoordinateSystem) -> (f32, f32) {
+        let x = floor(pen_x + self.bounds.xmin);
+        let y = match coordinate_system {
+            CoordinateSystem::PositiveYUp => floor(pen_y + self.bounds.ymin) + self.height as f32,
+            CoordinateSystem::PositiveYDown => floor(pen_y - self.bounds.height - self.bounds.ymin),
+        };
+        (x, y)
+    }
+}
+
+/// A glyph from a run of text placed along a single line by `Font::shape_simple`. To position the
+/// glyphs, move the pen by each glyph's x offset before drawing it, and by its advance after.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ShapedGlyph {
+    /// The glyph index in the font.
+    pub glyph_index: u16,
+    /// The horizontal advance of the glyph in pixels.
+    pub advance: f32,
+    /// The kerning between the previous glyph and this one in pixels. This moves this glyph and
+    /// every glyph after it.
+    pub x_offset: f32,
+    /// The byte offset into the text of the character the glyph is for.
+    pub cluster: usize,
+}
+
+/// The order of the subpixels in each pixel of a display, left to right, see
+/// `Font::rasterize_indexed_subpixel_layout`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SubpixelLayout {
+    /// Red, then green, then blue. This is the order `Font::rasterize_subpixel` uses.
+    Rgb,
+    /// Blue, then green, then red.
+    Bgr,
+}
+
+/// The platform and encoding of a character map subtable in a font, see `Font::cmap_encodings`.
+/// The IDs are listed in the OpenType specification of the name table.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CmapEncoding {
+    /// The platform ID, like 0 for Unicode, 1 for Macintosh, or 3 for Windows.
+    pub platform_id: u16,
+    /// The platform specific encoding ID, like 1 for Unicode BMP or 0 for Symbol on Windows.
+    pub encoding_id: u16,
+}
+
+impl CmapEncoding {
+    /// If this is the Windows Symbol encoding, which symbol fonts use to map their glyphs into the
+    /// private use area starting at U+F000.
+    pub fn is_symbol(&self) -> bool {
+        self.platform_id == 3 && self.encoding_id == 0
+    }
+}
+
 /// Metrics associated with line positioning.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct LineMetrics {
@@ -142,6 +409,9 @@ pub(crate) struct Glyph {
     advance_width: f32,
     advance_height: f32,
     pub bounds: OutlineBounds,
+    /// A cheaper copy of the geometry for rasterizing far below the flattening scale, if it's
+    /// meaningfully smaller than the regular geometry.
+    pub coarse: Option<Box<Glyph>>,
 }
 
 impl Default for Glyph {
@@ -152,26 +422,318 @@ impl Default for Glyph {
             advance_width: 0.0,
             advance_height: 0.0,
             bounds: OutlineBounds::default(),
+            coarse: None,
         }
     }
 }
 
-/// Settings for controlling specific font and layout behavior.
-#[derive(Copy, Clone, PartialEq, Debug)]
+impl Glyph {
+    /// Checks if the glyph has no geometry, meaning it never produces any coverage.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.v_lines.is_empty() && self.m_lines.is_empty()
+    }
+
+    /// The number of bytes allocated on the heap by this glyph's geometry.
+    fn heap_size(&self) -> usize {
+        let lines = (self.v_lines.capacity() + self.m_lines.capacity()) * mem::size_of::<Line>();
+        let coarse = self.coarse.as_ref().map_or(0, |coarse| mem::size_of::<Glyph>() + coarse.heap_size());
+        lines + coarse
+    }
+
+    /// Writes the glyph. Only the end points of each line are written, the rest of the line is
+    /// derived from them when it's read.
+    fn serialize(&self, writer: &mut Writer) {
+        writer.write_f32(self.advance_width);
+        writer.write_f32(self.advance_height);
+        let bounds = self.bounds;
+        for x in [bounds.xmin, bounds.ymin, bounds.width, bounds.height] {
+            writer.write_f32(x);
+        }
+        self.serialize_lines(writer);
+        writer.write_option(self.coarse.as_deref(), |writer, coarse| coarse.serialize_lines(writer));
+    }
+
+    fn serialize_lines(&self, writer: &mut Writer) {
+        for lines in [&self.v_lines, &self.m_lines] {
+            writer.write_len(lines.len());
+            for line in lines.iter() {
+                let (x0, y0, x1, y1) = line.coords.copied();
+                for x in [x0, y0, x1, y1] {
+                    writer.write_f32(x);
+                }
+            }
+        }
+    }
+
+    /// Reads a glyph written by `Glyph::serialize`. The raster writes without bounds checks, so
+    /// every line is checked to be within the glyph's bounds, and rebuilt from its end points,
+    /// the same as lines generated from the font are.
+    fn deserialize(reader: &mut Reader) -> Option<Glyph> {
+        let advance_width = reader.read_f32()?;
+        let advance_height = reader.read_f32()?;
+        let bounds = OutlineBounds {
+            xmin: reader.read_f32()?,
+            ymin: reader.read_f32()?,
+            width: reader.read_f32()?,
+            height: reader.read_f32()?,
+        };
+        let finite = [bounds.xmin, bounds.ymin, bounds.width, bounds.height].iter().all(|x| x.is_finite());
+        if !finite || !(bounds.width >= 0.0 && bounds.height >= 0.0) {
+            return None;
+        }
+        let (v_lines, m_lines) = Glyph::deserialize_lines(reader, bounds)?;
+        let coarse = reader.read_option(|reader| {
+            let (v_lines, m_lines) = Glyph::deserialize_lines(reader, bounds)?;
+            Some(Box::new(Glyph {
+                v_lines,
+                m_lines,
+                bounds,
+                ..Glyph::default()
+            }))
+        })?;
+        Some(Glyph {
+            v_lines,
+            m_lines,
+            advance_width,
+            advance_height,
+            bounds,
+            coarse,
+        })
+    }
+
+    /// Reads lines relative to the top left of the given bounds. Vertical lines must have the same
+    /// x at both ends, and other lines can't be horizontal or vertical, as the raster expects.
+    fn deserialize_lines(reader: &mut Reader, bounds: OutlineBounds) -> Option<(Vec<Line>, Vec<Line>)> {
+        let read_point = |reader: &mut Reader| {
+            let (x, y) = (reader.read_f32()?, reader.read_f32()?);
+            if (0.0..=bounds.width).contains(&x) && (0.0..=bounds.height).contains(&y) {
+                Some(Point::new(x, y))
+            } else {
+                None
+            }
+        };
+        let v_lines = reader.read_vec(|reader| {
+            let (start, end) = (read_point(reader)?, read_point(reader)?);
+            if start.x != end.x {
+                return None;
+            }
+            Some(Line::new(start, end))
+        })?;
+        let m_lines = reader.read_vec(|reader| {
+            let (start, end) = (read_point(reader)?, read_point(reader)?);
+            if start.x == end.x || start.y == end.y {
+                return None;
+            }
+            Some(Line::new(start, end))
+        })?;
+        Some((v_lines, m_lines))
+    }
+
+    /// Selects the geometry to rasterize with for the given px size. Sizes far below the scale the
+    /// glyph was flattened for use the coarse geometry, which draws the same outline with fewer
+    /// lines.
+    #[inline]
+    fn level_of_detail(&self, px: f32, scale: f32) -> &Glyph {
+        match &self.coarse {
+            Some(coarse) if px <= scale * COARSE_SCALE_RATIO => coarse,
+            _ => self,
+        }
+    }
+}
+
+/// Glyph geometry that is generated on first use rather than when the font is loaded.
+#[derive(Clone)]
+struct LazyGlyphs {
+    /// Memoized glyphs, indexed by glyph index.
+    glyphs: Vec<OnceBox<Glyph>>,
+}
+
+impl LazyGlyphs {
+    fn get(&self, face: Option<&Face>, index: u16, settings: &FontSettings, units_per_em: f32) -> &Glyph {
+        self.glyphs[index as usize].get_or_init(|| match face {
+            Some(face) => generate_glyph(face, index, settings, units_per_em),
+            None => Glyph::default(),
+        })
+    }
+}
+
+/// Working memory for the rasterizer, which can be kept between calls to
+/// `Font::rasterize_into_scratch` to avoid allocating for every glyph.
+pub struct RasterScratch {
+    raster: Raster,
+}
+
+impl RasterScratch {
+    /// Creates empty scratch space. It grows to fit the glyphs it's used to rasterize.
+    pub const fn new() -> RasterScratch {
+        RasterScratch {
+            raster: Raster::empty(),
+        }
+    }
+}
+
+impl Default for RasterScratch {
+    fn default() -> Self {
+        RasterScratch::new()
+    }
+}
+
+/// A table kerning is read from.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KerningTable {
+    /// The legacy kern table.
+    Kern,
+    /// Pair adjustments from the GPOS table.
+    Gpos,
+}
+
+/// Which tables horizontal kerning is read from, see `FontSettings::kerning_source`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KerningSource {
+    /// Only the kern table is read, GPOS is ignored.
+    KernOnly,
+    /// Only the GPOS table is read, the kern table is ignored.
+    GposOnly,
+    /// Both tables are read and merged. Pairs in both tables use the value from the given table.
+    Prefer(KerningTable),
+}
+
+impl Default for KerningSource {
+    fn default() -> KerningSource {
+        KerningSource::Prefer(KerningTable::Gpos)
+    }
+}
+
+/// Which table the horizontal line metrics are read from, see `FontSettings::line_metrics_source`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineMetricsSource {
+    /// The ascender, descender, and line gap from the hhea table. Fonts that set the
+    /// USE_TYPO_METRICS flag in their OS/2 table use the OS/2 typographic metrics instead.
+    Hhea,
+    /// The typographic ascender, descender, and line gap from the OS/2 table, which web browsers
+    /// use for line spacing.
+    Os2Typo,
+    /// The Windows ascent and descent from the OS/2 table, which cover every glyph in the font, with
+    /// no line gap.
+    Os2Win,
+}
+
+impl Default for LineMetricsSource {
+    fn default() -> LineMetricsSource {
+        LineMetricsSource::Hhea
+    }
+}
+
+/// How glyphs are fitted to the pixel grid, see `FontSettings::grid_fit`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GridFit {
+    /// Glyphs are placed exactly where their outlines are.
+    None,
+    /// The bottom of each glyph is moved by up to half a pixel to land on the edge of a pixel.
+    Vertical,
+}
+
+impl Default for GridFit {
+    fn default() -> GridFit {
+        GridFit::None
+    }
+}
+
+/// Settings for controlling specific font and layout behavior. New settings are added over time,
+/// so outside of this crate these are created with `FontSettings::default()` or
+/// `FontSettings::builder()`, and then changed field by field.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
 pub struct FontSettings {
     /// The default is 0. The index of the font to use if parsing a font collection.
     pub collection_index: u32,
     /// The default is 40. The scale in px the font geometry is optimized for. Fonts rendered at
     /// the scale defined here will be the most optimal in terms of looks and performance. Glyphs
     /// rendered smaller than this scale will look the same but perform slightly worse, while
-    /// glyphs rendered larger than this will looks worse but perform slightly better. The units of
-    /// the scale are pixels per Em unit.
+    /// glyphs rendered larger than this will looks worse but perform slightly better. Glyphs
+    /// rendered at a quarter of this scale or smaller use a coarser copy of the geometry to avoid
+    /// that cost. The units of the scale are pixels per Em unit.
     pub scale: f32,
     /// The default is true. If enabled, will load glyphs for substitutions (liagtures, etc.) from
     /// the gsub table on compatible fonts. Only makes a difference when using indexed operations,
     /// i.e. `Font::raserize_indexed`, as singular characters do not have enough context to be
     /// substituted.
     pub load_substitutions: bool,
+    /// The default is empty. Coordinates to load a variable font at, as pairs of an axis tag and
+    /// the value along that axis, like `(*b"wght", 700.0)`. Axes that aren't listed use their
+    /// default value, and axes the font doesn't have are ignored. See `Font::variation_axes` for
+    /// the axes a font has.
+    pub variations: Vec<([u8; 4], f32)>,
+    /// The default is 0. Synthesizes a bold style by moving glyph outlines outward by this
+    /// distance, as a fraction of the font size. Glyphs are moved right by the same distance, and
+    /// their advance width grows by twice the distance, so side bearings are kept. Negative values
+    /// thin glyphs instead. This is applied when glyphs are loaded, so rasterizing stays as cheap.
+    pub synthetic_bold: f32,
+    /// The default is 0. Synthesizes an italic style by slanting glyph outlines, moving every
+    /// point right by its height above the baseline times this factor. Negative values slant
+    /// glyphs backwards. Advance widths aren't changed. This is applied when glyphs are loaded,
+    /// so rasterizing stays as cheap.
+    pub synthetic_oblique: f32,
+    /// The default is false. If enabled, glyph geometry is generated the first time each glyph is
+    /// used rather than when the font is loaded, like `Font::from_bytes_lazy`. This greatly
+    /// reduces load time and memory for large fonts when only a few of their glyphs are used.
+    pub lazy: bool,
+    /// The default is 3. How far in pixels, at the scale given by `FontSettings::scale`, the lines
+    /// glyph curves are flattened into may stray from the curves. Smaller values give smoother
+    /// curves at the cost of more lines to rasterize, which helps when rendering far above the
+    /// scale, like for print. Must be positive.
+    pub curve_tolerance: f32,
+    /// The default is 0, which keeps all coverage. Coverage below this value is snapped to 0 in
+    /// rasterized bitmaps. Small text is anti-aliased into many faint pixels, which this drops to
+    /// sharpen it.
+    pub coverage_floor: u8,
+    /// The default is true. If disabled, rasterized bitmaps aren't anti-aliased: pixels at least
+    /// half covered by the glyph have full coverage, and every other pixel has none. This gives
+    /// crisp pixelated text, like for retro styles or e-ink displays.
+    pub antialias: bool,
+    /// The default is false. Fonts without a character map, like fonts subsetted for embedding in a
+    /// PDF, are used entirely by glyph index, with a character map from elsewhere. If enabled, every
+    /// glyph in such fonts is loaded, so the indexed functions like `Font::rasterize_indexed` and
+    /// `Font::metrics_indexed` work as they do for any other font. Otherwise they still load, but
+    /// only glyphs reachable through substitutions have geometry unless the font is lazy. Character
+    /// functions like `Font::rasterize` always use the missing glyph.
+    pub allow_no_cmap: bool,
+    /// The default is false, where pixel `i` of a bitmap covers coordinates `i` to `i + 1`, so a
+    /// glyph edge on a whole coordinate falls between two pixels. If enabled, pixel `i` is
+    /// centered on coordinate `i` instead, covering `i - 0.5` to `i + 0.5`, which matches
+    /// renderers that sample at pixel centers. Outlines are offset by half a pixel on both axes
+    /// when rasterized, and the returned metrics bounds include that offset.
+    pub pixel_center: bool,
+    /// The default is `KerningSource::Prefer(KerningTable::Gpos)`. Which of the kern and GPOS
+    /// tables horizontal kerning is read from, and which wins for pairs in both. Pinning this
+    /// keeps spacing stable if a font gains a table, or if the default changes.
+    pub kerning_source: KerningSource,
+    /// The default is Hhea. Which table `Font::horizontal_line_metrics` is read from. The tables
+    /// often disagree, and text spaced by the OS/2 typographic metrics matches web browsers. Fonts
+    /// without an OS/2 table always use the hhea table.
+    pub line_metrics_source: LineMetricsSource,
+    /// The default is None. fontdue doesn't hint glyphs, so horizontal strokes that fall between
+    /// pixels are blurred across two rows at small sizes. If set to Vertical, glyphs are moved up or
+    /// down by up to half a pixel so their bottom lands on the edge of a pixel, which sharpens the
+    /// strokes glyphs rest on the baseline with. This trades exact metrics for crisper UI text,
+    /// and the returned metrics bounds include the move.
+    pub grid_fit: GridFit,
+    /// The default is 67,108,864, the pixels in an 8192 by 8192 bitmap. The most pixels a bitmap
+    /// is allowed to have. Rasterizing at huge sizes, like from a corrupt size or a font with huge
+    /// bounds, would otherwise try to allocate gigabytes, so glyphs whose bitmap would be larger
+    /// are returned as `Metrics::default()` and an empty bitmap instead. Subpixel bitmaps count
+    /// each subpixel.
+    pub max_bitmap_pixels: usize,
+    /// The default is false. If enabled, the font keeps a copy of the font data after it's loaded,
+    /// for functions that read more of the font than is loaded up front: `Font::outline`,
+    /// `Font::build_outline`, `Font::rasterize_sdf`, `Font::rasterize_stroked`,
+    /// `Font::rasterize_indexed_rotated`, the color layers of `Font::rasterize_indexed_color`,
+    /// `Font::device_advance`, and `Font::cmap_encodings`.
+    /// It also lets `Font::set_default_glyph` and `Font::override_glyph` use glyphs that weren't
+    /// loaded up front. The copy costs the size of the font file in memory. Lazily loaded fonts
+    /// always keep their data, since they generate glyphs from it.
+    pub keep_data: bool,
 }
 
 impl Default for FontSettings {
@@ -180,20 +742,273 @@ impl Default for FontSettings {
             collection_index: 0,
             scale: 40.0,
             load_substitutions: true,
+            variations: Vec::new(),
+            synthetic_bold: 0.0,
+            synthetic_oblique: 0.0,
+            lazy: false,
+            curve_tolerance: DEFAULT_CURVE_TOLERANCE,
+            coverage_floor: 0,
+            antialias: true,
+            allow_no_cmap: false,
+            pixel_center: false,
+            kerning_source: KerningSource::default(),
+            line_metrics_source: LineMetricsSource::default(),
+            grid_fit: GridFit::default(),
+            max_bitmap_pixels: 8192 * 8192,
+            keep_data: false,
+        }
+    }
+}
+
+impl FontSettings {
+    /// Creates a builder for font settings, starting from the defaults.
+    pub fn builder() -> FontSettingsBuilder {
+        FontSettingsBuilder::default()
+    }
+
+    /// If fonts loaded with these settings keep their data.
+    fn keeps_data(&self) -> bool {
+        self.keep_data || self.lazy
+    }
+
+    /// If glyph outlines need to be changed to synthesize a style.
+    fn has_synthetic_style(&self) -> bool {
+        self.synthetic_bold != 0.0 || self.synthetic_oblique != 0.0
+    }
+
+    /// Applies the synthesized styles to an outline scaled to the given size.
+    fn synthesize(&self, mut commands: Vec<OutlineCommand>, size: f32) -> Vec<OutlineCommand> {
+        if self.synthetic_bold != 0.0 {
+            commands = embolden(&commands, self.synthetic_bold * size);
+        }
+        if self.synthetic_oblique != 0.0 {
+            shear(&mut commands, self.synthetic_oblique);
+        }
+        commands
+    }
+
+    /// Grows bounds in font units to fit outlines with the synthesized styles applied.
+    fn synthesize_bounds(&self, bounds: OutlineBounds, units_per_em: f32) -> OutlineBounds {
+        // Emboldening grows the outline on every side, then moves it right by the same amount.
+        let bold = self.synthetic_bold * units_per_em;
+        let ymin = bounds.ymin - bold;
+        let ymax = bounds.ymin + bounds.height + bold;
+        let xmax = bounds.xmin + bounds.width + 2.0 * bold;
+        // Shearing moves points right by their height, so the extremes come from the top and bottom.
+        let (low, high) = (ymin * self.synthetic_oblique, ymax * self.synthetic_oblique);
+        let xmin = bounds.xmin + low.min(high);
+        let xmax = xmax + low.max(high);
+        OutlineBounds {
+            xmin,
+            ymin,
+            width: xmax - xmin,
+            height: ymax - ymin,
         }
     }
 }
 
-/// Represents a font. Fonts are immutable after creation and owns its own copy of the font data.
+/// Builds `FontSettings` with chainable setters, see `FontSettings::builder`. Settings that aren't
+/// set keep their default. Every setting is documented on the matching `FontSettings` field.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FontSettingsBuilder {
+    settings: FontSettings,
+}
+
+impl FontSettingsBuilder {
+    /// Creates the font settings.
+    pub fn build(self) -> FontSettings {
+        self.settings
+    }
+
+    /// Sets `FontSettings::collection_index`.
+    pub fn collection_index(mut self, collection_index: u32) -> FontSettingsBuilder {
+        self.settings.collection_index = collection_index;
+        self
+    }
+
+    /// Sets `FontSettings::scale`.
+    pub fn scale(mut self, scale: f32) -> FontSettingsBuilder {
+        self.settings.scale = scale;
+        self
+    }
+
+    /// Sets `FontSettings::load_substitutions`.
+    pub fn load_substitutions(mut self, load_substitutions: bool) -> FontSettingsBuilder {
+        self.settings.load_substitutions = load_substitutions;
+        self
+    }
+
+    /// Sets `FontSettings::variations`.
+    pub fn variations(mut self, variations: Vec<([u8; 4], f32)>) -> FontSettingsBuilder {
+        self.settings.variations = variations;
+        self
+    }
+
+    /// Sets `FontSettings::synthetic_bold`.
+    pub fn synthetic_bold(mut self, synthetic_bold: f32) -> FontSettingsBuilder {
+        self.settings.synthetic_bold = synthetic_bold;
+        self
+    }
+
+    /// Sets `FontSettings::synthetic_oblique`.
+    pub fn synthetic_oblique(mut self, synthetic_oblique: f32) -> FontSettingsBuilder {
+        self.settings.synthetic_oblique = synthetic_oblique;
+        self
+    }
+
+    /// Sets `FontSettings::lazy`.
+    pub fn lazy(mut self, lazy: bool) -> FontSettingsBuilder {
+        self.settings.lazy = lazy;
+        self
+    }
+
+    /// Sets `FontSettings::curve_tolerance`.
+    pub fn curve_tolerance(mut self, curve_tolerance: f32) -> FontSettingsBuilder {
+        self.settings.curve_tolerance = curve_tolerance;
+        self
+    }
+
+    /// Sets `FontSettings::coverage_floor`.
+    pub fn coverage_floor(mut self, coverage_floor: u8) -> FontSettingsBuilder {
+        self.settings.coverage_floor = coverage_floor;
+        self
+    }
+
+    /// Sets `FontSettings::antialias`.
+    pub fn antialias(mut self, antialias: bool) -> FontSettingsBuilder {
+        self.settings.antialias = antialias;
+        self
+    }
+
+    /// Sets `FontSettings::allow_no_cmap`.
+    pub fn allow_no_cmap(mut self, allow_no_cmap: bool) -> FontSettingsBuilder {
+        self.settings.allow_no_cmap = allow_no_cmap;
+        self
+    }
+
+    /// Sets `FontSettings::pixel_center`.
+    pub fn pixel_center(mut self, pixel_center: bool) -> FontSettingsBuilder {
+        self.settings.pixel_center = pixel_center;
+        self
+    }
+
+    /// Sets `FontSettings::kerning_source`.
+    pub fn kerning_source(mut self, kerning_source: KerningSource) -> FontSettingsBuilder {
+        self.settings.kerning_source = kerning_source;
+        self
+    }
+
+    /// Sets `FontSettings::line_metrics_source`.
+    pub fn line_metrics_source(mut self, line_metrics_source: LineMetricsSource) -> FontSettingsBuilder {
+        self.settings.line_metrics_source = line_metrics_source;
+        self
+    }
+
+    /// Sets `FontSettings::grid_fit`.
+    pub fn grid_fit(mut self, grid_fit: GridFit) -> FontSettingsBuilder {
+        self.settings.grid_fit = grid_fit;
+        self
+    }
+
+    /// Sets `FontSettings::max_bitmap_pixels`.
+    pub fn max_bitmap_pixels(mut self, max_bitmap_pixels: usize) -> FontSettingsBuilder {
+        self.settings.max_bitmap_pixels = max_bitmap_pixels;
+        self
+    }
+
+    /// Sets `FontSettings::keep_data`.
+    pub fn keep_data(mut self, keep_data: bool) -> FontSettingsBuilder {
+        self.settings.keep_data = keep_data;
+        self
+    }
+}
+
+/// Describes an axis of a variable font.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AxisInfo {
+    /// The axis tag, like `wght` for weight or `wdth` for width.
+    pub tag: [u8; 4],
+    /// The minimum value along the axis.
+    pub min_value: f32,
+    /// The value along the axis the font is loaded at by default.
+    pub default_value: f32,
+    /// The maximum value along the axis.
+    pub max_value: f32,
+    /// If the font suggests the axis shouldn't be shown in user interfaces.
+    pub hidden: bool,
+}
+
+/// Parses the face from the font data, set to the variation coordinates in the settings.
+fn parse_face<'a>(data: &'a [u8], settings: &FontSettings) -> Result<Face<'a>, FaceParsingError> {
+    let mut face = Face::parse(data, settings.collection_index)?;
+    for &(tag, value) in &settings.variations {
+        // Axes the font doesn't have are ignored.
+        let _ = face.set_variation(Tag::from_bytes(&tag), value);
+    }
+    Ok(face)
+}
+
+/// A copy of the font data kept after loading, see `FontSettings::keep_data`, with the face parsed
+/// from it once.
+struct FontData {
+    /// Borrows from `data`. It's only handed out for as long as the struct is borrowed, so it never
+    /// outlives the data.
+    face: Face<'static>,
+    data: Arc<[u8]>,
+}
+
+impl FontData {
+    fn new(data: Arc<[u8]>, settings: &FontSettings) -> Result<FontData, FaceParsingError> {
+        // This is safe, the slice is in the Arc's allocation, which doesn't move and is kept alive
+        // by the struct for as long as the face.
+        let bytes: &'static [u8] = unsafe { &*(&*data as *const [u8]) };
+        let face = parse_face(bytes, settings)?;
+        Ok(FontData {
+            face,
+            data,
+        })
+    }
+
+    #[inline]
+    fn face(&self) -> &Face<'_> {
+        &self.face
+    }
+}
+
+/// Represents a font. Fonts are immutable after creation apart from explicit character mapping
+/// and default glyph overrides.
 #[derive(Clone)]
 pub struct Font {
+    /// The font data, if the font keeps it for features that read the font after it's loaded.
+    data: Option<Arc<FontData>>,
+    /// For fonts that don't keep their data, which glyphs had their geometry generated when the
+    /// font was loaded. Empty otherwise, since any glyph can be generated from the data.
+    loaded: Vec<bool>,
+    variation_axes: Vec<AxisInfo>,
     name: Option<String>,
+    family_name: Option<String>,
+    style_name: Option<String>,
+    postscript_name: Option<String>,
     units_per_em: f32,
     glyphs: Vec<Glyph>,
+    lazy_glyphs: Option<LazyGlyphs>,
     char_to_glyph: HashMap<char, NonZeroU16>,
+    /// The reverse of the character map, built the first time it's needed.
+    glyph_to_char: OnceBox<HashMap<u16, char>>,
+    /// The glyph used for characters missing from the character map.
+    default_glyph: u16,
     horizontal_line_metrics: Option<LineMetrics>,
     horizontal_kern: Option<HashMap<u32, i16>>,
     vertical_line_metrics: Option<LineMetrics>,
+    monospace_advance: Option<f32>,
+    /// The cap height from the OS/2 table, in font units.
+    cap_height: Option<f32>,
+    /// The x height from the OS/2 table, in font units.
+    x_height: Option<f32>,
+    /// The bounding box of every glyph from the head table, in font units.
+    bounding_box: OutlineBounds,
+    bold: bool,
+    italic: bool,
     settings: FontSettings,
     hash: usize,
 }
@@ -222,106 +1037,720 @@ fn convert_error(error: FaceParsingError) -> &'static str {
         MalformedFont => "An attempt to read out of bounds detected.",
         UnknownMagic => "Face data must start with 0x00010000, 0x74727565, 0x4F54544F or 0x74746366.",
         FaceIndexOutOfBounds => "The face index is larger than the number of faces in the font.",
-        NoHeadTable => "The head table is missing or malformed.",
+        NoHeadTable => "The head table is missing or malformed, or its units per em is out of range.",
         NoHheaTable => "The hhea table is missing or malformed.",
         NoMaxpTable => "The maxp table is missing or malformed.",
     }
 }
 
-fn convert_name(face: &Face) -> Option<String> {
+/// Parses and flattens the outline for a single glyph.
+fn generate_glyph(face: &Face, index: u16, settings: &FontSettings, units_per_em: f32) -> Glyph {
+    let mut glyph = Glyph::default();
+    let glyph_id = GlyphId(index);
+    if let Some(advance_width) = face.glyph_hor_advance(glyph_id) {
+        glyph.advance_width = advance_width as f32;
+    }
+    if let Some(advance_height) = face.glyph_ver_advance(glyph_id) {
+        glyph.advance_height = advance_height as f32;
+    }
+
+    let mut geometry = Geometry::new(settings.scale, units_per_em, settings.curve_tolerance);
+    if settings.has_synthetic_style() {
+        let mut outline = OutlineCommands {
+            scale: 1.0,
+            commands: Vec::new(),
+        };
+        face.outline_glyph(glyph_id, &mut outline);
+        draw_outline(&settings.synthesize(outline.commands, units_per_em), &mut geometry);
+        glyph.advance_width += 2.0 * settings.synthetic_bold * units_per_em;
+    } else {
+        face.outline_glyph(glyph_id, &mut geometry);
+    }
+    geometry.finalize(&mut glyph);
+    glyph
+}
+
+/// Gets the first unicode entry in the name table with the given Name ID.
+fn convert_name(face: &Face, name_id: u16) -> Option<String> {
     for name in face.names() {
-        if name.name_id == 4 && name.is_unicode() {
+        if name.name_id == name_id && name.is_unicode() {
             return Some(unicode::decode_utf16(name.name));
         }
     }
     None
 }
 
+/// Counts the faces in a font file, like for presenting every font in a TrueType collection.
+/// # Arguments
+///
+/// * `data` - The font file's data.
+/// # Returns
+///
+/// * `FontResult<u32>` - The number of faces in the collection, or 1 if the data is a single font
+/// rather than a collection. Returns an error if the data is neither.
+pub fn collection_face_count(data: &[u8]) -> FontResult<u32> {
+    match ttf_parser::fonts_in_collection(data) {
+        Some(count) => Ok(count),
+        None => Face::parse(data, 0).map(|_| 1).map_err(convert_error),
+    }
+}
+
 impl Font {
-    /// Constructs a font from an array of bytes.
+    /// Constructs a font from an array of bytes. Glyph geometry is generated up front, unless
+    /// `FontSettings::lazy` is set.
+    ///
+    /// The data isn't needed after loading unless `FontSettings::keep_data` or
+    /// `FontSettings::lazy` is set. Then the font keeps a copy of it for as long as it's alive,
+    /// shared with its clones, so its memory use includes the size of the font file, see
+    /// `Font::memory_usage`.
     pub fn from_bytes<Data: Deref<Target = [u8]>>(data: Data, settings: FontSettings) -> FontResult<Font> {
-        let hash = crate::hash::hash(&data);
-
-        let face = match Face::parse(&data, settings.collection_index) {
-            Ok(f) => f,
-            Err(e) => return Err(convert_error(e)),
+        let shared = if settings.keeps_data() {
+            Some(Arc::from(&*data))
+        } else {
+            None
         };
-        let name = convert_name(&face);
+        Self::from_bytes_internal(&data, shared, settings)
+    }
 
-        // Optionally get kerning values for the font. This should be a try block in the future.
-        let horizontal_kern: Option<HashMap<u32, i16>> = (|| {
-            let table: &[u8] = face.raw_face().table(Tag::from_bytes(&b"kern"))?;
-            let table: TableKern = TableKern::new(table)?;
-            Some(table.horizontal_mappings)
-        })();
+    /// Constructs a font from an array of bytes, deferring the generation of glyph geometry until
+    /// the glyph is first used. This only flattens the outlines of glyphs that are actually
+    /// rasterized or measured, which greatly reduces load time and peak memory for large fonts when
+    /// only a few glyphs are needed. Every glyph in the font is available, regardless of
+    /// `FontSettings::load_substitutions`. This is the same as loading with `FontSettings::lazy`
+    /// set.
+    pub fn from_bytes_lazy<Data: Deref<Target = [u8]>>(
+        data: Data,
+        settings: FontSettings,
+    ) -> FontResult<Font> {
+        Self::from_bytes_internal(
+            &data,
+            Some(Arc::from(&*data)),
+            FontSettings {
+                lazy: true,
+                ..settings
+            },
+        )
+    }
 
-        // Collect all the unique codepoint to glyph mappings.
-        let glyph_count = face.number_of_glyphs();
-        let mut indices_to_load = HashSet::with_capacity(glyph_count as usize);
-        let mut char_to_glyph = HashMap::with_capacity(glyph_count as usize);
-        indices_to_load.insert(0u16);
-        if let Some(subtable) = face.tables().cmap {
-            for subtable in subtable.subtables {
-                subtable.codepoints(|codepoint| {
-                    if let Some(mapping) = subtable.glyph_index(codepoint) {
-                        if let Some(mapping) = NonZeroU16::new(mapping.0) {
-                            indices_to_load.insert(mapping.get());
-                            char_to_glyph.insert(unsafe { mem::transmute::<u32, char>(codepoint) }, mapping);
-                        }
-                    }
-                })
-            }
+    /// Constructs a font for every face in a TrueType collection. Faces that keep the font data
+    /// share one copy of it. Single fonts that aren't collections load as one face.
+    /// # Arguments
+    ///
+    /// * `data` - The font file's data.
+    /// * `settings` - The settings to load each face with. The collection index is ignored.
+    /// # Returns
+    ///
+    /// * `FontResult<Vec<Font>>` - Every face in the collection, in order. Returns an error if any
+    /// of the faces fails to load.
+    pub fn from_collection<Data: Deref<Target = [u8]>>(
+        data: Data,
+        settings: FontSettings,
+    ) -> FontResult<Vec<Font>> {
+        let count = collection_face_count(&data)?;
+        let shared: Option<Arc<[u8]>> = if settings.keeps_data() {
+            Some(Arc::from(&*data))
+        } else {
+            None
+        };
+        let mut fonts = Vec::with_capacity(count as usize);
+        for collection_index in 0..count {
+            let settings = FontSettings {
+                collection_index,
+                ..settings.clone()
+            };
+            fonts.push(Self::from_bytes_internal(&data, shared.clone(), settings)?);
         }
+        Ok(fonts)
+    }
 
-        // If the gsub table exists and the user needs it, add all of its glyphs to the glyphs we should load.
-        if settings.load_substitutions {
-            load_gsub(&face, &mut indices_to_load);
+    /// Serializes the parsed font, including its glyph geometry, so it can be cached and reloaded
+    /// quickly with `Font::deserialize()`. The format is only meant to be read by the same version
+    /// of fontdue, and is rejected by other versions.
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The serialized font. This includes a copy of the font data if the font keeps
+    /// it, see `FontSettings::keep_data`.
+    pub fn serialize(&self) -> Vec<u8> {
+        fn write_line_metrics(writer: &mut Writer, metrics: LineMetrics) {
+            for x in [metrics.ascent, metrics.descent, metrics.line_gap, metrics.new_line_size] {
+                writer.write_f32(x);
+            }
         }
 
-        let units_per_em = face.units_per_em() as f32;
+        let mut writer = Writer::new();
+        writer.write_option(self.data.as_deref(), |writer, data| writer.write_bytes(&data.data));
+        let settings = &self.settings;
+        writer.write_u32(settings.collection_index);
+        writer.write_f32(settings.scale);
+        writer.write_bool(settings.load_substitutions);
+        writer.write_len(settings.variations.len());
+        for (tag, value) in &settings.variations {
+            writer.write_u32(u32::from_be_bytes(*tag));
+            writer.write_f32(*value);
+        }
+        writer.write_f32(settings.synthetic_bold);
+        writer.write_f32(settings.synthetic_oblique);
+        writer.write_bool(settings.lazy);
+        writer.write_f32(settings.curve_tolerance);
+        writer.write_u8(settings.coverage_floor);
+        writer.write_bool(settings.antialias);
+        writer.write_bool(settings.allow_no_cmap);
+        writer.write_bool(settings.pixel_center);
+        writer.write_u8(match settings.kerning_source {
+            KerningSource::KernOnly => 0,
+            KerningSource::GposOnly => 1,
+            KerningSource::Prefer(KerningTable::Kern) => 2,
+            KerningSource::Prefer(KerningTable::Gpos) => 3,
+        });
+        writer.write_u8(match settings.line_metrics_source {
+            LineMetricsSource::Hhea => 0,
+            LineMetricsSource::Os2Typo => 1,
+            LineMetricsSource::Os2Win => 2,
+        });
+        writer.write_bool(settings.grid_fit == GridFit::Vertical);
+        writer.write_u64(settings.max_bitmap_pixels as u64);
+        writer.write_bool(settings.keep_data);
+        for name in [&self.name, &self.family_name, &self.style_name, &self.postscript_name] {
+            writer.write_option(name.as_deref(), |writer, name| writer.write_bytes(name.as_bytes()));
+        }
+        writer.write_f32(self.units_per_em);
+        writer.write_len(self.glyphs.len());
+        for glyph in &self.glyphs {
+            glyph.serialize(&mut writer);
+        }
+        writer.write_option(self.lazy_glyphs.as_ref(), |writer, lazy| {
+            writer.write_len(lazy.glyphs.len());
+            for slot in &lazy.glyphs {
+                writer.write_option(slot.get(), |writer, glyph| glyph.serialize(writer));
+            }
+        });
+        writer.write_len(self.loaded.len());
+        for &loaded in &self.loaded {
+            writer.write_bool(loaded);
+        }
+        writer.write_len(self.char_to_glyph.len());
+        for (&character, &index) in &self.char_to_glyph {
+            writer.write_u32(character as u32);
+            writer.write_u16(index.get());
+        }
+        writer.write_u16(self.default_glyph);
+        writer.write_option(self.horizontal_line_metrics, write_line_metrics);
+        writer.write_option(self.horizontal_kern.as_ref(), |writer, kern| {
+            writer.write_len(kern.len());
+            for (&pair, &value) in kern {
+                writer.write_u32(pair);
+                writer.write_i16(value);
+            }
+        });
+        writer.write_option(self.vertical_line_metrics, write_line_metrics);
+        writer.write_option(self.monospace_advance, Writer::write_f32);
+        writer.write_option(self.cap_height, Writer::write_f32);
+        writer.write_option(self.x_height, Writer::write_f32);
+        writer.write_f32(self.bounding_box.xmin);
+        writer.write_f32(self.bounding_box.ymin);
+        writer.write_f32(self.bounding_box.width);
+        writer.write_f32(self.bounding_box.height);
+        writer.write_bool(self.bold);
+        writer.write_bool(self.italic);
+        writer.write_len(self.variation_axes.len());
+        for axis in &self.variation_axes {
+            writer.write_u32(u32::from_be_bytes(axis.tag));
+            writer.write_f32(axis.min_value);
+            writer.write_f32(axis.default_value);
+            writer.write_f32(axis.max_value);
+            writer.write_bool(axis.hidden);
+        }
+        writer.write_u64(self.hash as u64);
+        writer.finish()
+    }
+
+    /// Constructs a font from data written by `Font::serialize()`, without parsing the font's
+    /// glyphs again. The data is checked as it's read, so malformed or tampered data is an error
+    /// rather than drawing outside of the bitmap.
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized font.
+    /// # Returns
+    ///
+    /// * `FontResult<Font>` - The font, or an error if the data was written by a different version
+    /// of fontdue or is malformed.
+    pub fn deserialize(bytes: &[u8]) -> FontResult<Font> {
+        fn read_line_metrics(reader: &mut Reader) -> Option<LineMetrics> {
+            Some(LineMetrics {
+                ascent: reader.read_f32()?,
+                descent: reader.read_f32()?,
+                line_gap: reader.read_f32()?,
+                new_line_size: reader.read_f32()?,
+            })
+        }
+        fn read_name(reader: &mut Reader) -> Option<Option<String>> {
+            reader.read_option(|reader| Some(String::from(core::str::from_utf8(reader.read_bytes()?).ok()?)))
+        }
+
+        let mut reader =
+            Reader::new(bytes).ok_or("The serialized font is from a different version of fontdue.")?;
+        let font = (|| {
+            let data: Option<Arc<[u8]>> =
+                reader.read_option(|reader| Some(Arc::from(reader.read_bytes()?)))?;
+            let settings = FontSettings {
+                collection_index: reader.read_u32()?,
+                scale: reader.read_f32()?,
+                load_substitutions: reader.read_bool()?,
+                variations: reader
+                    .read_vec(|reader| Some((reader.read_u32()?.to_be_bytes(), reader.read_f32()?)))?,
+                synthetic_bold: reader.read_f32()?,
+                synthetic_oblique: reader.read_f32()?,
+                lazy: reader.read_bool()?,
+                curve_tolerance: reader.read_f32()?,
+                coverage_floor: reader.read_u8()?,
+                antialias: reader.read_bool()?,
+                allow_no_cmap: reader.read_bool()?,
+                pixel_center: reader.read_bool()?,
+                kerning_source: match reader.read_u8()? {
+                    0 => KerningSource::KernOnly,
+                    1 => KerningSource::GposOnly,
+                    2 => KerningSource::Prefer(KerningTable::Kern),
+                    3 => KerningSource::Prefer(KerningTable::Gpos),
+                    _ => return None,
+                },
+                line_metrics_source: match reader.read_u8()? {
+                    0 => LineMetricsSource::Hhea,
+                    1 => LineMetricsSource::Os2Typo,
+                    2 => LineMetricsSource::Os2Win,
+                    _ => return None,
+                },
+                grid_fit: if reader.read_bool()? {
+                    GridFit::Vertical
+                } else {
+                    GridFit::None
+                },
+                max_bitmap_pixels: reader.read_u64()?.min(usize::MAX as u64) as usize,
+                keep_data: reader.read_bool()?,
+            };
+            let name = read_name(&mut reader)?;
+            let family_name = read_name(&mut reader)?;
+            let style_name = read_name(&mut reader)?;
+            let postscript_name = read_name(&mut reader)?;
+            let units_per_em = reader.read_f32()?;
+            let glyphs = reader.read_vec(Glyph::deserialize)?;
+            let lazy_glyphs = reader.read_option(|reader| {
+                let glyphs = reader.read_vec(|reader| {
+                    let slot = OnceBox::new();
+                    if let Some(glyph) = reader.read_option(Glyph::deserialize)? {
+                        slot.get_or_init(|| glyph);
+                    }
+                    Some(slot)
+                })?;
+                Some(LazyGlyphs {
+                    glyphs,
+                })
+            })?;
+            let loaded = reader.read_vec(Reader::read_bool)?;
+            let glyph_count = lazy_glyphs.as_ref().map_or(glyphs.len(), |lazy| lazy.glyphs.len());
+            let char_to_glyph = reader
+                .read_vec(|reader| {
+                    let character = char::from_u32(reader.read_u32()?)?;
+                    let index = NonZeroU16::new(reader.read_u16()?)?;
+                    if index.get() as usize >= glyph_count {
+                        return None;
+                    }
+                    Some((character, index))
+                })?
+                .into_iter()
+                .collect();
+            let default_glyph = reader.read_u16()?;
+            if default_glyph as usize >= glyph_count {
+                return None;
+            }
+            let horizontal_line_metrics = reader.read_option(read_line_metrics)?;
+            let horizontal_kern = reader.read_option(|reader| {
+                Some(
+                    reader
+                        .read_vec(|reader| Some((reader.read_u32()?, reader.read_i16()?)))?
+                        .into_iter()
+                        .collect(),
+                )
+            })?;
+            let vertical_line_metrics = reader.read_option(read_line_metrics)?;
+            let monospace_advance = reader.read_option(Reader::read_f32)?;
+            let cap_height = reader.read_option(Reader::read_f32)?;
+            let x_height = reader.read_option(Reader::read_f32)?;
+            let bounding_box = OutlineBounds {
+                xmin: reader.read_f32()?,
+                ymin: reader.read_f32()?,
+                width: reader.read_f32()?,
+                height: reader.read_f32()?,
+            };
+            let bold = reader.read_bool()?;
+            let italic = reader.read_bool()?;
+            let variation_axes = reader.read_vec(|reader| {
+                Some(AxisInfo {
+                    tag: reader.read_u32()?.to_be_bytes(),
+                    min_value: reader.read_f32()?,
+                    default_value: reader.read_f32()?,
+                    max_value: reader.read_f32()?,
+                    hidden: reader.read_bool()?,
+                })
+            })?;
+            let hash = reader.read_u64()? as usize;
+            if reader.remaining() != 0 {
+                return None;
+            }
+            // Glyphs are scaled by the units per em and looked up by index, so both have to match
+            // the font's if the data is kept. Fonts without it need to know which glyphs they have.
+            if data.is_some() != settings.keeps_data() {
+                return None;
+            }
+            let data = match data {
+                Some(data) => {
+                    let data = FontData::new(data, &settings).ok()?;
+                    let face = data.face();
+                    if units_per_em != face.units_per_em() as f32
+                        || glyph_count != face.number_of_glyphs() as usize
+                        || !loaded.is_empty()
+                    {
+                        return None;
+                    }
+                    Some(Arc::new(data))
+                }
+                None => {
+                    if !(16.0..=16384.0).contains(&units_per_em)
+                        || lazy_glyphs.is_some()
+                        || loaded.len() != glyph_count
+                    {
+                        return None;
+                    }
+                    None
+                }
+            };
+            Some(Font {
+                data,
+                loaded,
+                variation_axes,
+                name,
+                family_name,
+                style_name,
+                postscript_name,
+                units_per_em,
+                glyphs,
+                lazy_glyphs,
+                char_to_glyph,
+                glyph_to_char: OnceBox::new(),
+                default_glyph,
+                horizontal_line_metrics,
+                horizontal_kern,
+                vertical_line_metrics,
+                monospace_advance,
+                cap_height,
+                x_height,
+                bounding_box,
+                bold,
+                italic,
+                settings,
+                hash,
+            })
+        })();
+        font.ok_or("The serialized font is malformed.")
+    }
+
+    fn from_bytes_internal(
+        data: &[u8],
+        shared: Option<Arc<[u8]>>,
+        settings: FontSettings,
+    ) -> FontResult<Font> {
+        let lazy = settings.lazy;
+        if !(settings.curve_tolerance > 0.0) {
+            return Err("The curve tolerance must be positive.");
+        }
+        // Settings that change rasterized output or layout are mixed into the hash so glyph and
+        // layout caches keyed on it stay correct.
+        let mut hash = crate::hash::hash(data);
+        if settings.collection_index != 0 {
+            hash = crate::hash::hash_continue(hash, &settings.collection_index.to_le_bytes());
+        }
+        for &(tag, value) in &settings.variations {
+            hash = crate::hash::hash_continue(hash, &tag);
+            hash = crate::hash::hash_continue(hash, &value.to_bits().to_le_bytes());
+        }
+        if settings.has_synthetic_style() {
+            hash = crate::hash::hash_continue(hash, &settings.synthetic_bold.to_bits().to_le_bytes());
+            hash = crate::hash::hash_continue(hash, &settings.synthetic_oblique.to_bits().to_le_bytes());
+        }
+        if settings.curve_tolerance != DEFAULT_CURVE_TOLERANCE {
+            hash = crate::hash::hash_continue(hash, &settings.curve_tolerance.to_bits().to_le_bytes());
+        }
+        if settings.coverage_floor != 0 {
+            hash = crate::hash::hash_continue(hash, &[settings.coverage_floor]);
+        }
+        if !settings.antialias {
+            hash = crate::hash::hash_continue(hash, b"aliased");
+        }
+        if settings.pixel_center {
+            hash = crate::hash::hash_continue(hash, b"pixel center");
+        }
+        if settings.grid_fit == GridFit::Vertical {
+            hash = crate::hash::hash_continue(hash, b"grid fit");
+        }
+        match settings.kerning_source {
+            KerningSource::Prefer(KerningTable::Gpos) => {}
+            KerningSource::Prefer(KerningTable::Kern) => {
+                hash = crate::hash::hash_continue(hash, b"prefer kern")
+            }
+            KerningSource::KernOnly => hash = crate::hash::hash_continue(hash, b"kern only"),
+            KerningSource::GposOnly => hash = crate::hash::hash_continue(hash, b"gpos only"),
+        }
+        match settings.line_metrics_source {
+            LineMetricsSource::Hhea => {}
+            LineMetricsSource::Os2Typo => hash = crate::hash::hash_continue(hash, b"os2 typo metrics"),
+            LineMetricsSource::Os2Win => hash = crate::hash::hash_continue(hash, b"os2 win metrics"),
+        }
+        // Distance fields, strokes, and color and rotated glyphs are drawn from the font data.
+        if !settings.keeps_data() {
+            hash = crate::hash::hash_continue(hash, b"no data");
+        }
+
+        // Fonts that keep their data parse the face from their copy, so it's only parsed once.
+        let font_data = match shared {
+            Some(shared) => Some(Arc::new(FontData::new(shared, &settings).map_err(convert_error)?)),
+            None => None,
+        };
+        let parsed;
+        let face = match &font_data {
+            Some(font_data) => font_data.face(),
+            None => {
+                parsed = parse_face(data, &settings).map_err(convert_error)?;
+                &parsed
+            }
+        };
+        let variation_axes = face
+            .variation_axes()
+            .into_iter()
+            .map(|axis| AxisInfo {
+                tag: axis.tag.to_bytes(),
+                min_value: axis.min_value,
+                default_value: axis.def_value,
+                max_value: axis.max_value,
+                hidden: axis.hidden,
+            })
+            .collect();
+        let name = convert_name(&face, name_id::FULL_NAME);
+        let family_name = convert_name(&face, name_id::FAMILY);
+        let style_name = convert_name(&face, name_id::SUBFAMILY);
+        let postscript_name = convert_name(&face, name_id::POST_SCRIPT_NAME);
 
-        // Parse and store all unique codepoints.
-        let mut glyphs: Vec<Glyph> = vec::from_elem(Glyph::default(), glyph_count as usize);
+        // Collect all the unique codepoint to glyph mappings.
+        let glyph_count = face.number_of_glyphs();
+        let mut indices_to_load = HashSet::new();
+        if !lazy {
+            indices_to_load.reserve(glyph_count as usize);
+        }
+        let mut char_to_glyph = HashMap::with_capacity(glyph_count as usize);
+        indices_to_load.insert(0u16);
+        if let Some(subtable) = face.tables().cmap {
+            for subtable in subtable.subtables {
+                // Symbol subtables map either the first 256 codepoints or the same codepoints moved
+                // up into the private use area at U+F000. Both ranges are mapped, without replacing
+                // mappings from other subtables.
+                let symbol = subtable.platform_id == PlatformId::Windows && subtable.encoding_id == 0;
+                subtable.codepoints(|codepoint| {
+                    if let Some(mapping) = subtable.glyph_index(codepoint) {
+                        if let Some(mapping) = NonZeroU16::new(mapping.0) {
+                            if !lazy {
+                                indices_to_load.insert(mapping.get());
+                            }
+                            char_to_glyph.insert(unsafe { char::from_u32_unchecked(codepoint) }, mapping);
+                            if symbol {
+                                let alias = match codepoint {
+                                    0x0000..=0x00FF => codepoint + 0xF000,
+                                    0xF000..=0xF0FF => codepoint - 0xF000,
+                                    _ => return,
+                                };
+                                char_to_glyph
+                                    .entry(unsafe { char::from_u32_unchecked(alias) })
+                                    .or_insert(mapping);
+                            }
+                        }
+                    }
+                })
+            }
+        }
 
-        let generate_glyph = |index: u16| -> Result<Glyph, &'static str> {
-            if index >= glyph_count {
-                return Err("Attempted to map a codepoint out of bounds.");
+        // Fonts without a character map can only be used by glyph index, so every glyph is loaded
+        // if allowed.
+        let load_every_glyph = char_to_glyph.is_empty() && settings.allow_no_cmap;
+        if load_every_glyph {
+            hash = crate::hash::hash_continue(hash, b"every glyph");
+            if !lazy {
+                indices_to_load.extend(0..glyph_count);
             }
+        }
 
-            let mut glyph = Glyph::default();
-            let glyph_id = GlyphId(index);
-            if let Some(advance_width) = face.glyph_hor_advance(glyph_id) {
-                glyph.advance_width = advance_width as f32;
+        // Fonts that don't declare themselves fixed pitch are checked by comparing the advances of
+        // printable ASCII characters, or every mapped character if the font has none of them. The
+        // advances may be off by a unit or two from rounding in the font's metrics. Fixed pitch
+        // fonts may have wide glyphs spanning multiple cells, so the common advance is the
+        // narrowest non-zero advance in the sample.
+        let monospace_advance = {
+            let mut sample: Vec<u16> =
+                (' '..='~').filter_map(|c| char_to_glyph.get(&c)).map(|glyph| glyph.get()).collect();
+            if sample.is_empty() {
+                sample = char_to_glyph.values().map(|glyph| glyph.get()).collect();
+            }
+            let mut narrowest = u16::MAX;
+            let mut widest = 0;
+            for glyph in sample {
+                let advance = face.glyph_hor_advance(GlyphId(glyph)).unwrap_or(0);
+                if advance != 0 {
+                    narrowest = narrowest.min(advance);
+                    widest = widest.max(advance);
+                }
             }
-            if let Some(advance_height) = face.glyph_ver_advance(glyph_id) {
-                glyph.advance_height = advance_height as f32;
+            if narrowest <= widest
+                && (face.is_monospaced() || widest - narrowest <= face.units_per_em() / 100)
+            {
+                Some(narrowest as f32 + 2.0 * settings.synthetic_bold * face.units_per_em() as f32)
+            } else {
+                None
             }
+        };
+
+        // Fonts without these in their OS/2 table, or with unset values, are measured from their
+        // glyphs instead.
+        let cap_height = face.capital_height().filter(|&height| height > 0).map(f32::from);
+        let x_height = face.x_height().filter(|&height| height > 0).map(f32::from);
+        let rect = face.global_bounding_box();
+        let bounding_box = settings.synthesize_bounds(
+            OutlineBounds {
+                xmin: rect.x_min as f32,
+                ymin: rect.y_min as f32,
+                width: rect.width() as f32,
+                height: rect.height() as f32,
+            },
+            face.units_per_em() as f32,
+        );
 
-            let mut geometry = Geometry::new(settings.scale, units_per_em);
-            face.outline_glyph(glyph_id, &mut geometry);
-            geometry.finalize(&mut glyph);
-            Ok(glyph)
+        // The OS/2 table's fsSelection flags are preferred for the style, falling back to the head
+        // table's macStyle flags for fonts without one.
+        let (bold, italic) = if face.tables().os2.is_some() {
+            (face.is_bold(), face.style() != ttf_parser::Style::Normal)
+        } else {
+            let mac_style = (|| {
+                let mut stream = Stream::new(face.raw_face().table(Tag::from_bytes(&b"head"))?);
+                stream.seek(44);
+                stream.read_u16()
+            })()
+            .unwrap_or(0);
+            (mac_style & 0x0001 != 0, mac_style & 0x0002 != 0)
         };
 
-        #[cfg(not(feature = "parallel"))]
-        for index in indices_to_load {
-            glyphs[index as usize] = generate_glyph(index)?;
+        // Optionally get kerning values for the font. This should be a try block in the future.
+        let kern_kern: Option<HashMap<u32, i16>> = (|| {
+            if settings.kerning_source == KerningSource::GposOnly {
+                return None;
+            }
+            let table: &[u8] = face.raw_face().table(Tag::from_bytes(&b"kern"))?;
+            let table: TableKern = TableKern::new(table)?;
+            Some(table.horizontal_mappings)
+        })();
+        let gpos_kern: Option<HashMap<u32, i16>> = (|| {
+            if settings.kerning_source == KerningSource::KernOnly {
+                return None;
+            }
+            let table: &[u8] = face.raw_face().table(Tag::from_bytes(&b"GPOS"))?;
+            let mut mapped: Vec<u16> = if load_every_glyph {
+                (0..glyph_count).collect()
+            } else {
+                char_to_glyph.values().map(|glyph| glyph.get()).collect()
+            };
+            mapped.sort_unstable();
+            mapped.dedup();
+            let table: TableGpos = TableGpos::new(table, &mapped)?;
+            Some(table.horizontal_mappings)
+        })();
+        // Pairs in both tables use the value from the preferred table, which is extended last.
+        let (first, preferred) = match settings.kerning_source {
+            KerningSource::Prefer(KerningTable::Kern) => (gpos_kern, kern_kern),
+            _ => (kern_kern, gpos_kern),
+        };
+        let horizontal_kern = match (first, preferred) {
+            (Some(mut first), Some(preferred)) => {
+                first.extend(preferred);
+                Some(first)
+            }
+            (first, preferred) => preferred.or(first),
+        };
+
+        // If the gsub table exists and the user needs it, add all of its glyphs to the glyphs we should load.
+        if settings.load_substitutions && !lazy {
+            load_gsub(&face, &mut indices_to_load);
         }
 
-        #[cfg(feature = "parallel")]
-        {
-            let generated: Vec<(u16, Glyph)> = indices_to_load
-                .into_par_iter()
-                .map(|index| Ok((index, generate_glyph(index)?)))
-                .collect::<Result<_, _>>()?;
-            for (index, glyph) in generated {
-                glyphs[index as usize] = glyph;
+        // Color glyphs are drawn from other glyphs in the font, which may not be mapped.
+        if !lazy {
+            load_colr(&face, &mut indices_to_load);
+        }
+
+        let units_per_em = face.units_per_em() as f32;
+
+        // Lazy fonts generate glyphs on demand, so there's nothing more to parse here.
+        let mut glyphs: Vec<Glyph> = Vec::new();
+        let mut lazy_glyphs = None;
+        let mut loaded = Vec::new();
+        if lazy {
+            let mut slots = Vec::with_capacity(glyph_count as usize);
+            slots.resize_with(glyph_count as usize, OnceBox::new);
+            lazy_glyphs = Some(LazyGlyphs {
+                glyphs: slots,
+            });
+        } else {
+            // Parse and store all unique codepoints.
+            glyphs = vec::from_elem(Glyph::default(), glyph_count as usize);
+            if font_data.is_none() {
+                loaded = vec::from_elem(false, glyph_count as usize);
+                for &index in &indices_to_load {
+                    if let Some(slot) = loaded.get_mut(index as usize) {
+                        *slot = true;
+                    }
+                }
+            }
+
+            let load_glyph = |index: u16| -> Result<Glyph, &'static str> {
+                if index >= glyph_count {
+                    return Err("Attempted to map a codepoint out of bounds.");
+                }
+                Ok(generate_glyph(&face, index, &settings, units_per_em))
+            };
+
+            #[cfg(not(feature = "parallel"))]
+            for index in indices_to_load {
+                glyphs[index as usize] = load_glyph(index)?;
+            }
+
+            #[cfg(feature = "parallel")]
+            {
+                let generated: Vec<(u16, Glyph)> = indices_to_load
+                    .into_par_iter()
+                    .map(|index| Ok((index, load_glyph(index)?)))
+                    .collect::<Result<_, _>>()?;
+                for (index, glyph) in generated {
+                    glyphs[index as usize] = glyph;
+                }
             }
         }
 
         // New line metrics.
-        let horizontal_line_metrics =
-            Some(LineMetrics::new(face.ascender(), face.descender(), face.line_gap()));
+        let horizontal_line_metrics = match settings.line_metrics_source {
+            LineMetricsSource::Hhea => None,
+            LineMetricsSource::Os2Typo => (|| {
+                let (ascender, descender) = (face.typographic_ascender()?, face.typographic_descender()?);
+                Some(LineMetrics::new(ascender, descender, face.typographic_line_gap()?))
+            })(),
+            LineMetricsSource::Os2Win => (|| {
+                let os2 = face.tables().os2?;
+                Some(LineMetrics::new(os2.windows_ascender(), os2.windows_descender(), 0))
+            })(),
+        }
+        .or_else(|| Some(LineMetrics::new(face.ascender(), face.descender(), face.line_gap())));
         let vertical_line_metrics = if let Some(ascender) = face.vertical_ascender() {
             Some(LineMetrics::new(
                 ascender,
@@ -333,13 +1762,28 @@ impl Font {
         };
 
         Ok(Font {
+            data: font_data,
+            loaded,
+            variation_axes,
             name,
+            family_name,
+            style_name,
+            postscript_name,
             glyphs,
+            lazy_glyphs,
             char_to_glyph,
+            glyph_to_char: OnceBox::new(),
+            default_glyph: 0,
             units_per_em,
             horizontal_line_metrics,
             horizontal_kern,
             vertical_line_metrics,
+            monospace_advance,
+            cap_height,
+            x_height,
+            bounding_box,
+            bold,
+            italic,
             settings,
             hash,
         })
@@ -351,6 +1795,36 @@ impl Font {
         self.name.as_deref()
     }
 
+    /// Returns the font's family name if it has one, like "Roboto". It is from `Name ID 1` (Font
+    /// Family name) in the name table.
+    pub fn family_name(&self) -> Option<&str> {
+        self.family_name.as_deref()
+    }
+
+    /// Returns the font's style name within its family if it has one, like "Bold Italic". It is from
+    /// `Name ID 2` (Font Subfamily name) in the name table.
+    pub fn style_name(&self) -> Option<&str> {
+        self.style_name.as_deref()
+    }
+
+    /// Returns the font's PostScript name if it has one, like "Roboto-BoldItalic". It is from
+    /// `Name ID 6` (PostScript name) in the name table.
+    pub fn postscript_name(&self) -> Option<&str> {
+        self.postscript_name.as_deref()
+    }
+
+    /// Returns true if the font is marked as bold. This is from the fsSelection flags in the OS/2
+    /// table, or the macStyle flags in the head table if the font doesn't have an OS/2 table.
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    /// Returns true if the font is marked as italic or oblique. This is from the fsSelection flags in
+    /// the OS/2 table, or the macStyle flags in the head table if the font doesn't have an OS/2 table.
+    pub fn is_italic(&self) -> bool {
+        self.italic
+    }
+
     /// Returns all valid unicode codepoints that have mappings to glyph geometry in the font, along
     /// with their associated index. This does not include grapheme cluster mappings. The mapped
     /// NonZeroU16 index can be used in the _indexed font functions.
@@ -358,11 +1832,84 @@ impl Font {
         &self.char_to_glyph
     }
 
-    /// Returns a precomputed hash for the font file.
+    /// Returns the same mappings as `chars`, sorted by codepoint. The iteration order of `chars`
+    /// isn't specified, so this is useful for listing a font's coverage the same way every time.
+    pub fn sorted_chars(&self) -> Vec<(char, u16)> {
+        let mut chars: Vec<(char, u16)> =
+            self.char_to_glyph.iter().map(|(&character, index)| (character, index.get())).collect();
+        chars.sort_unstable_by_key(|&(character, _)| character);
+        chars
+    }
+
+    /// Returns a precomputed hash for the font file, and the variation coordinates and synthesized
+    /// styles it was loaded with if there are any. Character mapping overrides made with
+    /// `Font::override_glyph` change the hash.
     pub fn file_hash(&self) -> usize {
         self.hash
     }
 
+    /// Returns the variation axes of the font, or an empty list if it isn't a variable font.
+    pub fn variation_axes(&self) -> Vec<AxisInfo> {
+        self.variation_axes.clone()
+    }
+
+    /// Returns an estimate of the total memory used by the font in bytes. This includes the glyph
+    /// geometry, the character and kerning maps, and the copy of the font data if it's kept. Hash
+    /// map sizes are approximated from their capacity.
+    pub fn memory_usage(&self) -> usize {
+        fn map_size<K, V>(map: &HashMap<K, V>) -> usize {
+            map.capacity() * (mem::size_of::<K>() + mem::size_of::<V>() + 1)
+        }
+
+        let mut total = mem::size_of::<Font>();
+        total += self.data.as_ref().map_or(0, |data| mem::size_of::<FontData>() + data.data.len());
+        total += self.loaded.capacity() + self.variation_axes.capacity() * mem::size_of::<AxisInfo>();
+        for name in [&self.name, &self.family_name, &self.style_name, &self.postscript_name] {
+            total += name.as_ref().map_or(0, |name| name.capacity());
+        }
+        total += self.glyphs.capacity() * mem::size_of::<Glyph>();
+        total += self.glyphs.iter().map(Glyph::heap_size).sum::<usize>();
+        if let Some(lazy) = &self.lazy_glyphs {
+            total += lazy.glyphs.capacity() * mem::size_of::<OnceBox<Glyph>>();
+            for slot in &lazy.glyphs {
+                if let Some(glyph) = slot.get() {
+                    total += mem::size_of::<Glyph>() + glyph.heap_size();
+                }
+            }
+        }
+        total += map_size(&self.char_to_glyph);
+        total +=
+            self.glyph_to_char.get().map_or(0, |map| mem::size_of::<HashMap<u16, char>>() + map_size(map));
+        total += self.horizontal_kern.as_ref().map_or(0, map_size);
+        total
+    }
+
+    /// Returns the number of bytes used by the geometry of the glyph at the given index. Glyphs in
+    /// lazily loaded fonts that haven't been generated yet use 0 bytes.
+    pub fn glyph_memory(&self, index: u16) -> usize {
+        let glyph = match &self.lazy_glyphs {
+            Some(lazy) => lazy.glyphs.get(index as usize).and_then(OnceBox::get),
+            None => self.glyphs.get(index as usize),
+        };
+        glyph.map_or(0, |glyph| mem::size_of::<Glyph>() + glyph.heap_size())
+    }
+
+    /// Generates the geometry of the glyphs for the given characters ahead of time. Lazily loaded
+    /// fonts otherwise generate each glyph the first time it's used, so this moves that cost to a
+    /// predictable point like startup. This does nothing for fonts that aren't lazily loaded.
+    /// # Arguments
+    ///
+    /// * `chars` - The characters to generate glyphs for. Characters missing from the font
+    /// generate the font's default glyph.
+    pub fn preload<I: IntoIterator<Item = char>>(&self, chars: I) {
+        if self.lazy_glyphs.is_none() {
+            return;
+        }
+        for character in chars {
+            self.glyph(self.lookup_glyph_index(character));
+        }
+    }
+
     /// New line metrics for fonts that append characters to lines horizontally, and append new
     /// lines vertically (above or below the current line). Only populated for fonts with the
     /// appropriate metrics, none if it's missing.
@@ -387,12 +1934,54 @@ impl Font {
         Some(metrics.scale(self.scale_factor(px)))
     }
 
+    /// The same as `horizontal_line_metrics`, but in the font's design units instead of pixels. See
+    /// `units_per_em` for the size of the em in these units.
+    #[inline]
+    pub fn horizontal_line_metrics_unscaled(&self) -> Option<LineMetrics> {
+        self.horizontal_line_metrics
+    }
+
+    /// The same as `vertical_line_metrics`, but in the font's design units instead of pixels. See
+    /// `units_per_em` for the size of the em in these units.
+    #[inline]
+    pub fn vertical_line_metrics_unscaled(&self) -> Option<LineMetrics> {
+        self.vertical_line_metrics
+    }
+
     /// Gets the font's units per em.
     #[inline(always)]
     pub fn units_per_em(&self) -> f32 {
         self.units_per_em
     }
 
+    /// Gets the size glyph curves were flattened for, from `FontSettings::scale`. Glyphs look best
+    /// rasterized near this size. Far above it, curves look faceted since they were flattened into
+    /// too few lines, and far below it, rasterizing is slower than it needs to be since there are
+    /// more lines than the size needs, until the coarse geometry takes over at a quarter of it.
+    /// The units of the scale are pixels per Em unit.
+    #[inline(always)]
+    pub fn optimized_scale(&self) -> f32 {
+        self.settings.scale
+    }
+
+    /// Counts the lines the glyph at the given index was flattened into, for diagnosing faceted
+    /// curves. Raising `FontSettings::scale` or lowering `FontSettings::curve_tolerance` gives
+    /// curves more lines.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font.
+    /// # Returns
+    ///
+    /// * `usize` - The number of lines in the glyph's geometry, or 0 for blank glyphs and indices
+    /// that aren't in the font.
+    pub fn flattening_segments(&self, index: u16) -> usize {
+        if index >= self.glyph_count() {
+            return 0;
+        }
+        let glyph = self.glyph(index);
+        glyph.v_lines.len() + glyph.m_lines.len()
+    }
+
     /// Calculates the glyph's outline scale factor for a given px size. The units of the scale are
     /// pixels per Em unit.
     #[inline(always)]
@@ -436,162 +2025,1157 @@ impl Font {
         Some((*value as f32) * scale)
     }
 
-    /// Retrieves the layout metrics for the given character. If the character isn't present in the
-    /// font, then the layout for the font's default character is returned instead.
+    /// Sums the horizontal kerning between every pair of adjacent characters in the text. Each
+    /// character is only looked up once.
     /// # Arguments
     ///
-    /// * `index` - The character in the font to to generate the layout metrics for.
-    /// * `px` - The size to generate the layout metrics for the character at. Cannot be negative.
-    /// The units of the scale are pixels per Em unit.
+    /// * `text` - The text to sum the kerning of.
+    /// * `px` - The size to scale the kerning values for. The units of the scale are pixels per Em
+    /// unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the glyph.
-    #[inline]
-    pub fn metrics(&self, character: char, px: f32) -> Metrics {
-        self.metrics_indexed(self.lookup_glyph_index(character), px)
+    /// * `f32` - The total scaled kerning of the text. Pairs without kerning count as 0.
+    pub fn total_kern(&self, text: &str, px: f32) -> f32 {
+        let mut total = 0.0;
+        let mut chars = text.chars().map(|character| self.lookup_glyph_index(character));
+        if let Some(mut left) = chars.next() {
+            for right in chars {
+                total += self.horizontal_kern_indexed(left, right, px).unwrap_or(0.0);
+                left = right;
+            }
+        }
+        total
     }
 
-    /// Retrieves the layout metrics at the given index. You normally want to be using
-    /// metrics(char, f32) instead, unless your glyphs are pre-indexed.
+    /// Retrieves the scaled horizontal advance of a glyph, plus the kerning between it and the
+    /// glyph before it. This is equivalent to summing the `advance_width` from `metrics_indexed`
+    /// and the value from `horizontal_kern_indexed`, but skips computing the glyph's bounds and
+    /// only scales once, which is useful in tight layout loops.
     /// # Arguments
     ///
-    /// * `index` - The glyph index in the font to to generate the layout metrics for.
-    /// * `px` - The size to generate the layout metrics for the glyph at. Cannot be negative. The
-    /// units of the scale are pixels per Em unit.
+    /// * `prev` - The glyph index on the left hand side of the pairing.
+    /// * `cur` - The glyph index to get the advance of, on the right hand side of the pairing.
+    /// * `px` - The size to scale the advance and kerning values for. The units of the scale are
+    /// pixels per Em unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the glyph.
-    pub fn metrics_indexed(&self, index: u16, px: f32) -> Metrics {
-        let glyph = &self.glyphs[index as usize];
-        let scale = self.scale_factor(px);
-        let (metrics, _, _) = self.metrics_raw(scale, glyph, 0.0);
-        metrics
-    }
-
-    /// Internal function to generate the metrics, offset_x, and offset_y of the glyph.
-    fn metrics_raw(&self, scale: f32, glyph: &Glyph, offset: f32) -> (Metrics, f32, f32) {
-        let bounds = glyph.bounds.scale(scale);
-        let mut offset_x = fract(bounds.xmin + offset);
-        let mut offset_y = fract(1.0 - fract(bounds.height) - fract(bounds.ymin));
-        if is_negative(offset_x) {
-            offset_x += 1.0;
-        }
-        if is_negative(offset_y) {
-            offset_y += 1.0;
+    /// * `f32` - The scaled horizontal advance of `cur`, adjusted by kerning if the font has a
+    /// value for the given pair.
+    #[inline]
+    pub fn advance_and_kern_indexed(&self, prev: u16, cur: u16, px: f32) -> f32 {
+        let mut advance = self.glyph(cur).advance_width;
+        if let Some(map) = &self.horizontal_kern {
+            if let Some(value) = map.get(&(u32::from(prev) << 16 | u32::from(cur))) {
+                advance += *value as f32;
+            }
         }
-        let metrics = Metrics {
-            xmin: as_i32(floor(bounds.xmin)),
-            ymin: as_i32(floor(bounds.ymin)),
-            width: as_i32(ceil(bounds.width + offset_x)) as usize,
-            height: as_i32(ceil(bounds.height + offset_y)) as usize,
-            advance_width: scale * glyph.advance_width,
-            advance_height: scale * glyph.advance_height,
-            bounds,
-        };
-        (metrics, offset_x, offset_y)
+        advance * self.scale_factor(px)
     }
 
-    /// Retrieves the layout rasterized bitmap for the given raster config. If the raster config's
-    /// character isn't present in the font, then the layout and bitmap for the font's default
-    /// character's raster is returned instead.
+    /// Retrieves the horizontal advance width for the given character. If the character isn't
+    /// present in the font, then the advance for the font's default character is returned instead.
+    /// This is the same as the `advance_width` from `metrics`, without computing the glyph's
+    /// bounds.
     /// # Arguments
     ///
-    /// * `config` - The settings to render the character at.
+    /// * `character` - The character in the font to get the advance width of.
+    /// * `px` - The size to scale the advance width for. The units of the scale are pixels per Em
+    /// unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
-    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
-    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
-    /// the top left corner of the glyph.
+    /// * `f32` - The scaled horizontal advance width of the glyph.
     #[inline]
-    pub fn rasterize_config(&self, config: GlyphRasterConfig) -> (Metrics, Vec<u8>) {
-        self.rasterize_indexed(config.glyph_index, config.px)
+    pub fn advance_width(&self, character: char, px: f32) -> f32 {
+        self.advance_width_indexed(self.lookup_glyph_index(character), px)
     }
 
-    /// Retrieves the layout metrics and rasterized bitmap for the given character. If the
-    /// character isn't present in the font, then the layout and bitmap for the font's default
-    /// character is returned instead.
+    /// Retrieves the horizontal advance width at the given index. You normally want to be using
+    /// advance_width(char, f32) instead, unless your glyphs are pre-indexed.
     /// # Arguments
     ///
-    /// * `character` - The character to rasterize.
-    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
-    /// are pixels per Em unit.
+    /// * `index` - The glyph index in the font to get the advance width of.
+    /// * `px` - The size to scale the advance width for. The units of the scale are pixels per Em
+    /// unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
-    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
-    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
-    /// the top left corner of the glyph.
+    /// * `f32` - The scaled horizontal advance width of the glyph.
     #[inline]
-    pub fn rasterize(&self, character: char, px: f32) -> (Metrics, Vec<u8>) {
-        self.rasterize_indexed(self.lookup_glyph_index(character), px)
+    pub fn advance_width_indexed(&self, index: u16, px: f32) -> f32 {
+        self.scale_factor(px) * self.glyph(index).advance_width
     }
 
-    /// Retrieves the layout rasterized bitmap for the given raster config. If the raster config's
-    /// character isn't present in the font, then the layout and bitmap for the font's default
-    /// character's raster is returned instead.
-    ///
-    /// This will perform the operation with the width multiplied by 3, as to simulate subpixels.
-    /// Taking these as RGB values will perform subpixel anti aliasing.
+    /// Measures a single line of text without laying it out. This sums the advance widths of the
+    /// text's characters, including the kerning between adjacent characters, and finds the
+    /// tallest glyph. Characters aren't shaped or wrapped, and new lines are measured like any
+    /// other character, so use `Layout` for anything more involved.
     /// # Arguments
     ///
-    /// * `config` - The settings to render the character at.
+    /// * `text` - The text to measure.
+    /// * `px` - The size to measure the text at. The units of the scale are pixels per Em unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
-    /// * `Vec<u8>` - Swizzled RGB coverage vector for the glyph. Coverage is a linear scale where 0
-    /// represents 0% coverage of that subpixel by the glyph and 255 represents 100% coverage. The
-    /// vec starts at the top left corner of the glyph.
+    /// * `(f32, f32)` - The total advance width of the text, and the height of its tallest glyph's
+    /// outline.
     #[inline]
-    pub fn rasterize_config_subpixel(&self, config: GlyphRasterConfig) -> (Metrics, Vec<u8>) {
-        self.rasterize_indexed_subpixel(config.glyph_index, config.px)
+    pub fn measure(&self, text: &str, px: f32) -> (f32, f32) {
+        self.measure_with_kerning(text, px, true)
     }
 
-    /// Retrieves the layout metrics and rasterized bitmap for the given character. If the
-    /// character isn't present in the font, then the layout and bitmap for the font's default
-    /// character is returned instead.
+    /// Measures a single line of text without laying it out, the same as `measure`, optionally
+    /// leaving out the kerning between adjacent characters.
+    /// # Arguments
     ///
-    /// This will perform the operation with the width multiplied by 3, as to simulate subpixels.
-    /// Taking these as RGB values will perform subpixel anti aliasing.
+    /// * `text` - The text to measure.
+    /// * `px` - The size to measure the text at. The units of the scale are pixels per Em unit.
+    /// * `kerning` - If the kerning between adjacent characters is included in the width. Rendered
+    /// text is usually kerned, so this should only be disabled when it's rendered without kerning.
+    /// # Returns
+    ///
+    /// * `(f32, f32)` - The total advance width of the text, and the height of its tallest glyph's
+    /// outline.
+    pub fn measure_with_kerning(&self, text: &str, px: f32, kerning: bool) -> (f32, f32) {
+        let scale = self.scale_factor(px);
+        let mut width = 0.0;
+        let mut height: f32 = 0.0;
+        let mut prev = None;
+        for character in text.chars() {
+            let index = self.lookup_glyph_index(character);
+            width += match prev {
+                Some(prev) if kerning => self.advance_and_kern_indexed(prev, index, px),
+                _ => self.advance_width_indexed(index, px),
+            };
+            height = height.max(self.glyph(index).bounds.height * scale);
+            prev = Some(index);
+        }
+        (width, height)
+    }
+
+    /// Maps each character of the text to a glyph, with its advance and the kerning before it.
+    /// This is a simple form of shaping for renderers that do their own layout: there are no
+    /// substitutions, and characters missing from the font use the font's default glyph.
     /// # Arguments
     ///
-    /// * `character` - The character to rasterize.
-    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
-    /// are pixels per Em unit.
+    /// * `text` - The text to shape.
+    /// * `px` - The size to shape the text at. The units of the scale are pixels per Em unit.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
-    /// * `Vec<u8>` - Swizzled RGB coverage vector for the glyph. Coverage is a linear scale where 0
-    /// represents 0% coverage of that subpixel by the glyph and 255 represents 100% coverage. The
-    /// vec starts at the top left corner of the glyph.
+    /// * `Vec<ShapedGlyph>` - A glyph for each character in the text, in order.
     #[inline]
-    pub fn rasterize_subpixel(&self, character: char, px: f32) -> (Metrics, Vec<u8>) {
-        self.rasterize_indexed_subpixel(self.lookup_glyph_index(character), px)
+    pub fn shape_simple(&self, text: &str, px: f32) -> Vec<ShapedGlyph> {
+        self.shape_simple_with_kerning(text, px, true)
     }
 
-    /// Retrieves the layout metrics and rasterized bitmap at the given index. You normally want to
-    /// be using rasterize(char, f32) instead, unless your glyphs are pre-indexed.
+    /// Maps each character of the text to a glyph, the same as `shape_simple`, optionally leaving
+    /// out the kerning between adjacent characters.
     /// # Arguments
     ///
-    /// * `index` - The glyph index in the font to rasterize.
-    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
-    /// are pixels per Em unit.
+    /// * `text` - The text to shape.
+    /// * `px` - The size to shape the text at. The units of the scale are pixels per Em unit.
+    /// * `kerning` - If the kerning before each glyph is included as its `x_offset`. When disabled,
+    /// every offset is 0.
     /// # Returns
     ///
-    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
-    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
-    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
-    /// the top left corner of the glyph.
-    pub fn rasterize_indexed(&self, index: u16, px: f32) -> (Metrics, Vec<u8>) {
+    /// * `Vec<ShapedGlyph>` - A glyph for each character in the text, in order.
+    pub fn shape_simple_with_kerning(&self, text: &str, px: f32, kerning: bool) -> Vec<ShapedGlyph> {
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut prev = None;
+        for (cluster, character) in text.char_indices() {
+            let glyph_index = self.lookup_glyph_index(character);
+            let x_offset =
+                prev.filter(|_| kerning).and_then(|prev| self.horizontal_kern_indexed(prev, glyph_index, px));
+            glyphs.push(ShapedGlyph {
+                glyph_index,
+                advance: self.advance_width_indexed(glyph_index, px),
+                x_offset: x_offset.unwrap_or(0.0),
+                cluster,
+            });
+            prev = Some(glyph_index);
+        }
+        glyphs
+    }
+
+    /// Returns true if the font's characters share the same advance width. This is true for fonts
+    /// that declare themselves fixed pitch in the post table, or otherwise when the font's printable
+    /// ASCII characters all have the same advance.
+    pub fn is_monospaced(&self) -> bool {
+        self.monospace_advance.is_some()
+    }
+
+    /// Retrieves the common horizontal advance width of a monospaced font, for sizing a character
+    /// grid. Fixed pitch fonts with wide glyphs spanning multiple cells return the width of a
+    /// single cell.
+    /// # Arguments
+    ///
+    /// * `px` - The size to scale the advance width for. The units of the scale are pixels per Em
+    /// unit.
+    /// # Returns
+    ///
+    /// * `Option<f32>` - The scaled common advance width, or none if the font isn't monospaced.
+    pub fn monospace_advance(&self, px: f32) -> Option<f32> {
+        Some(self.monospace_advance? * self.scale_factor(px))
+    }
+
+    /// Retrieves the height of capital letters above the baseline, which is useful for aligning
+    /// text to its capitals rather than its ascent. This is from the OS/2 table, or measured from
+    /// the top of the 'H' glyph if the font doesn't specify it.
+    /// # Arguments
+    ///
+    /// * `px` - The size to scale the cap height for. The units of the scale are pixels per Em
+    /// unit.
+    /// # Returns
+    ///
+    /// * `Option<f32>` - The scaled cap height, or none if the font doesn't specify it and has no
+    /// 'H' glyph.
+    pub fn cap_height(&self, px: f32) -> Option<f32> {
+        Some(self.design_height(self.cap_height, 'H')? * self.scale_factor(px))
+    }
+
+    /// Retrieves the height of lowercase letters above the baseline. This is from the OS/2 table,
+    /// or measured from the top of the 'x' glyph if the font doesn't specify it.
+    /// # Arguments
+    ///
+    /// * `px` - The size to scale the x height for. The units of the scale are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Option<f32>` - The scaled x height, or none if the font doesn't specify it and has no
+    /// 'x' glyph.
+    pub fn x_height(&self, px: f32) -> Option<f32> {
+        Some(self.design_height(self.x_height, 'x')? * self.scale_factor(px))
+    }
+
+    /// Retrieves the bounding box that fits every glyph in the font, which is useful for sizing a
+    /// uniform grid of glyph cells. This is from the head table, grown to fit any synthesized
+    /// styles the font was loaded with.
+    /// # Arguments
+    ///
+    /// * `px` - The size to scale the bounding box for. The units of the scale are pixels per Em
+    /// unit.
+    /// # Returns
+    ///
+    /// * `(f32, f32, f32, f32)` - The scaled xmin, ymin, xmax, and ymax of the bounding box,
+    /// relative to the glyph origin with positive Y up.
+    pub fn bounding_box(&self, px: f32) -> (f32, f32, f32, f32) {
+        let bounds = self.bounding_box.scale(self.scale_factor(px));
+        (bounds.xmin, bounds.ymin, bounds.xmin + bounds.width, bounds.ymin + bounds.height)
+    }
+
+    /// Gets a height from the font's tables, falling back to the top of the given character's
+    /// outline, in font units.
+    fn design_height(&self, height: Option<f32>, fallback: char) -> Option<f32> {
+        if height.is_some() {
+            return height;
+        }
+        let index = self.mapped_glyph_index(fallback);
+        if index == 0 {
+            return None;
+        }
+        let bounds = self.glyph(index).bounds;
+        Some(bounds.ymin + bounds.height).filter(|&height| height > 0.0)
+    }
+
+    /// Retrieves the advance width of a glyph in whole pixels at the given size, as specified by
+    /// the font's horizontal device metrics (hdmx) table. Fonts include these for the sizes their
+    /// hinted advances differ from the scaled ones, and matching them lines text up with renderers
+    /// that apply hinting.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font.
+    /// * `ppem` - The size in pixels per em.
+    /// # Returns
+    ///
+    /// * `Option<u32>` - The device advance width in pixels, or none if the font doesn't have one
+    /// for the glyph at the size, or doesn't keep its data, see `FontSettings::keep_data`.
+    pub fn device_advance(&self, index: u16, ppem: u32) -> Option<u32> {
+        if ppem > u8::MAX as u32 {
+            return None;
+        }
+        let face = self.face()?;
+        let hdmx = face.raw_face().table(Tag::from_bytes(b"hdmx"))?;
+        device_advance(hdmx, face.number_of_glyphs(), index, ppem as u8).map(u32::from)
+    }
+
+    /// The encodings of the character map subtables characters were read from, in the order they
+    /// were read. Every subtable is read, and later subtables take precedence for characters in
+    /// more than one. This tells symbol fonts mapped into the private use area apart from Unicode
+    /// fonts.
+    /// # Returns
+    ///
+    /// * `Vec<CmapEncoding>` - The encoding of each subtable that maps at least one character.
+    /// Empty if the font doesn't keep its data, see `FontSettings::keep_data`.
+    pub fn cmap_encodings(&self) -> Vec<CmapEncoding> {
+        let mut encodings = Vec::new();
+        let cmap = match self.face().and_then(|face| face.tables().cmap) {
+            Some(cmap) => cmap,
+            None => return encodings,
+        };
+        for subtable in cmap.subtables {
+            let mut maps = false;
+            subtable.codepoints(|codepoint| {
+                maps = maps || subtable.glyph_index(codepoint).map_or(false, |index| index.0 != 0);
+            });
+            if maps {
+                encodings.push(CmapEncoding {
+                    platform_id: match subtable.platform_id {
+                        PlatformId::Unicode => 0,
+                        PlatformId::Macintosh => 1,
+                        PlatformId::Iso => 2,
+                        PlatformId::Windows => 3,
+                        PlatformId::Custom => 4,
+                    },
+                    encoding_id: subtable.encoding_id,
+                });
+            }
+        }
+        encodings
+    }
+
+    /// Retrieves the layout metrics for the given character. If the character isn't present in the
+    /// font, then the layout for the font's default character is returned instead.
+    /// # Arguments
+    ///
+    /// * `index` - The character in the font to to generate the layout metrics for.
+    /// * `px` - The size to generate the layout metrics for the character at. Cannot be negative.
+    /// The units of the scale are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the glyph.
+    #[inline]
+    pub fn metrics(&self, character: char, px: f32) -> Metrics {
+        self.metrics_indexed(self.lookup_glyph_index(character), px)
+    }
+
+    /// Retrieves the layout metrics at the given index. You normally want to be using
+    /// metrics(char, f32) instead, unless your glyphs are pre-indexed.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to to generate the layout metrics for.
+    /// * `px` - The size to generate the layout metrics for the glyph at. Cannot be negative. The
+    /// units of the scale are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the glyph.
+    pub fn metrics_indexed(&self, index: u16, px: f32) -> Metrics {
+        let glyph = self.glyph(index);
+        let scale = self.scale_factor(px);
+        let (metrics, _, _) = self.metrics_raw(scale, glyph, 0.0, 0.0);
+        metrics
+    }
+
+    /// Retrieves the layout metrics of many glyphs at the given indices at once. This matches
+    /// calling `metrics_indexed` on each index, but computes the scale once for the whole batch,
+    /// which adds up when laying out thousands of glyphs. With the `parallel` feature enabled, the
+    /// batch is split across threads.
+    /// # Arguments
+    ///
+    /// * `indices` - The glyph indices in the font to generate the layout metrics for.
+    /// * `px` - The size to generate the layout metrics for the glyphs at. Cannot be negative. The
+    /// units of the scale are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Vec<Metrics>` - Sizing and positioning metadata for each glyph, in the order the indices
+    /// were given.
+    pub fn metrics_batch(&self, indices: &[u16], px: f32) -> Vec<Metrics> {
+        let scale = self.scale_factor(px);
+        let metrics = |&index: &u16| self.metrics_raw(scale, self.glyph(index), 0.0, 0.0).0;
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            indices.iter().map(metrics).collect()
+        }
+
+        #[cfg(feature = "parallel")]
+        indices.par_iter().map(metrics).collect()
+    }
+
+    /// Internal function to generate the metrics, offset_x, and offset_y of the glyph. The glyph is
+    /// shifted by the given offset in pixels before being aligned to the pixel grid, where positive
+    /// offsets move the glyph right and up.
+    fn metrics_raw(&self, scale: f32, glyph: &Glyph, offset_x: f32, offset_y: f32) -> (Metrics, f32, f32) {
+        self.metrics_raw_scaled(scale, scale, glyph, offset_x, offset_y)
+    }
+
+    /// Internal function to generate the metrics, offset_x, and offset_y of the glyph, with separate
+    /// horizontal and vertical scales.
+    fn metrics_raw_scaled(
+        &self,
+        scale_x: f32,
+        scale_y: f32,
+        glyph: &Glyph,
+        offset_x: f32,
+        offset_y: f32,
+    ) -> (Metrics, f32, f32) {
+        let mut bounds = OutlineBounds {
+            xmin: glyph.bounds.xmin * scale_x,
+            ymin: glyph.bounds.ymin * scale_y,
+            width: glyph.bounds.width * scale_x,
+            height: glyph.bounds.height * scale_y,
+        };
+        // Bearings are relative to the pen, so they're measured before the glyph is offset.
+        let advance_width = scale_x * glyph.advance_width;
+        let left_side_bearing = bounds.xmin;
+        let right_side_bearing = advance_width - (bounds.xmin + bounds.width);
+        bounds.xmin += offset_x;
+        bounds.ymin += offset_y;
+        if self.settings.pixel_center {
+            bounds.xmin += 0.5;
+            bounds.ymin += 0.5;
+        }
+        if self.settings.grid_fit == GridFit::Vertical {
+            bounds.ymin = floor(bounds.ymin + 0.5);
+        }
+        let mut offset_x = fract(bounds.xmin);
+        let mut offset_y = fract(1.0 - fract(bounds.height) - fract(bounds.ymin));
+        if is_negative(offset_x) {
+            offset_x += 1.0;
+        }
+        if is_negative(offset_y) {
+            offset_y += 1.0;
+        }
+        let metrics = Metrics {
+            xmin: as_i32(floor(bounds.xmin)),
+            ymin: as_i32(floor(bounds.ymin)),
+            width: as_i32(ceil(bounds.width + offset_x)) as usize,
+            height: as_i32(ceil(bounds.height + offset_y)) as usize,
+            advance_width,
+            advance_height: scale_y * glyph.advance_height,
+            left_side_bearing,
+            right_side_bearing,
+            bounds,
+        };
+        (metrics, offset_x, offset_y)
+    }
+
+    /// Internal function to check if a bitmap with the given dimensions is within
+    /// `FontSettings::max_bitmap_pixels`.
+    pub(crate) fn fits_bitmap(&self, width: usize, height: usize) -> bool {
+        width.checked_mul(height).map_or(false, |pixels| pixels <= self.settings.max_bitmap_pixels)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap for the given character, if the font has
+    /// a glyph for it. Unlike `rasterize`, this doesn't fall back to the font's default character,
+    /// which is useful for trying each font in a fallback chain.
+    /// # Arguments
+    ///
+    /// * `character` - The character to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Option<(Metrics, Vec<u8>)>` - The metrics and coverage vector for the glyph, the same as
+    /// from `rasterize`, or none if the character isn't present in the font.
+    #[inline]
+    pub fn try_rasterize(&self, character: char, px: f32) -> Option<(Metrics, Vec<u8>)> {
+        match self.mapped_glyph_index(character) {
+            0 => None,
+            index => Some(self.rasterize_indexed(index, px)),
+        }
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, checking that the
+    /// index is in the font first. This is useful for indices from outside sources, like a separate
+    /// shaper, since `rasterize_indexed` panics for indices that aren't in the font.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `FontResult<(Metrics, Vec<u8>)>` - The metrics and coverage vector for the glyph, the
+    /// same as from `rasterize_indexed`, or an error if the index is out of bounds.
+    #[inline]
+    pub fn try_rasterize_indexed(&self, index: u16, px: f32) -> FontResult<(Metrics, Vec<u8>)> {
+        if index >= self.glyph_count() {
+            return Err("Attempted to rasterize a glyph out of bounds.");
+        }
+        Ok(self.rasterize_indexed(index, px))
+    }
+
+    /// Rasterizes every given character, like for filling a glyph atlas up front.
+    /// # Arguments
+    ///
+    /// * `chars` - The characters to rasterize. Characters missing from the font use the font's
+    /// default character, the same as `rasterize`.
+    /// * `px` - The size to render the characters at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `HashMap<char, (Metrics, Vec<u8>)>` - The metrics and coverage vector of each character,
+    /// the same as from `rasterize`.
+    pub fn rasterize_all<I: IntoIterator<Item = char>>(
+        &self,
+        chars: I,
+        px: f32,
+    ) -> HashMap<char, (Metrics, Vec<u8>)> {
+        let mut rasterized = HashMap::new();
+        for character in chars {
+            rasterized.entry(character).or_insert_with(|| self.rasterize(character, px));
+        }
+        rasterized
+    }
+
+    /// Lays out and rasterizes a single line of text into one bitmap, which is the quickest way to
+    /// draw a simple label. Glyphs are placed the same way as `shape_simple`, with kerning, and
+    /// overlapping glyphs keep the greater coverage of the two. Characters aren't wrapped, and
+    /// new lines are drawn like any other character, so use `Layout` for anything more involved.
+    /// Lines that would have more pixels than `FontSettings::max_bitmap_pixels` return an empty
+    /// bitmap with a width and height of 0.
+    /// # Arguments
+    ///
+    /// * `text` - The text to rasterize.
+    /// * `px` - The size to render the text at. Cannot be negative. The units of the scale are
+    /// pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `usize` - The width of the bitmap in whole pixels. This covers the advance of the text,
+    /// grown to fit glyphs that extend past either end of it.
+    /// * `usize` - The height of the bitmap in whole pixels. This covers the font's ascent and
+    /// descent, grown to fit glyphs that extend past them. The baseline is the font's ascent
+    /// down from the top, rounded up.
+    /// * `Vec<u8>` - Coverage vector for the line. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel and 255 represents 100% coverage. The vec starts at the top left
+    /// corner of the line.
+    pub fn rasterize_line(&self, text: &str, px: f32) -> (usize, usize, Vec<u8>) {
+        let (ascent, descent) = match self.horizontal_line_metrics(px) {
+            Some(metrics) => (ceil(metrics.ascent), ceil(-metrics.descent)),
+            None => (0.0, 0.0),
+        };
+        // Glyphs are placed relative to the baseline at the origin, then the bitmap is grown to
+        // fit them.
+        let mut glyphs = Vec::new();
+        let (mut left, mut top, mut right, mut bottom) = (0.0f32, -ascent, 0.0f32, descent);
+        let mut pen = 0.0;
+        for shaped in self.shape_simple(text, px) {
+            pen += shaped.x_offset;
+            let (metrics, bitmap) = self.rasterize_indexed(shaped.glyph_index, px);
+            if !bitmap.is_empty() {
+                let (x, y) = metrics.origin(pen, 0.0, CoordinateSystem::PositiveYDown);
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x + metrics.width as f32);
+                bottom = bottom.max(y + metrics.height as f32);
+                glyphs.push((x, y, metrics, bitmap));
+            }
+            pen += shaped.advance;
+        }
+        let right = right.max(ceil(pen));
+
+        let width = as_i32(right - left) as usize;
+        let height = as_i32(bottom - top) as usize;
+        if !self.fits_bitmap(width, height) {
+            return (0, 0, Vec::new());
+        }
+        let mut line = vec![0; width * height];
+        for (x, y, metrics, bitmap) in glyphs {
+            let x = as_i32(x - left) as usize;
+            let y = as_i32(y - top) as usize;
+            for row in 0..metrics.height {
+                let dst = &mut line[(y + row) * width + x..][..metrics.width];
+                let src = &bitmap[row * metrics.width..][..metrics.width];
+                for (dst, &src) in dst.iter_mut().zip(src) {
+                    *dst = (*dst).max(src);
+                }
+            }
+        }
+        (width, height, line)
+    }
+
+    /// Rasterizes the given glyphs and packs them into a single atlas bitmap, like for uploading
+    /// to a GPU texture. Atlases that would have more pixels than `FontSettings::max_bitmap_pixels`
+    /// are returned empty, without any glyphs.
+    /// # Arguments
+    ///
+    /// * `glyphs` - The glyph indices to rasterize, and the size to render each at. Sizes cannot be
+    /// negative, and the units of the scale are pixels per Em unit. Repeated glyphs are only
+    /// packed once.
+    /// * `padding` - The number of empty pixels to leave around each glyph in the atlas.
+    /// # Returns
+    ///
+    /// * `Atlas` - The packed coverage bitmap, along with where each glyph is in it and its
+    /// metrics.
+    pub fn rasterize_atlas(&self, glyphs: &[(u16, f32)], padding: usize) -> Atlas {
+        let mut requested = HashSet::new();
+        let mut rasterized = Vec::new();
+        for &(index, px) in glyphs {
+            let config = GlyphRasterConfig {
+                glyph_index: index,
+                px,
+                font_hash: self.hash,
+            };
+            if requested.insert(config) {
+                let (metrics, bitmap) = self.rasterize_indexed(index, px);
+                rasterized.push((config, metrics, bitmap));
+            }
+        }
+        Atlas::pack(rasterized, padding, self.hash, self.settings.max_bitmap_pixels)
+    }
+
+    /// Retrieves the layout rasterized bitmap for the given raster config. If the raster config's
+    /// character isn't present in the font, then the layout and bitmap for the font's default
+    /// character's raster is returned instead.
+    /// # Arguments
+    ///
+    /// * `config` - The settings to render the character at.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize_config(&self, config: GlyphRasterConfig) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed(config.glyph_index, config.px)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap for the given character. If the
+    /// character isn't present in the font, then the layout and bitmap for the font's default
+    /// character is returned instead.
+    /// # Arguments
+    ///
+    /// * `character` - The character to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize(&self, character: char, px: f32) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed(self.lookup_glyph_index(character), px)
+    }
+
+    /// Retrieves the layout rasterized bitmap for the given raster config. If the raster config's
+    /// character isn't present in the font, then the layout and bitmap for the font's default
+    /// character's raster is returned instead.
+    ///
+    /// This will perform the operation with the width multiplied by 3, as to simulate subpixels.
+    /// Taking these as RGB values will perform subpixel anti aliasing.
+    /// # Arguments
+    ///
+    /// * `config` - The settings to render the character at.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Swizzled RGB coverage vector for the glyph. Coverage is a linear scale where 0
+    /// represents 0% coverage of that subpixel by the glyph and 255 represents 100% coverage. The
+    /// vec starts at the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize_config_subpixel(&self, config: GlyphRasterConfig) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed_subpixel(config.glyph_index, config.px)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap for the given character. If the
+    /// character isn't present in the font, then the layout and bitmap for the font's default
+    /// character is returned instead.
+    ///
+    /// This will perform the operation with the width multiplied by 3, as to simulate subpixels.
+    /// Taking these as RGB values will perform subpixel anti aliasing.
+    /// # Arguments
+    ///
+    /// * `character` - The character to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Swizzled RGB coverage vector for the glyph. Coverage is a linear scale where 0
+    /// represents 0% coverage of that subpixel by the glyph and 255 represents 100% coverage. The
+    /// vec starts at the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize_subpixel(&self, character: char, px: f32) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed_subpixel(self.lookup_glyph_index(character), px)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index. You normally want to
+    /// be using rasterize(char, f32) instead, unless your glyphs are pre-indexed.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    pub fn rasterize_indexed(&self, index: u16, px: f32) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed_offset(index, px, 0.0, 0.0)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, with rows and columns
+    /// of zero coverage cropped from the edges of the bitmap. Rounding can leave such a border on
+    /// glyphs, which wastes space and can leave seams when glyphs are packed tightly into an atlas.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph, with the `xmin`,
+    /// `ymin`, `width`, and `height` of the cropped bitmap. Glyphs without any coverage have a
+    /// width and height of 0.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    pub fn rasterize_indexed_trimmed(&self, index: u16, px: f32) -> (Metrics, Vec<u8>) {
+        let (mut metrics, coverage) = self.rasterize_indexed(index, px);
+        let width = metrics.width;
+        let inked_row = |y: usize| coverage[y * width..(y + 1) * width].iter().any(|&c| c != 0);
+        let inked_column = |x: usize| (0..metrics.height).any(|y| coverage[y * width + x] != 0);
+        let top = match (0..metrics.height).find(|&y| inked_row(y)) {
+            Some(top) => top,
+            None => {
+                metrics.width = 0;
+                metrics.height = 0;
+                return (metrics, Vec::new());
+            }
+        };
+        // A row has coverage, so every search below finds one too.
+        let bottom = (0..metrics.height).rev().find(|&y| inked_row(y)).unwrap_or(top) + 1;
+        let left = (0..width).find(|&x| inked_column(x)).unwrap_or(0);
+        let right = (0..width).rev().find(|&x| inked_column(x)).unwrap_or(left) + 1;
+        let mut trimmed = Vec::with_capacity((right - left) * (bottom - top));
+        for y in top..bottom {
+            trimmed.extend_from_slice(&coverage[y * width + left..y * width + right]);
+        }
+        // The bitmap starts at the top, so ymin moves up by the rows cropped from the bottom.
+        metrics.xmin += left as i32;
+        metrics.ymin += (metrics.height - bottom) as i32;
+        metrics.width = right - left;
+        metrics.height = bottom - top;
+        (metrics, trimmed)
+    }
+
+    /// Retrieves the layout metrics and a 1 bit per pixel bitmap at the given index, like
+    /// monochrome displays take. Pixels are set where rasterizing with `FontSettings::antialias`
+    /// disabled gives full coverage.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `usize` - The stride of each row in bytes, which is the width rounded up to a multiple of
+    /// 8 pixels.
+    /// * `Vec<u8>` - The packed rows of the glyph, starting at the top left corner. Each byte holds
+    /// 8 pixels with the leftmost in the most significant bit, and bits past the width of each row
+    /// are 0.
+    pub fn rasterize_indexed_packed(&self, index: u16, px: f32) -> (Metrics, usize, Vec<u8>) {
+        let (metrics, coverage) = self.rasterize_indexed(index, px);
+        let stride = (metrics.width + 7) / 8;
+        let mut packed = vec![0; stride * metrics.height];
+        if stride == 0 {
+            return (metrics, 0, packed);
+        }
+        for (row, packed_row) in coverage.chunks_exact(metrics.width).zip(packed.chunks_exact_mut(stride)) {
+            for (x, &coverage) in row.iter().enumerate() {
+                if coverage >= 128 {
+                    packed_row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        (metrics, stride, packed)
+    }
+
+    /// Internal function to rasterize the glyph shifted by the given offset in pixels. Positive
+    /// offsets move the glyph right and up.
+    #[inline]
+    fn rasterize_indexed_offset(
+        &self,
+        index: u16,
+        px: f32,
+        offset_x: f32,
+        offset_y: f32,
+    ) -> (Metrics, Vec<u8>) {
+        let mut output = Vec::new();
+        let metrics = self.rasterize_indexed_offset_into(
+            index,
+            px,
+            offset_x,
+            offset_y,
+            &mut Raster::empty(),
+            &mut output,
+        );
+        (metrics, output)
+    }
+
+    /// Internal function to rasterize the glyph shifted by the given offset in pixels into the
+    /// given buffer, reusing the given raster.
+    #[inline]
+    fn rasterize_indexed_offset_into(
+        &self,
+        index: u16,
+        px: f32,
+        offset_x: f32,
+        offset_y: f32,
+        canvas: &mut Raster,
+        output: &mut Vec<u8>,
+    ) -> Metrics {
+        output.clear();
+        if px <= 0.0 {
+            return Metrics::default();
+        }
+        let glyph = self.glyph(index);
+        let scale = self.scale_factor(px);
+        let (mut metrics, offset_x, offset_y) = self.metrics_raw(scale, glyph, offset_x, offset_y);
+        if glyph.is_empty() {
+            // Blank glyphs like spaces have nothing to draw, skip the raster entirely.
+            metrics.width = 0;
+            metrics.height = 0;
+            return metrics;
+        }
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return Metrics::default();
+        }
+        canvas.reset(metrics.width, metrics.height);
+        canvas.draw(glyph.level_of_detail(px, self.settings.scale), scale, scale, offset_x, offset_y);
+        canvas.get_bitmap_into(output, &self.settings);
+        metrics
+    }
+
+    /// Retrieves the layout metrics at the given index and rasterizes the glyph into the given
+    /// buffer. This is the same as `rasterize_indexed`, but reuses the buffer's allocation instead
+    /// of allocating a new bitmap each call. See `rasterize_into_scratch` to also reuse the
+    /// raster's working memory.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `buffer` - The buffer to write the coverage vector into. It's cleared first, and holds
+    /// the same coverage vector `rasterize_indexed` returns afterwards.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    pub fn rasterize_into(&self, index: u16, px: f32, buffer: &mut Vec<u8>) -> Metrics {
+        self.rasterize_indexed_offset_into(index, px, 0.0, 0.0, &mut Raster::empty(), buffer)
+    }
+
+    /// Retrieves the layout metrics at the given index and rasterizes the glyph into the given
+    /// buffer, using the scratch space for the raster's working memory. Once the scratch space and
+    /// buffer have grown to fit the largest glyph, this doesn't allocate.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `scratch` - Working memory for the raster, which can be reused between calls.
+    /// * `buffer` - The buffer to write the coverage vector into. It's cleared first, and holds
+    /// the same coverage vector `rasterize_indexed` returns afterwards.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    pub fn rasterize_into_scratch(
+        &self,
+        index: u16,
+        px: f32,
+        scratch: &mut RasterScratch,
+        buffer: &mut Vec<u8>,
+    ) -> Metrics {
+        self.rasterize_indexed_offset_into(index, px, 0.0, 0.0, &mut scratch.raster, buffer)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, using the scratch
+    /// space for the raster's working memory. This skips allocating the raster for every glyph
+    /// when the returned bitmaps can't be cached, see `rasterize_into_scratch` to reuse the
+    /// bitmap too.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `scratch` - Working memory for the raster, which can be reused between calls.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    pub fn rasterize_indexed_with(
+        &self,
+        index: u16,
+        px: f32,
+        scratch: &mut RasterScratch,
+    ) -> (Metrics, Vec<u8>) {
+        let mut buffer = Vec::new();
+        let metrics = self.rasterize_into_scratch(index, px, scratch, &mut buffer);
+        (metrics, buffer)
+    }
+
+    /// Retrieves the layout metrics and a signed distance field for the glyph at the given index.
+    /// Distance fields can be scaled and thresholded on the GPU, so they don't need to be
+    /// rasterized again for every size. The field is generated from the glyph's outline, so fonts
+    /// that don't keep their data return an empty field, see `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to generate the field for.
+    /// * `px` - The size to generate the field at. Cannot be negative. The units of the scale are
+    /// pixels per Em unit.
+    /// * `spread` - The distance from the outline in pixels that the field covers on either side.
+    /// Must be positive.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the field. The field is padded by the
+    /// spread (rounded up) on every side, so xmin, ymin, width, and height include the padding.
+    /// * `Vec<u8>` - Distance vector for the glyph. 128 represents the outline, larger values are
+    /// inside the glyph and smaller values are outside of it, with 255 and 0 being the spread away
+    /// from the outline. The vec starts at the top left corner of the field.
+    pub fn rasterize_sdf(&self, index: u16, px: f32, spread: f32) -> (Metrics, Vec<u8>) {
+        if px <= 0.0 {
+            return (Metrics::default(), Vec::new());
+        }
+        let scale = self.scale_factor(px);
+        let glyph = self.glyph(index);
+        let (mut metrics, _, _) = self.metrics_raw(scale, glyph, 0.0, 0.0);
+        let outline = match self.outline(index, px) {
+            Some(outline) => outline,
+            None => {
+                metrics.width = 0;
+                metrics.height = 0;
+                return (metrics, Vec::new());
+            }
+        };
+        let padding = as_i32(ceil(spread.max(0.0)));
+        metrics.xmin -= padding;
+        metrics.ymin -= padding;
+        metrics.width += 2 * padding as usize;
+        metrics.height += 2 * padding as usize;
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
+        // The outline isn't moved by pixel_center or grid_fit like the metrics are, so the field is
+        // sampled the same distance the other way.
+        let shift_x = metrics.bounds.xmin - glyph.bounds.xmin * scale;
+        let shift_y = metrics.bounds.ymin - glyph.bounds.ymin * scale;
+        let ymax = (metrics.ymin + metrics.height as i32) as f32;
+        let field = crate::sdf::distance_field(
+            &outline,
+            metrics.xmin as f32 - shift_x,
+            ymax - shift_y,
+            metrics.width,
+            metrics.height,
+            spread,
+        );
+        (metrics, field)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap of a stroke along the outline of the
+    /// glyph at the given index, instead of the filled glyph. The stroke is centered on the
+    /// outline and has round joins. Fonts that don't keep their data have no outline to stroke, see
+    /// `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to stroke.
+    /// * `px` - The size to render the glyph at. Cannot be negative. The units of the scale are
+    /// pixels per Em unit.
+    /// * `width` - The width of the stroke in pixels. Cannot be negative.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the stroke. The bounds are grown by half
+    /// the width on every side to fit the stroke.
+    /// * `Vec<u8>` - Coverage vector for the stroke. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the stroke and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the stroke.
+    pub fn rasterize_stroked(&self, index: u16, px: f32, width: f32) -> (Metrics, Vec<u8>) {
+        if px <= 0.0 {
+            return (Metrics::default(), Vec::new());
+        }
+        let glyph = self.glyph(index);
+        let scale = self.scale_factor(px);
+        let outline = match self.outline(index, px) {
+            Some(outline) if width > 0.0 => outline,
+            _ => {
+                let (mut metrics, _, _) = self.metrics_raw(scale, glyph, 0.0, 0.0);
+                metrics.width = 0;
+                metrics.height = 0;
+                return (metrics, Vec::new());
+            }
+        };
+        // The stroke is built in pixels, so it's drawn without scaling.
+        let mut stroke = crate::stroke::stroke(&outline, width);
+        stroke.advance_width = glyph.advance_width * scale;
+        stroke.advance_height = glyph.advance_height * scale;
+        let (metrics, offset_x, offset_y) = self.metrics_raw(1.0, &stroke, 0.0, 0.0);
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
+        let mut canvas = Raster::new(metrics.width, metrics.height);
+        canvas.draw(&stroke, 1.0, 1.0, offset_x, offset_y);
+        (metrics, canvas.get_bitmap(&self.settings))
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, with the glyph rotated
+    /// around its origin on the baseline. The outline is rotated before it's rasterized, so the
+    /// result is as sharp as an upright glyph, unlike rotating the bitmap afterwards. The outline
+    /// is read from the font data, so fonts that don't keep it return an empty bitmap, see
+    /// `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `radians` - The angle to rotate the glyph by. Positive angles rotate it counterclockwise.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph. The bounds are the
+    /// bounding box of the rotated glyph, and the advances are the same as the upright glyph's.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    pub fn rasterize_indexed_rotated(&self, index: u16, px: f32, radians: f32) -> (Metrics, Vec<u8>) {
         if px <= 0.0 {
             return (Metrics::default(), Vec::new());
         }
-        let glyph = &self.glyphs[index as usize];
+        let upright = self.glyph(index);
+        let mut outline = OutlineCommands {
+            scale: 1.0,
+            commands: Vec::new(),
+        };
+        if let Some(face) = self.face() {
+            face.outline_glyph(GlyphId(index), &mut outline);
+        }
+        let mut commands = self.settings.synthesize(outline.commands, self.units_per_em);
+        let (sin, cos) = sin_cos(radians);
+        rotate(&mut commands, sin, cos);
+
+        // The rotated outline is flattened in font units, the same as the upright glyphs.
+        let mut glyph = Glyph::default();
+        glyph.advance_width = upright.advance_width;
+        glyph.advance_height = upright.advance_height;
+        let mut geometry =
+            Geometry::new(self.settings.scale, self.units_per_em, self.settings.curve_tolerance);
+        draw_outline(&commands, &mut geometry);
+        geometry.finalize(&mut glyph);
+
         let scale = self.scale_factor(px);
-        let (metrics, offset_x, offset_y) = self.metrics_raw(scale, glyph, 0.0);
+        let (mut metrics, offset_x, offset_y) = self.metrics_raw(scale, &glyph, 0.0, 0.0);
+        if glyph.is_empty() {
+            metrics.width = 0;
+            metrics.height = 0;
+            return (metrics, Vec::new());
+        }
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
         let mut canvas = Raster::new(metrics.width, metrics.height);
         canvas.draw(&glyph, scale, scale, offset_x, offset_y);
-        (metrics, canvas.get_bitmap())
+        (metrics, canvas.get_bitmap(&self.settings))
+    }
+
+    /// Retrieves the layout metrics and rasterized color bitmap for the given character. If the
+    /// character isn't present in the font, then the layout and bitmap for the font's default
+    /// character is returned instead. See `rasterize_indexed_color` for how color glyphs are
+    /// drawn.
+    /// # Arguments
+    ///
+    /// * `character` - The character to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<BGRA8Color>` - Color vector for the glyph. The vec starts at the top left corner of
+    /// the glyph.
+    #[inline]
+    pub fn rasterize_color(&self, character: char, px: f32) -> (Metrics, Vec<BGRA8Color>) {
+        self.rasterize_indexed_color(self.lookup_glyph_index(character), px)
+    }
+
+    /// Retrieves the layout metrics and rasterized color bitmap at the given index. You normally
+    /// want to be using rasterize_color(char, f32) instead, unless your glyphs are pre-indexed.
+    ///
+    /// Color glyphs from the font's COLR table are drawn by filling each of their layers with the
+    /// layer's color from the default CPAL palette, back to front with straight alpha. Layers that
+    /// use the foreground color are drawn black. Only solid color layers are supported, glyphs
+    /// using gradients or transforms are drawn like glyphs without a COLR entry: their grayscale
+    /// coverage is expanded to opaque gray, with the coverage in each color channel. The layers are
+    /// read from the font data, so fonts that don't keep it draw every glyph that way, see
+    /// `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph. The bounds cover
+    /// every layer of color glyphs.
+    /// * `Vec<BGRA8Color>` - Color vector for the glyph. The vec starts at the top left corner of
+    /// the glyph.
+    pub fn rasterize_indexed_color(&self, index: u16, px: f32) -> (Metrics, Vec<BGRA8Color>) {
+        let layers = self.face().and_then(|face| color_layers(&face, index, RgbaColor::new(0, 0, 0, 255)));
+        let layers = match layers {
+            Some(layers) if px > 0.0 => layers,
+            _ => {
+                let (metrics, coverage) = self.rasterize_indexed(index, px);
+                let bitmap = coverage.iter().map(|&c| BGRA8Color::new(c, c, c, 255)).collect();
+                return (metrics, bitmap);
+            }
+        };
+
+        // The bitmap covers the union of the layers' bounds.
+        let base = self.glyph(index);
+        let mut union = Glyph::default();
+        union.advance_width = base.advance_width;
+        union.advance_height = base.advance_height;
+        let (mut xmin, mut ymin) = (core::f32::MAX, core::f32::MAX);
+        let (mut xmax, mut ymax) = (core::f32::MIN, core::f32::MIN);
+        for &(layer, _) in &layers {
+            let layer = self.glyph(layer);
+            if !layer.is_empty() {
+                xmin = xmin.min(layer.bounds.xmin);
+                ymin = ymin.min(layer.bounds.ymin);
+                xmax = xmax.max(layer.bounds.xmin + layer.bounds.width);
+                ymax = ymax.max(layer.bounds.ymin + layer.bounds.height);
+            }
+        }
+        if xmin > xmax {
+            let (metrics, _, _) = self.metrics_raw(self.scale_factor(px), &union, 0.0, 0.0);
+            return (metrics, Vec::new());
+        }
+        union.bounds = OutlineBounds {
+            xmin,
+            ymin,
+            width: xmax - xmin,
+            height: ymax - ymin,
+        };
+
+        let scale = self.scale_factor(px);
+        let (metrics, offset_x, offset_y) = self.metrics_raw(scale, &union, 0.0, 0.0);
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
+        let mut bitmap = vec![BGRA8Color::default(); metrics.width * metrics.height];
+        let mut canvas = Raster::empty();
+        let mut coverage = Vec::new();
+        for (layer, color) in layers {
+            let layer = self.glyph(layer);
+            if layer.is_empty() {
+                continue;
+            }
+            // Layer geometry is relative to the top left of its own bounds.
+            let layer_x = (layer.bounds.xmin - xmin) * scale;
+            let layer_y = (ymax - (layer.bounds.ymin + layer.bounds.height)) * scale;
+            canvas.reset(metrics.width, metrics.height);
+            canvas.draw(
+                layer.level_of_detail(px, self.settings.scale),
+                scale,
+                scale,
+                offset_x + layer_x,
+                offset_y + layer_y,
+            );
+            canvas.get_bitmap_into(&mut coverage, &self.settings);
+            for (pixel, &c) in bitmap.iter_mut().zip(coverage.iter()) {
+                pixel.blend(color, c);
+            }
+        }
+        (metrics, bitmap)
     }
 
     /// Retrieves the layout metrics and rasterized bitmap at the given index. You normally want to
@@ -614,30 +3198,418 @@ impl Font {
         if px <= 0.0 {
             return (Metrics::default(), Vec::new());
         }
-        let glyph = &self.glyphs[index as usize];
+        let glyph = self.glyph(index);
         let scale = self.scale_factor(px);
-        let (metrics, offset_x, offset_y) = self.metrics_raw(scale, glyph, 0.0);
+        let (mut metrics, offset_x, offset_y) = self.metrics_raw(scale, glyph, 0.0, 0.0);
+        if glyph.is_empty() {
+            metrics.width = 0;
+            metrics.height = 0;
+            return (metrics, Vec::new());
+        }
+        if !self.fits_bitmap(metrics.width.saturating_mul(3), metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
         let mut canvas = Raster::new(metrics.width * 3, metrics.height);
-        canvas.draw(&glyph, scale * 3.0, scale, offset_x, offset_y);
-        (metrics, canvas.get_bitmap())
+        canvas.draw(glyph.level_of_detail(px, self.settings.scale), scale * 3.0, scale, offset_x, offset_y);
+        (metrics, canvas.get_bitmap(&self.settings))
+    }
+
+    /// Retrieves the layout metrics and subpixel rasterized bitmap at the given index, like
+    /// `rasterize_indexed_subpixel`, for displays with the given order of subpixels, and optionally
+    /// filtered to reduce color fringing.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `layout` - The order of the subpixels in each pixel of the display, left to right.
+    /// * `filter` - If enabled, each subpixel's coverage is blended with the two subpixels on each
+    /// side of it, weighted 1, 2, 3, 2, 1. The filter spreads coverage past the edges of the glyph,
+    /// so the bitmap is widened by a pixel on each side to keep it.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph. When filtered, xmin
+    /// and width include the extra pixel on each side.
+    /// * `Vec<u8>` - Swizzled coverage vector for the glyph, with 3 values per pixel in the order of
+    /// the layout. Coverage is a linear scale where 0 represents 0% coverage of that subpixel by the
+    /// glyph and 255 represents 100% coverage. The vec starts at the top left corner of the glyph.
+    pub fn rasterize_indexed_subpixel_layout(
+        &self,
+        index: u16,
+        px: f32,
+        layout: SubpixelLayout,
+        filter: bool,
+    ) -> (Metrics, Vec<u8>) {
+        let (mut metrics, mut bitmap) = self.rasterize_indexed_subpixel(index, px);
+        if filter && !bitmap.is_empty() {
+            const WEIGHTS: [u32; 5] = [1, 2, 3, 2, 1];
+            if !self.fits_bitmap((metrics.width + 2) * 3, metrics.height) {
+                return (Metrics::default(), Vec::new());
+            }
+            let row_length = metrics.width * 3;
+            let mut filtered = Vec::with_capacity((row_length + 6) * metrics.height);
+            let mut row = vec![0; row_length + 6];
+            for chunk in bitmap.chunks_exact(row_length) {
+                row[3..3 + row_length].copy_from_slice(chunk);
+                for x in 0..row.len() {
+                    let mut sum = 0;
+                    for (tap, weight) in WEIGHTS.iter().enumerate() {
+                        if let Some(&sample) = (x + tap).checked_sub(2).and_then(|x| row.get(x)) {
+                            sum += sample as u32 * weight;
+                        }
+                    }
+                    filtered.push(((sum + 4) / 9) as u8);
+                }
+            }
+            bitmap = filtered;
+            metrics.xmin -= 1;
+            metrics.width += 2;
+        }
+        if layout == SubpixelLayout::Bgr {
+            for pixel in bitmap.chunks_exact_mut(3) {
+                pixel.swap(0, 2);
+            }
+        }
+        (metrics, bitmap)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, with separate
+    /// horizontal and vertical sizes. This is useful for condensed or stretched text, and keeps the
+    /// quality of rasterizing at the target size instead of resizing the bitmap afterwards.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px_x` - The horizontal size to render the character at. Cannot be negative. The advance
+    /// width in the returned metrics uses this size.
+    /// * `px_y` - The vertical size to render the character at. Cannot be negative. The advance
+    /// height in the returned metrics uses this size.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    pub fn rasterize_indexed_scaled(&self, index: u16, px_x: f32, px_y: f32) -> (Metrics, Vec<u8>) {
+        if px_x <= 0.0 || px_y <= 0.0 {
+            return (Metrics::default(), Vec::new());
+        }
+        let glyph = self.glyph(index);
+        let scale_x = self.scale_factor(px_x);
+        let scale_y = self.scale_factor(px_y);
+        let (mut metrics, offset_x, offset_y) = self.metrics_raw_scaled(scale_x, scale_y, glyph, 0.0, 0.0);
+        if glyph.is_empty() {
+            metrics.width = 0;
+            metrics.height = 0;
+            return (metrics, Vec::new());
+        }
+        if !self.fits_bitmap(metrics.width, metrics.height) {
+            return (Metrics::default(), Vec::new());
+        }
+        let mut canvas = Raster::new(metrics.width, metrics.height);
+        let glyph = glyph.level_of_detail(px_x.max(px_y), self.settings.scale);
+        canvas.draw(glyph, scale_x, scale_y, offset_x, offset_y);
+        (metrics, canvas.get_bitmap(&self.settings))
+    }
+
+    /// Rasterizes the glyph at the given index for a pen positioned at a fractional pixel
+    /// coordinate. The glyph is rasterized with the pen's fractional offset applied, so the
+    /// returned bitmap can be copied directly onto the destination at whole pixel coordinates.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `pen_x` - The x coordinate of the pen on the baseline, in pixels.
+    /// * `pen_y` - The y coordinate of the pen on the baseline, in pixels. The Y axis is assumed to
+    /// increase downwards, like it does in images.
+    /// # Returns
+    ///
+    /// * `i32` - The x coordinate of the destination pixel for the left side of the bitmap.
+    /// * `i32` - The y coordinate of the destination pixel for the top side of the bitmap.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    /// * `usize` - The width of the bitmap in whole pixels.
+    /// * `usize` - The height of the bitmap in whole pixels.
+    pub fn rasterize_at_pen(
+        &self,
+        index: u16,
+        px: f32,
+        pen_x: f32,
+        pen_y: f32,
+    ) -> (i32, i32, Vec<u8>, usize, usize) {
+        // Metrics are Y up, so the pen's y offset is flipped going in and coming out.
+        let (metrics, bitmap) = self.rasterize_indexed_offset(index, px, pen_x, -pen_y);
+        if px <= 0.0 {
+            return (as_i32(floor(pen_x)), as_i32(floor(pen_y)), bitmap, 0, 0);
+        }
+        let dst_x = metrics.xmin;
+        let dst_y = -(metrics.ymin + metrics.height as i32);
+        (dst_x, dst_y, bitmap, metrics.width, metrics.height)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap for the given subpixel glyph key. The
+    /// glyph is rasterized shifted right by the key's quantized fractional offset. The returned
+    /// metrics include this offset, so the bitmap should be placed relative to the whole pixel
+    /// position the key was created with.
+    /// # Arguments
+    ///
+    /// * `key` - The glyph, size, and horizontal subpixel variant to rasterize.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize_subpixel_variant(&self, key: SubpixelGlyphKey) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed_offset(key.glyph_index, key.px, key.offset_x(), 0.0)
+    }
+
+    /// Retrieves the layout metrics and rasterized bitmap at the given index, with the glyph shifted
+    /// by a fractional pixel offset. This is useful for smoothly animating text, or for caching a
+    /// few subpixel phases of each glyph to place at fractional positions like
+    /// `GlyphPosition::pen_x`. The returned metrics include the offset.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to rasterize.
+    /// * `px` - The size to render the character at. Cannot be negative. The units of the scale
+    /// are pixels per Em unit.
+    /// * `offset_x` - The horizontal offset to shift the glyph by, in pixels. Positive values move
+    /// the glyph right.
+    /// * `offset_y` - The vertical offset to shift the glyph by, in pixels. Positive values move
+    /// the glyph up.
+    /// # Returns
+    ///
+    /// * `Metrics` - Sizing and positioning metadata for the rasterized glyph.
+    /// * `Vec<u8>` - Coverage vector for the glyph. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by the glyph and 255 represents 100% coverage. The vec starts at
+    /// the top left corner of the glyph.
+    #[inline]
+    pub fn rasterize_subpixel_offset(
+        &self,
+        index: u16,
+        px: f32,
+        offset_x: f32,
+        offset_y: f32,
+    ) -> (Metrics, Vec<u8>) {
+        self.rasterize_indexed_offset(index, px, offset_x, offset_y)
     }
 
     /// Checks if the font has a glyph for the given character.
     #[inline]
     pub fn has_glyph(&self, character: char) -> bool {
-        self.lookup_glyph_index(character) != 0
+        self.mapped_glyph_index(character) != 0
+    }
+
+    /// Checks if the glyph at the given index has any geometry to rasterize. This is false for blank
+    /// glyphs like spaces, including non-ASCII ones like the ideographic space that
+    /// `CharacterData::rasterize` doesn't recognize, so rasterizing them can be skipped.
+    ///
+    /// Non-lazy fonts only generate geometry for glyphs reachable from the character map and
+    /// substitutions, so this is false for any other glyph.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to check.
+    /// # Returns
+    ///
+    /// * `bool` - If the glyph has an outline. False for indices that aren't in the font.
+    pub fn has_outline(&self, index: u16) -> bool {
+        index < self.glyph_count() && !self.glyph(index).is_empty()
     }
 
     /// Finds the internal glyph index for the given character. If the character is not present in
-    /// the font then 0 is returned.
+    /// the font then the default glyph is returned, which is 0 unless it's changed with
+    /// `Font::set_default_glyph`.
     #[inline]
     pub fn lookup_glyph_index(&self, character: char) -> u16 {
+        match self.mapped_glyph_index(character) {
+            0 => self.default_glyph,
+            index => index,
+        }
+    }
+
+    /// Finds the glyph index the character map has for the given character, or 0 if it isn't
+    /// present in the font, regardless of the default glyph.
+    #[inline]
+    pub(crate) fn mapped_glyph_index(&self, character: char) -> u16 {
         // This is safe, Option<NonZeroU16> is documented to have the same layout as u16.
         unsafe { mem::transmute::<Option<NonZeroU16>, u16>(self.char_to_glyph.get(&character).copied()) }
     }
 
+    /// Gets the glyph used for characters that aren't present in the font. This is 0, the font's
+    /// missing glyph, unless it's changed with `Font::set_default_glyph`.
+    #[inline]
+    pub fn default_glyph(&self) -> u16 {
+        self.default_glyph
+    }
+
+    /// Sets the glyph used for characters that aren't present in the font, in place of glyph 0.
+    /// This is useful for fonts with a nicer box than their missing glyph, or for drawing missing
+    /// characters with a specific symbol. Layout still classifies these characters as missing.
+    ///
+    /// Non-lazy fonts only generate geometry for glyphs reachable from the character map and
+    /// substitutions, so the geometry for the glyph is generated here if it wasn't loaded. This is
+    /// an error for fonts that don't keep their data, see `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to use for missing characters.
+    pub fn set_default_glyph(&mut self, index: u16) -> FontResult<()> {
+        if index >= self.glyph_count() {
+            return Err("Attempted to set the default glyph out of bounds.");
+        }
+        self.load_glyph(index)?;
+        self.default_glyph = index;
+        Ok(())
+    }
+
+    /// Finds a character that maps to the given glyph index, like for recovering the text of shaped
+    /// glyphs. When several characters map to the glyph, the one with the lowest code point is
+    /// returned. The reverse mapping is built and kept the first time this is called.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to find a character for.
+    /// # Returns
+    ///
+    /// * `Option<char>` - A character that maps to the glyph, or none if no character does, like
+    /// for glyphs only reachable through substitutions.
+    pub fn glyph_to_char(&self, index: u16) -> Option<char> {
+        let reverse = self.glyph_to_char.get_or_init(|| {
+            let mut reverse = HashMap::with_capacity(self.char_to_glyph.len());
+            for (&character, &glyph) in &self.char_to_glyph {
+                let entry = reverse.entry(glyph.get()).or_insert(character);
+                *entry = (*entry).min(character);
+            }
+            reverse
+        });
+        reverse.get(&index).copied()
+    }
+
+    /// Retrieves the outline of the glyph at the given index, scaled to the given px size. These
+    /// are the font's original curves, before they're flattened into lines for rasterization.
+    /// Composite glyphs are resolved into the outlines of their components.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to get the outline of.
+    /// * `px` - The size to scale the outline to. The units of the scale are pixels per Em unit.
+    /// # Returns
+    ///
+    /// * `Option<Vec<OutlineCommand>>` - The drawing commands for the outline, in pixels with the Y
+    /// axis increasing upwards from the baseline. None if the glyph has no outline, like a space, or
+    /// if the font doesn't keep its data, see `FontSettings::keep_data`.
+    pub fn outline(&self, index: u16, px: f32) -> Option<Vec<OutlineCommand>> {
+        let face = self.face()?;
+        let mut outline = OutlineCommands {
+            scale: self.scale_factor(px),
+            commands: Vec::new(),
+        };
+        face.outline_glyph(GlyphId(index), &mut outline)?;
+        if outline.commands.is_empty() {
+            return None;
+        }
+        Some(self.settings.synthesize(outline.commands, px))
+    }
+
+    /// Draws the outline of the glyph at the given index, scaled to the given px size, into the
+    /// given sink. This is the same outline `outline` returns, with the font's original curves and
+    /// synthetic styles applied, but each command goes straight to the sink as the glyph is read.
+    /// Synthetic bold offsets whole contours, so emboldened outlines are collected before they're
+    /// drawn. Glyphs without an outline, like a space, don't draw anything, and neither do fonts
+    /// that don't keep their data, see `FontSettings::keep_data`.
+    /// # Arguments
+    ///
+    /// * `index` - The glyph index in the font to draw the outline of.
+    /// * `px` - The size to scale the outline to. The units of the scale are pixels per Em unit.
+    /// * `sink` - The sink to draw the outline into, in pixels with the Y axis increasing upwards
+    /// from the baseline.
+    pub fn build_outline(&self, index: u16, px: f32, sink: &mut dyn OutlineSink) {
+        if self.settings.synthetic_bold != 0.0 {
+            if let Some(commands) = self.outline(index, px) {
+                let mut builder = SinkBuilder {
+                    scale: 1.0,
+                    oblique: 0.0,
+                    sink,
+                };
+                draw_outline(&commands, &mut builder);
+            }
+            return;
+        }
+        if let Some(face) = self.face() {
+            let mut builder = SinkBuilder {
+                scale: self.scale_factor(px),
+                oblique: self.settings.synthetic_oblique,
+                sink,
+            };
+            face.outline_glyph(GlyphId(index), &mut builder);
+        }
+    }
+
+    /// Overrides which glyph the given character maps to. This is useful for patching fonts with
+    /// encoding quirks, or forcing a specific glyph for a character, without parsing the font
+    /// again. After an override, `chars()` no longer reflects the font's character map exactly.
+    ///
+    /// Non-lazy fonts only generate geometry for glyphs reachable from the character map and
+    /// substitutions, so the geometry for the glyph is generated here if it wasn't loaded. This is
+    /// an error for fonts that don't keep their data, see `FontSettings::keep_data`. The override is
+    /// mixed into `Font::file_hash`, so caches keyed on it don't return stale glyphs or layouts.
+    /// # Arguments
+    ///
+    /// * `character` - The character to remap.
+    /// * `glyph_index` - The glyph index in the font to map the character to. Mapping to 0 removes
+    /// the character from the font, making it use the missing glyph.
+    pub fn override_glyph(&mut self, character: char, glyph_index: u16) -> FontResult<()> {
+        if glyph_index >= self.glyph_count() {
+            return Err("Attempted to map a character to a glyph out of bounds.");
+        }
+        self.load_glyph(glyph_index)?;
+        match NonZeroU16::new(glyph_index) {
+            Some(index) => self.char_to_glyph.insert(character, index),
+            None => self.char_to_glyph.remove(&character),
+        };
+        self.glyph_to_char = OnceBox::new();
+        self.hash = crate::hash::hash_continue(self.hash, &(character as u32).to_le_bytes());
+        self.hash = crate::hash::hash_continue(self.hash, &glyph_index.to_le_bytes());
+        Ok(())
+    }
+
+    /// Generates the geometry for the glyph at the given index if the font isn't lazy, since
+    /// non-lazy fonts only load the glyphs reachable from the character map and substitutions.
+    /// Fonts that don't keep their data can't generate glyphs after loading.
+    fn load_glyph(&mut self, index: u16) -> FontResult<()> {
+        if self.lazy_glyphs.is_some() {
+            return Ok(());
+        }
+        match &self.data {
+            Some(data) => {
+                self.glyphs[index as usize] =
+                    generate_glyph(data.face(), index, &self.settings, self.units_per_em);
+                Ok(())
+            }
+            None if self.loaded[index as usize] => Ok(()),
+            None => Err("The glyph wasn't loaded, and the font doesn't keep its data to generate it."),
+        }
+    }
+
     /// Gets the total glyphs in the font.
     pub fn glyph_count(&self) -> u16 {
-        self.glyphs.len() as u16
+        match &self.lazy_glyphs {
+            Some(lazy) => lazy.glyphs.len() as u16,
+            None => self.glyphs.len() as u16,
+        }
+    }
+
+    /// Gets the face parsed from the font data, if the font keeps its data.
+    #[inline]
+    fn face(&self) -> Option<&Face<'_>> {
+        self.data.as_ref().map(|data| data.face())
+    }
+
+    /// Gets the glyph at the given index, generating it first if the font is lazily loaded.
+    #[inline]
+    fn glyph(&self, index: u16) -> &Glyph {
+        match &self.lazy_glyphs {
+            Some(lazy) => lazy.get(self.face(), index, &self.settings, self.units_per_em),
+            None => &self.glyphs[index as usize],
+        }
     }
 }
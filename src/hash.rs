@@ -24,6 +24,7 @@
 //! this hash in places where collissions or DDOS attacks may be a concern.
 
 use core::convert::TryInto;
+use core::hash::{BuildHasherDefault, Hasher};
 use core::ops::BitXor;
 
 const ROTATE: u32 = 5;
@@ -121,3 +122,65 @@ fn write(initial_state: usize, mut bytes: &[u8]) -> usize {
 pub fn hash(bytes: &[u8]) -> usize {
     write(0usize, bytes)
 }
+
+/// Continues hashing from the state returned by a previous hash, for hashing multiple inputs
+/// together.
+pub fn hash_continue(state: usize, bytes: &[u8]) -> usize {
+    write(state, bytes)
+}
+
+/// A fast hasher using the Fx hash algorithm, for hash maps keyed on small values like
+/// `GlyphRasterConfig`. It's much faster than the standard library's default hasher, but isn't
+/// resistant to collision attacks, so it shouldn't be used for keys from untrusted input.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct FxHasher {
+    hash: usize,
+}
+
+/// Builds `FxHasher`s, for use as the hasher of a hash map.
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.hash = write(self.hash, bytes);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.hash.hash_word(i as usize);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.hash.hash_word(i as usize);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.hash.hash_word(i as usize);
+    }
+
+    #[inline]
+    #[cfg(target_pointer_width = "32")]
+    fn write_u64(&mut self, i: u64) {
+        self.hash.hash_word(i as usize);
+        self.hash.hash_word((i >> 32) as usize);
+    }
+
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    fn write_u64(&mut self, i: u64) {
+        self.hash.hash_word(i as usize);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.hash.hash_word(i);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash as u64
+    }
+}
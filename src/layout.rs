@@ -1,14 +1,20 @@
-pub use crate::unicode::CharacterData;
+pub use crate::unicode::{mirror, CharacterData};
 
+use crate::hash::{hash, hash_continue};
 use crate::unicode::{read_utf8, LinebreakData, Linebreaker, LINEBREAK_NONE};
 use crate::Font;
 use crate::{
     platform::{ceil, floor},
-    Metrics,
+    FxBuildHasher, HashMap, LineMetrics, Metrics, ShapedGlyph,
 };
+use alloc::vec;
 use alloc::vec::*;
 use core::borrow::Borrow;
+use core::convert::TryFrom;
 use core::hash::{Hash, Hasher};
+use core::mem;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Horizontal alignment options for text when a max_width is provided.
 #[derive(Copy, Clone, PartialEq)]
@@ -43,6 +49,73 @@ pub enum WrapStyle {
     Letter,
 }
 
+/// The direction text flows in along a line.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TextDirection {
+    /// Glyphs are placed from left to right, and lines fill from the left edge.
+    LeftToRight,
+    /// Glyphs are placed from right to left, and lines fill from the right edge. Characters with a
+    /// mirror image, like brackets, are shown mirrored. This applies a single direction to all of
+    /// the text, it doesn't implement the Unicode bidirectional algorithm.
+    RightToLeft,
+}
+
+/// How lines of text are oriented.
+#[derive(Copy, Clone, PartialEq)]
+pub enum WritingMode {
+    /// Characters are placed along horizontal lines, and lines stack top to bottom.
+    HorizontalTB,
+    /// Characters stack top to bottom within vertical columns, and columns are placed right to
+    /// left. This is the traditional orientation for CJK text.
+    VerticalRL,
+}
+
+/// How control characters, like new lines and stray control bytes, are shown.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ControlHandling {
+    /// Control characters don't have a glyph or take any space.
+    Ignore,
+    /// Control characters other than whitespace, like tabs and new lines, are shown as the
+    /// replacement character U+FFFD.
+    Replace,
+    /// Every control character is shown in caret notation, like "^J" for a new line. Whitespace
+    /// control characters still break lines as usual.
+    Visible,
+}
+
+/// How characters missing from every font in their style are shown.
+#[derive(Copy, Clone, PartialEq)]
+pub enum MissingGlyph {
+    /// Missing characters are shown as the font's .notdef glyph, which is usually a box.
+    Tofu,
+    /// Missing characters are left out of the layout, and don't take any space.
+    Skip,
+    /// Missing characters aren't shown, but take the space of the font's space character.
+    Space,
+}
+
+/// The spacing between the baselines of consecutive lines.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LineHeight {
+    /// A multiplier of the line's size, which is the largest ascent - descent + line gap of the
+    /// fonts on the line.
+    Relative(f32),
+    /// A distance in pixels, regardless of the fonts on the line. This is useful for matching CSS
+    /// line heights, or for aligning text in different fonts to a common grid. Distances smaller
+    /// than the fonts' size make lines overlap.
+    Absolute(f32),
+}
+
+impl LineHeight {
+    /// The distance between the baseline of a line and the next, given the line's size.
+    fn line_size(self, new_line_size: f32) -> f32 {
+        match self {
+            LineHeight::Relative(multiplier) => new_line_size * multiplier,
+            LineHeight::Absolute(size) => size,
+        }
+    }
+}
+
 /// The direction that the Y coordinate increases in. Layout needs to be aware of your coordinate
 /// system to place the glyphs correctly.
 #[derive(Copy, Clone, PartialEq)]
@@ -55,9 +128,44 @@ pub enum CoordinateSystem {
     PositiveYDown,
 }
 
+/// The kind of a line break opportunity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinebreakKind {
+    /// The line may be wrapped here, like after a space.
+    Soft,
+    /// The line must be broken here, like after a new line character.
+    Hard,
+}
+
+/// Finds where lines of text may be broken, following the Unicode line breaking algorithm used by
+/// `Layout`. This is useful for layout engines that only need line breaking.
+/// # Arguments
+///
+/// * `text` - The text to find line breaks in.
+/// # Returns
+///
+/// * `Vec<(usize, LinebreakKind)>` - The byte offsets of characters a new line may start at, and
+/// the kind of break before them, in order. The start and end of the text are never included.
+pub fn line_break_opportunities(text: &str) -> Vec<(usize, LinebreakKind)> {
+    let mut linebreaker = Linebreaker::new();
+    let mut breaks = Vec::new();
+    for (byte_offset, character) in text.char_indices() {
+        let linebreak = linebreaker.next(character);
+        if linebreak.is_hard() {
+            breaks.push((byte_offset, LinebreakKind::Hard));
+        } else if linebreak.is_soft() {
+            breaks.push((byte_offset, LinebreakKind::Soft));
+        }
+    }
+    breaks
+}
+
 /// Settings to configure how text layout is constrained. Text layout is considered best effort and
-/// layout may violate the constraints defined here if they prevent text from being laid out.
+/// layout may violate the constraints defined here if they prevent text from being laid out. New
+/// settings are added over time, so outside of this crate these are created with
+/// `LayoutSettings::default()` or `LayoutSettings::builder()`, and then changed field by field.
 #[derive(Copy, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct LayoutSettings {
     /// The leftmost boundary of the text region.
     pub x: f32,
@@ -76,14 +184,54 @@ pub struct LayoutSettings {
     pub horizontal_align: HorizontalAlign,
     /// The default is Top. This option does nothing if the max_height isn't set.
     pub vertical_align: VerticalAlign,
-    /// The height of each line as a multiplier of the default.
-    pub line_height: f32,
+    /// The default is Relative(1.0). The spacing between the baselines of consecutive lines, either
+    /// as a multiplier of the fonts' line size or as an absolute distance in pixels.
+    pub line_height: LineHeight,
     /// The default is Word. Wrap style is a hint for how strings of text should be wrapped to the
     /// next line. Line wrapping can happen when the max width/height is reached.
     pub wrap_style: WrapStyle,
     /// The default is true. This option enables hard breaks, like new line characters, to
     /// prematurely wrap lines. If false, hard breaks will not prematurely create a new line.
     pub wrap_hard_breaks: bool,
+    /// The default is LeftToRight. The direction text flows in along each line. Right to left
+    /// text mirrors where glyphs are placed within the text region, so horizontal alignment is
+    /// mirrored too. Glyphs are still output in the order of the source text.
+    pub direction: TextDirection,
+    /// The default is HorizontalTB. How lines of text are oriented. In vertical writing modes,
+    /// characters advance by their advance height, columns wrap against the max_height, the
+    /// vertical_align positions text within each column, and the horizontal_align positions the
+    /// columns within the max_width. The direction option only applies to horizontal text.
+    pub writing_mode: WritingMode,
+    /// The default is 0. The distance between tab stops in pixels. Tab characters advance to the
+    /// next tab stop, measured from the start of their line. If this isn't positive, tabs use the
+    /// advance the font gives them, which is usually zero.
+    pub tab_width: f32,
+    /// The default is 0. Extra space in pixels added to the advance of every glyph, apart from
+    /// control characters like tabs and new lines. Negative values tighten the text.
+    pub letter_spacing: f32,
+    /// The default is 0. Extra space in pixels added to the advance of whitespace glyphs, on top
+    /// of the letter spacing.
+    pub word_spacing: f32,
+    /// The default is false. Glyph advances are rounded up to whole pixels unless this is set. If
+    /// set, advances keep their fractional part, so `GlyphPosition::pen_x` is fractional. Glyphs
+    /// can be rasterized with the fractional part of their pen position using
+    /// `Font::rasterize_subpixel_offset`, and drawn at whole pixels for smooth spacing and motion.
+    pub subpixel_positioning: bool,
+    /// The default is Ignore. How control characters are shown. Glyphs shown for a control
+    /// character keep it as their parent and byte offset.
+    pub control_handling: ControlHandling,
+    /// The default is Tofu. How characters missing from the style's font and its fallbacks are
+    /// shown. This doesn't apply to control characters.
+    pub missing_glyph: MissingGlyph,
+    /// The default is false. If set, whitespace at the end of a line doesn't count toward its width
+    /// when aligning it, so centered and right aligned lines line up by their visible text. The
+    /// whitespace glyphs are still output.
+    pub trim_trailing_whitespace: bool,
+    /// The default is 0. The smallest advance in pixels a glyph with ink gets along its line,
+    /// before letter and word spacing. Some decorative and symbol fonts give glyphs zero advance,
+    /// which piles them on top of each other. Combining marks are still placed over their base,
+    /// and blank glyphs like zero width joiners keep their advance.
+    pub min_advance: f32,
 }
 
 impl Default for LayoutSettings {
@@ -95,13 +243,159 @@ impl Default for LayoutSettings {
             max_height: None,
             horizontal_align: HorizontalAlign::Left,
             vertical_align: VerticalAlign::Top,
-            line_height: 1.0,
+            line_height: LineHeight::Relative(1.0),
             wrap_style: WrapStyle::Word,
             wrap_hard_breaks: true,
+            direction: TextDirection::LeftToRight,
+            writing_mode: WritingMode::HorizontalTB,
+            tab_width: 0.0,
+            letter_spacing: 0.0,
+            word_spacing: 0.0,
+            subpixel_positioning: false,
+            control_handling: ControlHandling::Ignore,
+            missing_glyph: MissingGlyph::Tofu,
+            trim_trailing_whitespace: false,
+            min_advance: 0.0,
         }
     }
 }
 
+impl LayoutSettings {
+    /// Creates a builder for layout settings, starting from the defaults.
+    pub fn builder() -> LayoutSettingsBuilder {
+        LayoutSettingsBuilder::default()
+    }
+}
+
+/// Builds `LayoutSettings` with chainable setters, see `LayoutSettings::builder`. Settings that
+/// aren't set keep their default. Every setting is documented on the matching `LayoutSettings`
+/// field.
+#[derive(Copy, Clone, PartialEq, Default)]
+pub struct LayoutSettingsBuilder {
+    settings: LayoutSettings,
+}
+
+impl LayoutSettingsBuilder {
+    /// Creates the layout settings.
+    pub fn build(self) -> LayoutSettings {
+        self.settings
+    }
+
+    /// Sets `LayoutSettings::x`.
+    pub fn x(mut self, x: f32) -> LayoutSettingsBuilder {
+        self.settings.x = x;
+        self
+    }
+
+    /// Sets `LayoutSettings::y`.
+    pub fn y(mut self, y: f32) -> LayoutSettingsBuilder {
+        self.settings.y = y;
+        self
+    }
+
+    /// Sets `LayoutSettings::max_width`.
+    pub fn max_width(mut self, max_width: Option<f32>) -> LayoutSettingsBuilder {
+        self.settings.max_width = max_width;
+        self
+    }
+
+    /// Sets `LayoutSettings::max_height`.
+    pub fn max_height(mut self, max_height: Option<f32>) -> LayoutSettingsBuilder {
+        self.settings.max_height = max_height;
+        self
+    }
+
+    /// Sets `LayoutSettings::horizontal_align`.
+    pub fn horizontal_align(mut self, horizontal_align: HorizontalAlign) -> LayoutSettingsBuilder {
+        self.settings.horizontal_align = horizontal_align;
+        self
+    }
+
+    /// Sets `LayoutSettings::vertical_align`.
+    pub fn vertical_align(mut self, vertical_align: VerticalAlign) -> LayoutSettingsBuilder {
+        self.settings.vertical_align = vertical_align;
+        self
+    }
+
+    /// Sets `LayoutSettings::line_height`.
+    pub fn line_height(mut self, line_height: LineHeight) -> LayoutSettingsBuilder {
+        self.settings.line_height = line_height;
+        self
+    }
+
+    /// Sets `LayoutSettings::wrap_style`.
+    pub fn wrap_style(mut self, wrap_style: WrapStyle) -> LayoutSettingsBuilder {
+        self.settings.wrap_style = wrap_style;
+        self
+    }
+
+    /// Sets `LayoutSettings::wrap_hard_breaks`.
+    pub fn wrap_hard_breaks(mut self, wrap_hard_breaks: bool) -> LayoutSettingsBuilder {
+        self.settings.wrap_hard_breaks = wrap_hard_breaks;
+        self
+    }
+
+    /// Sets `LayoutSettings::direction`.
+    pub fn direction(mut self, direction: TextDirection) -> LayoutSettingsBuilder {
+        self.settings.direction = direction;
+        self
+    }
+
+    /// Sets `LayoutSettings::writing_mode`.
+    pub fn writing_mode(mut self, writing_mode: WritingMode) -> LayoutSettingsBuilder {
+        self.settings.writing_mode = writing_mode;
+        self
+    }
+
+    /// Sets `LayoutSettings::tab_width`.
+    pub fn tab_width(mut self, tab_width: f32) -> LayoutSettingsBuilder {
+        self.settings.tab_width = tab_width;
+        self
+    }
+
+    /// Sets `LayoutSettings::letter_spacing`.
+    pub fn letter_spacing(mut self, letter_spacing: f32) -> LayoutSettingsBuilder {
+        self.settings.letter_spacing = letter_spacing;
+        self
+    }
+
+    /// Sets `LayoutSettings::word_spacing`.
+    pub fn word_spacing(mut self, word_spacing: f32) -> LayoutSettingsBuilder {
+        self.settings.word_spacing = word_spacing;
+        self
+    }
+
+    /// Sets `LayoutSettings::subpixel_positioning`.
+    pub fn subpixel_positioning(mut self, subpixel_positioning: bool) -> LayoutSettingsBuilder {
+        self.settings.subpixel_positioning = subpixel_positioning;
+        self
+    }
+
+    /// Sets `LayoutSettings::control_handling`.
+    pub fn control_handling(mut self, control_handling: ControlHandling) -> LayoutSettingsBuilder {
+        self.settings.control_handling = control_handling;
+        self
+    }
+
+    /// Sets `LayoutSettings::missing_glyph`.
+    pub fn missing_glyph(mut self, missing_glyph: MissingGlyph) -> LayoutSettingsBuilder {
+        self.settings.missing_glyph = missing_glyph;
+        self
+    }
+
+    /// Sets `LayoutSettings::trim_trailing_whitespace`.
+    pub fn trim_trailing_whitespace(mut self, trim_trailing_whitespace: bool) -> LayoutSettingsBuilder {
+        self.settings.trim_trailing_whitespace = trim_trailing_whitespace;
+        self
+    }
+
+    /// Sets `LayoutSettings::min_advance`.
+    pub fn min_advance(mut self, min_advance: f32) -> LayoutSettingsBuilder {
+        self.settings.min_advance = min_advance;
+        self
+    }
+}
+
 /// Configuration for rasterizing a glyph. This struct is also a hashable key that can be used to
 /// uniquely identify a rasterized glyph for applications that want to cache glyphs.
 #[derive(Debug, Copy, Clone)]
@@ -130,6 +424,106 @@ impl PartialEq for GlyphRasterConfig {
 
 impl Eq for GlyphRasterConfig {}
 
+/// A hash map keyed by `GlyphRasterConfig`, for caching rasterized glyphs. This uses the fast
+/// `FxBuildHasher` instead of the default hasher, since glyph caches are looked up for every
+/// glyph drawn. Create one with `GlyphCache::default()`.
+pub type GlyphCache<V> = HashMap<GlyphRasterConfig, V, FxBuildHasher>;
+
+/// A hashable key that identifies a glyph at every size, for caches of size independent data like
+/// outlines or signed distance fields that are scaled when drawn. Create one from the glyph's
+/// `GlyphRasterConfig` with `GlyphRasterConfig::cache_key`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GlyphCacheKey {
+    /// The glyph index represented by the glyph being positioned.
+    pub glyph_index: u16,
+    /// The hash of the font used in layout to raster the glyph.
+    pub font_hash: usize,
+}
+
+impl GlyphRasterConfig {
+    /// Gets the key for this glyph at every size, which leaves out the px.
+    pub fn cache_key(&self) -> GlyphCacheKey {
+        GlyphCacheKey {
+            glyph_index: self.glyph_index,
+            font_hash: self.font_hash,
+        }
+    }
+}
+
+impl From<GlyphRasterConfig> for GlyphCacheKey {
+    fn from(config: GlyphRasterConfig) -> GlyphCacheKey {
+        config.cache_key()
+    }
+}
+
+/// Configuration for rasterizing a glyph at one of a fixed set of horizontal subpixel positions.
+/// This struct is a hashable key that can be used to uniquely identify a rasterized subpixel
+/// variant of a glyph for applications that want to cache glyphs for crisp positioning.
+#[derive(Debug, Copy, Clone)]
+pub struct SubpixelGlyphKey {
+    /// The glyph index represented by the glyph being positioned.
+    pub glyph_index: u16,
+    /// The scale of the glyph being positioned in px.
+    pub px: f32,
+    /// The horizontal position of the glyph within a pixel, quantized to one of
+    /// `SubpixelGlyphKey::VARIANTS` steps. The offset in pixels is this divided by `VARIANTS`.
+    pub x_fraction_quantized: u8,
+    /// The hash of the font used in layout to raster the glyph.
+    pub font_hash: usize,
+}
+
+impl SubpixelGlyphKey {
+    /// The number of subpixel positions a pixel is divided into.
+    pub const VARIANTS: u8 = 4;
+
+    /// Creates a key for the glyph described by the config, positioned at the given x coordinate.
+    /// The fractional part of x is rounded to the nearest subpixel variant.
+    /// # Returns
+    ///
+    /// * `SubpixelGlyphKey` - The key for the glyph's subpixel variant.
+    /// * `f32` - The whole pixel x coordinate that the variant's metrics are relative to.
+    pub fn new(config: GlyphRasterConfig, x: f32) -> (SubpixelGlyphKey, f32) {
+        let mut whole = floor(x);
+        let mut step = (x - whole) * Self::VARIANTS as f32 + 0.5;
+        if step >= Self::VARIANTS as f32 {
+            whole += 1.0;
+            step = 0.0;
+        }
+        let key = SubpixelGlyphKey {
+            glyph_index: config.glyph_index,
+            px: config.px,
+            x_fraction_quantized: step as u8,
+            font_hash: config.font_hash,
+        };
+        (key, whole)
+    }
+
+    /// The horizontal offset of this variant in pixels, in the range [0, 1).
+    pub fn offset_x(&self) -> f32 {
+        self.x_fraction_quantized as f32 / Self::VARIANTS as f32
+    }
+}
+
+impl Hash for SubpixelGlyphKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.glyph_index.hash(state);
+        self.px.to_bits().hash(state);
+        self.x_fraction_quantized.hash(state);
+        self.font_hash.hash(state);
+    }
+}
+
+impl PartialEq for SubpixelGlyphKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.glyph_index == other.glyph_index
+            && self.px == other.px
+            && self.x_fraction_quantized == other.x_fraction_quantized
+            && self.font_hash == other.font_hash
+    }
+}
+
+impl Eq for SubpixelGlyphKey {}
+
 /// A positioned scaled glyph.
 #[derive(Debug, Copy, Clone)]
 pub struct GlyphPosition<U: Copy + Clone = ()> {
@@ -152,11 +546,27 @@ pub struct GlyphPosition<U: Copy + Clone = ()> {
     pub width: usize,
     /// The height of the glyph. Dimensions are in pixels.
     pub height: usize,
+    /// The x position of the pen on the baseline when this glyph was placed. This is the left
+    /// side of the glyph's advance. In vertical writing modes, this is the y position of the pen
+    /// at the top of the glyph's advance instead. Dimensions are in pixels, and are whole numbers
+    /// unless `LayoutSettings::subpixel_positioning` is set.
+    pub pen_x: f32,
+    /// How far the pen moved after placing this glyph. In vertical writing modes, the pen moves
+    /// down. Dimensions are in pixels, and are whole numbers unless
+    /// `LayoutSettings::subpixel_positioning` is set.
+    pub advance: f32,
+    /// The y coordinate of the baseline of the line this glyph is on, the same as the line's
+    /// `LinePosition::baseline_y`. Decorations like underlines can be drawn relative to this
+    /// without finding the glyph's line. In vertical writing modes, this is the x coordinate of the
+    /// column's center line instead.
+    pub baseline_y: f32,
     /// The byte offset into the original string used in the append call which created
     /// this glyph.
     pub byte_offset: usize,
     /// Additional metadata associated with the character used to generate this glyph.
     pub char_data: CharacterData,
+    /// The color of the text style used to generate this glyph.
+    pub color: [u8; 4],
     /// Custom user data associated with the text styled used to generate this glyph.
     pub user_data: U,
 }
@@ -169,6 +579,13 @@ pub struct TextStyle<'a, U: Copy + Clone = ()> {
     pub px: f32,
     /// The font to layout the text in.
     pub font_index: usize,
+    /// Fonts to try in order for characters missing from the font at `font_index`. Characters
+    /// missing from every fallback font use the main font's default glyph. The default is empty.
+    pub fallbacks: &'a [usize],
+    /// The color to associate with glyphs produced by this text style, like for syntax
+    /// highlighting. Layout doesn't use the color, so the channels can be in whatever order the
+    /// renderer expects. The default is opaque white, [255, 255, 255, 255].
+    pub color: [u8; 4],
     /// Additional user data to associate with glyphs produced by this text style.
     pub user_data: U,
 }
@@ -179,6 +596,8 @@ impl<'a> TextStyle<'a> {
             text,
             px,
             font_index,
+            fallbacks: &[],
+            color: [255; 4],
             user_data: (),
         }
     }
@@ -190,18 +609,38 @@ impl<'a, U: Copy + Clone> TextStyle<'a, U> {
             text,
             px,
             font_index,
+            fallbacks: &[],
+            color: [255; 4],
             user_data,
         }
     }
+
+    /// Sets the fonts to try in order for characters missing from the style's font.
+    pub fn with_fallbacks(self, fallbacks: &'a [usize]) -> TextStyle<'a, U> {
+        TextStyle {
+            fallbacks,
+            ..self
+        }
+    }
+
+    /// Sets the color to associate with glyphs produced by this text style.
+    pub fn with_color(self, color: [u8; 4]) -> TextStyle<'a, U> {
+        TextStyle {
+            color,
+            ..self
+        }
+    }
 }
 
 /// Metrics about a positioned line.
 #[derive(Debug, Copy, Clone)]
 pub struct LinePosition {
-    /// The y coordinate of the baseline of this line, in pixels.
+    /// The y coordinate of the baseline of this line, in pixels. In vertical writing modes, this is
+    /// the x coordinate of the column's center line instead.
     pub baseline_y: f32,
     /// How much empty space is left at the end of the line before any alignment. If no max width is
-    /// specified, f32::MAX is used.
+    /// specified, f32::MAX is used. Trailing whitespace counts as empty space if
+    /// `LayoutSettings::trim_trailing_whitespace` is set.
     pub padding: f32,
     /// The highest point that any glyph in the font extends to above the baseline. Typically
     /// positive. If there are multiple styles on this line, this is their max value.
@@ -216,10 +655,13 @@ pub struct LinePosition {
     /// A precalculated value for the of the line depending. It's calculated by: ascent - descent +
     /// line_gap. If there are multiple styles on this line, this is their max value.
     pub max_new_line_size: f32,
-    /// The GlyphPosition index of the first glyph in the line.
+    /// The index in `Layout::glyphs` of the first glyph in the line.
     pub glyph_start: usize,
-    /// The GlyphPosition index of the last glyph in the line.
+    /// The index in `Layout::glyphs` of the last glyph in the line, inclusive.
     pub glyph_end: usize,
+    /// Why the line ended. Hard if it ended at a hard break like a new line character, and soft if
+    /// it was wrapped to fit the max width. This is none for the last line, which hasn't ended.
+    pub terminated_by: Option<LinebreakKind>,
     /// The x offset into the first layout pass.
     tracking_x: f32,
 }
@@ -235,6 +677,7 @@ impl Default for LinePosition {
             max_new_line_size: 0.0,
             glyph_start: 0,
             glyph_end: 0,
+            terminated_by: None,
             tracking_x: 0.0,
         }
     }
@@ -244,9 +687,14 @@ impl Default for LinePosition {
 /// context is reused between layout calls. Reusing the Layout struct will greatly reduce memory
 /// allocations and is advisable for performance.
 pub struct Layout<U: Copy + Clone = ()> {
+    /// The coordinate system to use starting from the next reset.
+    coordinate_system: CoordinateSystem,
     /// Marks if layout should be performed as if the Y axis is flipped (Positive Y incrementing
     /// down instead of up).
     flip: bool,
+    /// Marks if lines are vertical columns instead of horizontal rows. When set, the max width and
+    /// alignment state below are measured along the columns, and the max height across them.
+    vertical: bool,
     /// Origin position. Left side of the region text is being laid out in.
     x: f32,
     /// Origin position. Top side of the region text is being laid out in.
@@ -261,8 +709,8 @@ pub struct Layout<U: Copy + Clone = ()> {
     vertical_align: f32,
     /// A multiplier for how text fills unused horizontal space.
     horizontal_align: f32,
-    /// A multiplier for the amount of space between lines.
-    line_height: f32,
+    /// The spacing between lines.
+    line_height: LineHeight,
     /// The current height of all laid out text.
     height: f32,
 
@@ -284,6 +732,11 @@ pub struct Layout<U: Copy + Clone = ()> {
 
     /// Layout state of each line currently laid out. This always has at least 1 element.
     line_metrics: Vec<LinePosition>,
+    /// The number of leading lines that are already finalized in the output. Lines before the last
+    /// are complete, so they're only finalized again if their position depends on later lines.
+    finalized_lines: usize,
+    /// The index of the first glyph in the output that the last finalize repositioned.
+    finalized_from: usize,
     /// The x position the next glyph starts at.
     current_pos: f32,
     /// The ceil(ascent) of the current style.
@@ -294,6 +747,11 @@ pub struct Layout<U: Copy + Clone = ()> {
     current_line_gap: f32,
     /// The ceil(new_line_size) of the current style.
     current_new_line: f32,
+    /// The ceil(ascent) of the current style's horizontal metrics. Used to place glyphs within
+    /// their cell in vertical writing modes.
+    current_cell_ascent: f32,
+    /// The advance to use in vertical writing modes for glyphs without an advance height.
+    current_cell_advance: f32,
     /// The x position the current line starts at.
     start_pos: f32,
 
@@ -308,7 +766,9 @@ impl<'a, U: Copy + Clone> Layout<U> {
         let settings = LayoutSettings::default();
 
         let mut layout = Layout {
+            coordinate_system,
             flip: coordinate_system == CoordinateSystem::PositiveYDown,
+            vertical: false,
             x: 0.0,
             y: 0.0,
             wrap_mask: LINEBREAK_NONE,
@@ -316,10 +776,12 @@ impl<'a, U: Copy + Clone> Layout<U> {
             max_height: 0.0,
             vertical_align: 0.0,
             horizontal_align: 0.0,
-            line_height: 1.0,
+            line_height: LineHeight::Relative(1.0),
             output: Vec::new(),
             glyphs: Vec::new(),
             line_metrics: Vec::new(),
+            finalized_lines: 0,
+            finalized_from: 0,
             linebreaker: Linebreaker::new(),
             linebreak_prev: LINEBREAK_NONE,
             linebreak_pos: 0.0,
@@ -329,6 +791,8 @@ impl<'a, U: Copy + Clone> Layout<U> {
             current_descent: 0.0,
             current_line_gap: 0.0,
             current_new_line: 0.0,
+            current_cell_ascent: 0.0,
+            current_cell_advance: 0.0,
             start_pos: 0.0,
             height: 0.0,
             settings,
@@ -337,36 +801,53 @@ impl<'a, U: Copy + Clone> Layout<U> {
         layout
     }
 
+    /// Changes the direction that the Y coordinate increases in. This takes effect on the next call
+    /// to `reset`, so text that is already laid out keeps its positions until then.
+    pub fn set_coordinate_system(&mut self, coordinate_system: CoordinateSystem) {
+        self.coordinate_system = coordinate_system;
+    }
+
     /// Resets the current layout settings and clears all appended text.
     pub fn reset(&mut self, settings: &LayoutSettings) {
         self.settings = *settings;
+        self.flip = self.coordinate_system == CoordinateSystem::PositiveYDown;
         self.x = settings.x;
         self.y = settings.y;
-        self.wrap_mask = LinebreakData::from_mask(
-            settings.wrap_style == WrapStyle::Word,
-            settings.wrap_hard_breaks,
-            settings.max_width.is_some(),
-        );
-        self.max_width = settings.max_width.unwrap_or(core::f32::MAX);
-        self.max_height = settings.max_height.unwrap_or(core::f32::MAX);
-        self.vertical_align = if settings.max_height.is_none() {
-            0.0
-        } else {
-            match settings.vertical_align {
+        self.vertical = settings.writing_mode == WritingMode::VerticalRL;
+        let vertical_align = match settings.max_height {
+            None => 0.0,
+            Some(_) => match settings.vertical_align {
                 VerticalAlign::Top => 0.0,
                 VerticalAlign::Middle => 0.5,
                 VerticalAlign::Bottom => 1.0,
-            }
+            },
         };
-        self.horizontal_align = if settings.max_width.is_none() {
-            0.0
-        } else {
-            match settings.horizontal_align {
+        let horizontal_align = match settings.max_width {
+            None => 0.0,
+            Some(_) => match settings.horizontal_align {
                 HorizontalAlign::Left => 0.0,
                 HorizontalAlign::Center => 0.5,
                 HorizontalAlign::Right => 1.0,
-            }
+            },
         };
+        // Vertical text runs along the height and stacks columns across the width, so the axes
+        // are swapped.
+        let (max_line, max_stack) = if self.vertical {
+            self.vertical_align = horizontal_align;
+            self.horizontal_align = vertical_align;
+            (settings.max_height, settings.max_width)
+        } else {
+            self.vertical_align = vertical_align;
+            self.horizontal_align = horizontal_align;
+            (settings.max_width, settings.max_height)
+        };
+        self.wrap_mask = LinebreakData::from_mask(
+            settings.wrap_style == WrapStyle::Word,
+            settings.wrap_hard_breaks,
+            max_line.is_some(),
+        );
+        self.max_width = max_line.unwrap_or(core::f32::MAX);
+        self.max_height = max_stack.unwrap_or(core::f32::MAX);
         self.line_height = settings.line_height;
         self.clear();
     }
@@ -377,6 +858,8 @@ impl<'a, U: Copy + Clone> Layout<U> {
         self.output.clear();
         self.line_metrics.clear();
         self.line_metrics.push(LinePosition::default());
+        self.finalized_lines = 0;
+        self.finalized_from = 0;
 
         self.linebreaker.reset();
         self.linebreak_prev = LINEBREAK_NONE;
@@ -387,6 +870,8 @@ impl<'a, U: Copy + Clone> Layout<U> {
         self.current_descent = 0.0;
         self.current_line_gap = 0.0;
         self.current_new_line = 0.0;
+        self.current_cell_ascent = 0.0;
+        self.current_cell_advance = 0.0;
         self.start_pos = 0.0;
         self.height = 0.0;
     }
@@ -423,104 +908,455 @@ impl<'a, U: Copy + Clone> Layout<U> {
             return;
         }
 
-        let font: &Font = &fonts[style.font_index].borrow();
+        let mut metrics_font = style.font_index;
+        self.apply_line_metrics(fonts[metrics_font].borrow(), style.px);
 
-        if let Some(metrics) = font.horizontal_line_metrics(style.px) {
-            self.current_ascent = ceil(metrics.ascent);
-            self.current_new_line = ceil(metrics.new_line_size);
-            self.current_descent = ceil(metrics.descent);
-            self.current_line_gap = ceil(metrics.line_gap);
-            if let Some(line) = self.line_metrics.last_mut() {
-                if self.current_ascent > line.max_ascent {
-                    line.max_ascent = self.current_ascent;
-                }
-                if self.current_descent < line.min_descent {
-                    line.min_descent = self.current_descent;
-                }
-                if self.current_line_gap > line.max_line_gap {
-                    line.max_line_gap = self.current_line_gap;
+        // The byte offset, pen position, and advance of the base character of the current grapheme
+        // cluster, which combining marks are placed over.
+        let mut cluster: Option<(usize, f32, f32)> = None;
+        let mut byte_offset = 0;
+        // The second character of a control character's caret notation, with the control character
+        // and its byte offset.
+        let mut pending: Option<(char, char, usize)> = None;
+        // The linebreak before a skipped missing character, which moves to the next character.
+        let mut skipped_linebreak = LINEBREAK_NONE;
+        while byte_offset < style.text.len() || pending.is_some() {
+            let (prev_byte_offset, parent, character, linebreak) = match pending.take() {
+                Some((character, parent, offset)) => (offset, parent, character, LINEBREAK_NONE),
+                None => {
+                    let prev_byte_offset = byte_offset;
+                    let parent = read_utf8(style.text.as_bytes(), &mut byte_offset);
+                    let linebreak = self.linebreaker.next(parent).mask(self.wrap_mask);
+                    let (character, caret) = self.display_control(parent);
+                    let character = if self.settings.direction == TextDirection::RightToLeft && !self.vertical
+                    {
+                        mirror(character)
+                    } else {
+                        character
+                    };
+                    pending = caret.map(|caret| (caret, parent, prev_byte_offset));
+                    (prev_byte_offset, parent, character, linebreak)
                 }
-                if self.current_new_line > line.max_new_line_size {
-                    line.max_new_line_size = self.current_new_line;
+            };
+            // Characters missing from the style's font use the first fallback font that has them.
+            let mut font_index = style.font_index;
+            let mut glyph_index = fonts[font_index].borrow().mapped_glyph_index(character);
+            if glyph_index == 0 && !character.is_control() {
+                for &fallback in style.fallbacks {
+                    let fallback_index = fonts[fallback].borrow().mapped_glyph_index(character);
+                    if fallback_index != 0 {
+                        font_index = fallback;
+                        glyph_index = fallback_index;
+                        break;
+                    }
                 }
             }
-        }
-
-        let mut byte_offset = 0;
-        while byte_offset < style.text.len() {
-            let prev_byte_offset = byte_offset;
-            let character = read_utf8(style.text.as_bytes(), &mut byte_offset);
-            let linebreak = self.linebreaker.next(character).mask(self.wrap_mask);
-            let glyph_index = font.lookup_glyph_index(character);
+            let font: &Font = fonts[font_index].borrow();
+            if font_index != metrics_font {
+                metrics_font = font_index;
+                self.apply_line_metrics(font, style.px);
+            }
+            let linebreak = linebreak.max(mem::replace(&mut skipped_linebreak, LINEBREAK_NONE));
             let char_data = CharacterData::classify(character, glyph_index);
-            let metrics = if !char_data.is_control() {
-                font.metrics_indexed(glyph_index, style.px)
+            if char_data.is_missing() {
+                glyph_index = font.default_glyph();
+            }
+            let missing_glyph = if char_data.is_missing() && !char_data.is_control() {
+                self.settings.missing_glyph
             } else {
+                MissingGlyph::Tofu
+            };
+            if missing_glyph == MissingGlyph::Skip {
+                skipped_linebreak = linebreak;
+                cluster = None;
+                continue;
+            }
+            let metrics = if char_data.is_control() {
                 Metrics::default()
+            } else if missing_glyph == MissingGlyph::Space {
+                let space = font.metrics(' ', style.px);
+                Metrics {
+                    advance_width: space.advance_width,
+                    advance_height: space.advance_height,
+                    ..Metrics::default()
+                }
+            } else {
+                font.metrics_indexed(glyph_index, style.px)
+            };
+            let is_tab = character == '\t' && self.settings.tab_width > 0.0;
+            let mut advance = if is_tab {
+                self.tab_advance()
+            } else if !self.vertical {
+                self.round_advance(metrics.advance_width)
+            } else if metrics.advance_height > 0.0 {
+                self.round_advance(metrics.advance_height)
+            } else if char_data.is_control() {
+                0.0
+            } else {
+                self.current_cell_advance
+            };
+            // Combining marks take no space of their own. Marks the font already gives an advance are
+            // centered over their base, otherwise the font positions them over the previous glyph.
+            let mark = if char_data.is_mark() {
+                cluster
+            } else {
+                None
             };
-            let advance = ceil(metrics.advance_width);
+            let mut pen = self.current_pos;
+            if let Some((_, base_pen, base_advance)) = mark {
+                if advance > 0.0 {
+                    pen = base_pen + (base_advance - advance) / 2.0;
+                }
+                advance = 0.0;
+            } else if !char_data.is_control() {
+                if !is_tab && metrics.width > 0 && metrics.height > 0 {
+                    advance = advance.max(self.round_advance(self.settings.min_advance));
+                }
+                advance += self.settings.letter_spacing;
+                if char_data.is_whitespace() {
+                    advance += self.settings.word_spacing;
+                }
+            }
+
+            if self.break_line(linebreak, mark.is_none(), advance) && is_tab {
+                // Tabs that wrap are measured from the start of their new line.
+                advance = self.tab_advance();
+            }
 
-            if linebreak >= self.linebreak_prev {
-                self.linebreak_prev = linebreak;
-                self.linebreak_pos = self.current_pos;
-                self.linebreak_idx = self.glyphs.len().saturating_sub(1); // Mark the previous glyph
+            let (x, y) = self.glyph_origin(pen, &metrics);
+
+            self.glyphs.push(GlyphPosition {
+                key: GlyphRasterConfig {
+                    glyph_index: glyph_index as u16,
+                    px: style.px,
+                    font_hash: font.file_hash(),
+                },
+                font_index,
+                parent,
+                byte_offset: match mark {
+                    Some((cluster_offset, _, _)) => cluster_offset,
+                    None => prev_byte_offset,
+                },
+                x,
+                y,
+                width: metrics.width,
+                height: metrics.height,
+                pen_x: pen,
+                advance,
+                baseline_y: 0.0,
+                char_data,
+                color: style.color,
+                user_data: style.user_data,
+            });
+            if char_data.is_control() {
+                cluster = None;
+            } else if mark.is_none() {
+                cluster = Some((prev_byte_offset, pen, advance));
             }
+            self.current_pos += advance;
+        }
+
+        self.end_line();
+        self.finalize();
+    }
+
+    /// Performs layout the same as `append`, and returns the glyphs that were added or moved by it.
+    /// Glyphs from earlier calls can move, like when a word continues from the previous call and
+    /// wraps onto a new line, or when alignment depends on the size of the whole block. Every glyph
+    /// before the returned ones is unchanged, which makes this useful for streaming text into a
+    /// layout a piece at a time.
+    ///
+    /// Only the last line of earlier calls is positioned again when text is left to right and top
+    /// aligned, so appending a line at a time takes time proportional to the new text.
+    /// # Arguments
+    ///
+    /// * `fonts` - The fonts used by the style.
+    /// * `style` - The text to lay out, and how.
+    /// # Returns
+    ///
+    /// * `&[GlyphPosition<U>]` - The glyphs that were added or repositioned, which are always at
+    /// the end of `Layout::glyphs`.
+    pub fn append_returning<T: Borrow<Font>>(
+        &mut self,
+        fonts: &[T],
+        style: &TextStyle<U>,
+    ) -> &[GlyphPosition<U>] {
+        self.finalized_from = self.output.len();
+        self.append(fonts, style);
+        &self.output[self.finalized_from..]
+    }
+
+    /// Performs layout for glyphs that were already shaped, like by a shaping engine for complex
+    /// scripts. This positions the glyphs along lines and wraps them like `append`, but uses the
+    /// given glyph indices and advances instead of looking up each character. Lines only break
+    /// between clusters, where the text allows it.
+    ///
+    /// Glyphs are used as given, so the style's fallbacks, `LayoutSettings::missing_glyph`,
+    /// `LayoutSettings::control_handling`, and `LayoutSettings::min_advance` don't apply, and right
+    /// to left text isn't mirrored. Letter and word spacing are added to the first glyph of each
+    /// cluster.
+    /// # Arguments
+    ///
+    /// * `fonts` - The fonts used by the style.
+    /// * `style` - The text that was shaped, the size it was shaped at, and the font the glyphs are
+    /// from.
+    /// * `glyphs` - The shaped glyphs, in the order of the text. `ShapedGlyph::cluster` is the byte
+    /// offset into the style's text of the first character the glyph is for, and glyphs for the
+    /// same characters share it. The advance is along the line in pixels.
+    pub fn append_shaped<T: Borrow<Font>>(
+        &mut self,
+        fonts: &[T],
+        style: &TextStyle<U>,
+        glyphs: &[ShapedGlyph],
+    ) {
+        if glyphs.is_empty() {
+            return;
+        }
+        let font: &Font = fonts[style.font_index].borrow();
+        self.apply_line_metrics(font, style.px);
 
-            // Perform a linebreak
-            if linebreak.is_hard() || (self.current_pos - self.start_pos + advance > self.max_width) {
-                self.linebreak_prev = LINEBREAK_NONE;
-                let mut next_glyph_start = self.glyphs().len();
-                if let Some(line) = self.line_metrics.last_mut() {
-                    line.glyph_end = self.linebreak_idx;
-                    line.padding = self.max_width - (self.linebreak_pos - self.start_pos);
-                    self.height += line.max_new_line_size * self.line_height;
-                    next_glyph_start = self.linebreak_idx + 1;
+        let text = style.text.as_bytes();
+        // The byte offset of the next character to feed to the linebreaker.
+        let mut byte_offset = 0;
+        let mut prev_cluster = None;
+        for shaped in glyphs {
+            let new_cluster = prev_cluster != Some(shaped.cluster);
+            prev_cluster = Some(shaped.cluster);
+            // Breaks are only taken at the start of clusters, and hard breaks within a cluster move
+            // to the next one.
+            let mut linebreak = LINEBREAK_NONE;
+            while byte_offset <= shaped.cluster && byte_offset < text.len() {
+                let offset = byte_offset;
+                let data = self.linebreaker.next(read_utf8(text, &mut byte_offset)).mask(self.wrap_mask);
+                if offset == shaped.cluster || data.is_hard() {
+                    linebreak = linebreak.max(data);
                 }
-                self.line_metrics.push(LinePosition {
-                    baseline_y: 0.0,
-                    padding: 0.0,
-                    max_ascent: self.current_ascent,
-                    min_descent: self.current_descent,
-                    max_line_gap: self.current_line_gap,
-                    max_new_line_size: self.current_new_line,
-                    glyph_start: next_glyph_start,
-                    glyph_end: 0,
-                    tracking_x: self.linebreak_pos,
-                });
-                self.start_pos = self.linebreak_pos;
             }
+            let parent =
+                style.text.get(shaped.cluster..).and_then(|text| text.chars().next()).unwrap_or('\0');
 
-            let y = if self.flip {
-                floor(-metrics.bounds.height - metrics.bounds.ymin) // PositiveYDown
+            let char_data = CharacterData::classify(parent, shaped.glyph_index);
+            let metrics = if char_data.is_control() {
+                Metrics::default()
+            } else {
+                font.metrics_indexed(shaped.glyph_index, style.px)
+            };
+            let is_tab = parent == '\t' && self.settings.tab_width > 0.0;
+            let mut advance = if is_tab {
+                self.tab_advance()
+            } else if char_data.is_control() {
+                0.0
             } else {
-                floor(metrics.bounds.ymin) // PositiveYUp
+                self.round_advance(shaped.advance)
             };
+            if new_cluster && !char_data.is_control() {
+                advance += self.settings.letter_spacing;
+                if char_data.is_whitespace() {
+                    advance += self.settings.word_spacing;
+                }
+            }
 
+            if self.break_line(linebreak, new_cluster, advance) && is_tab {
+                advance = self.tab_advance();
+            }
+            self.current_pos += shaped.x_offset;
+            let pen = self.current_pos;
+            let (x, y) = self.glyph_origin(pen, &metrics);
             self.glyphs.push(GlyphPosition {
                 key: GlyphRasterConfig {
-                    glyph_index: glyph_index as u16,
+                    glyph_index: shaped.glyph_index,
                     px: style.px,
                     font_hash: font.file_hash(),
                 },
                 font_index: style.font_index,
-                parent: character,
-                byte_offset: prev_byte_offset,
-                x: floor(self.current_pos + metrics.bounds.xmin),
+                parent,
+                byte_offset: shaped.cluster,
+                x,
                 y,
                 width: metrics.width,
                 height: metrics.height,
+                pen_x: pen,
+                advance,
+                baseline_y: 0.0,
                 char_data,
+                color: style.color,
                 user_data: style.user_data,
             });
             self.current_pos += advance;
         }
+        // The rest of the text still moves the linebreaker along.
+        while byte_offset < text.len() {
+            let character = read_utf8(text, &mut byte_offset);
+            self.linebreaker.next(character);
+        }
+
+        self.end_line();
+        self.finalize();
+    }
 
+    /// Marks the linebreak opportunity before the next glyph, and starts a new line before it if
+    /// the linebreak is hard, or if the glyph can wrap and doesn't fit on the current line.
+    /// # Returns
+    ///
+    /// * `bool` - If a new line was started.
+    fn break_line(&mut self, linebreak: LinebreakData, can_wrap: bool, advance: f32) -> bool {
+        if linebreak >= self.linebreak_prev {
+            self.linebreak_prev = linebreak;
+            self.linebreak_pos = self.current_pos;
+            self.linebreak_idx = self.glyphs.len().saturating_sub(1); // Mark the previous glyph
+        }
+
+        // Perform a linebreak. Glyphs too wide for an empty line overflow it instead of wrapping,
+        // which would leave the line without any glyphs.
+        let line_is_empty =
+            self.line_metrics.last().map_or(true, |line| line.glyph_start >= self.glyphs.len());
+        let overflows = self.current_pos - self.start_pos + advance > self.max_width;
+        if !linebreak.is_hard() && !(can_wrap && !line_is_empty && overflows) {
+            return false;
+        }
+        self.linebreak_prev = LINEBREAK_NONE;
+        let mut next_glyph_start = self.glyphs.len();
+        let line_end = self.line_end(self.linebreak_idx, self.linebreak_pos);
         if let Some(line) = self.line_metrics.last_mut() {
-            line.padding = self.max_width - (self.current_pos - self.start_pos);
+            line.glyph_end = self.linebreak_idx;
+            line.terminated_by = Some(if linebreak.is_hard() {
+                LinebreakKind::Hard
+            } else {
+                LinebreakKind::Soft
+            });
+            line.padding = self.max_width - (line_end - self.start_pos);
+            self.height += self.line_height.line_size(line.max_new_line_size);
+            next_glyph_start = self.linebreak_idx + 1;
+        }
+        self.line_metrics.push(LinePosition {
+            baseline_y: 0.0,
+            padding: 0.0,
+            max_ascent: self.current_ascent,
+            min_descent: self.current_descent,
+            max_line_gap: self.current_line_gap,
+            max_new_line_size: self.current_new_line,
+            glyph_start: next_glyph_start,
+            glyph_end: 0,
+            terminated_by: None,
+            tracking_x: self.linebreak_pos,
+        });
+        self.start_pos = self.linebreak_pos;
+        true
+    }
+
+    /// The position of a glyph's bounding box relative to its line, given its pen position.
+    /// Vertical glyphs are positioned along the column in x and across it in y, relative to the top
+    /// of their cell and the column's center line. These are swapped in finalize.
+    fn glyph_origin(&self, pen: f32, metrics: &Metrics) -> (f32, f32) {
+        if self.vertical {
+            let top = self.current_cell_ascent - (metrics.bounds.ymin + metrics.bounds.height);
+            (floor(pen + top), floor(metrics.bounds.xmin - metrics.advance_width / 2.0))
+        } else if self.flip {
+            (
+                floor(pen + metrics.bounds.xmin),
+                floor(-metrics.bounds.height - metrics.bounds.ymin), // PositiveYDown
+            )
+        } else {
+            (floor(pen + metrics.bounds.xmin), floor(metrics.bounds.ymin)) // PositiveYUp
+        }
+    }
+
+    /// Updates the current line to end at the last glyph.
+    fn end_line(&mut self) {
+        let line_end = self.line_end(self.glyphs.len().saturating_sub(1), self.current_pos);
+        if let Some(line) = self.line_metrics.last_mut() {
+            line.padding = self.max_width - (line_end - self.start_pos);
             line.glyph_end = self.glyphs.len().saturating_sub(1);
         }
+    }
 
-        self.finalize();
+    /// Gets the character to show for the given character, and the second character of its caret
+    /// notation if it's shown that way. Characters other than controls are shown as themselves.
+    fn display_control(&self, character: char) -> (char, Option<char>) {
+        let data = CharacterData::classify(character, 0);
+        if !data.is_control() {
+            return (character, None);
+        }
+        match self.settings.control_handling {
+            ControlHandling::Ignore => (character, None),
+            ControlHandling::Replace if data.is_whitespace() => (character, None),
+            ControlHandling::Replace => ('\u{FFFD}', None),
+            // Caret notation flips the 7th bit, so 0x00 is ^@, 0x0A is ^J, and 0x7F is ^?.
+            ControlHandling::Visible => ('^', Some((character as u8 ^ 0x40) as char)),
+        }
+    }
+
+    /// Gets the position the current line ends at, given its last glyph and where that glyph ends.
+    /// Trailing whitespace is left out if it's trimmed, where combining marks belong with the
+    /// glyph before them.
+    fn line_end(&self, glyph_end: usize, end: f32) -> f32 {
+        if !self.settings.trim_trailing_whitespace {
+            return end;
+        }
+        let glyph_start = self.line_metrics.last().map_or(0, |line| line.glyph_start);
+        let glyphs = self.glyphs.get(glyph_start..=glyph_end).unwrap_or(&[]);
+        match glyphs.iter().rev().find(|glyph| !glyph.char_data.is_whitespace() && !glyph.char_data.is_mark())
+        {
+            Some(glyph) => glyph.pen_x + glyph.advance,
+            None => self.start_pos,
+        }
+    }
+
+    /// Rounds a glyph's advance up to whole pixels, unless subpixel positioning is enabled.
+    fn round_advance(&self, advance: f32) -> f32 {
+        if self.settings.subpixel_positioning {
+            advance
+        } else {
+            ceil(advance)
+        }
+    }
+
+    /// The distance from the current position to the next tab stop on the current line.
+    fn tab_advance(&self) -> f32 {
+        let tab_width = self.settings.tab_width;
+        let offset = self.current_pos - self.start_pos;
+        (floor(offset / tab_width) + 1.0) * tab_width - offset
+    }
+
+    /// Applies the line metrics of the font at the given size to the current style, and grows the
+    /// current line to fit them.
+    fn apply_line_metrics(&mut self, font: &Font, px: f32) {
+        let line_metrics = if self.vertical {
+            let horizontal = font.horizontal_line_metrics(px);
+            self.current_cell_ascent = horizontal.map_or(0.0, |metrics| ceil(metrics.ascent));
+            self.current_cell_advance =
+                horizontal.map_or(px, |metrics| ceil(metrics.ascent - metrics.descent));
+            // Columns are centered on their baseline. Fonts without vertical metrics get columns
+            // one em wide.
+            font.vertical_line_metrics(px).or(Some(LineMetrics {
+                ascent: px / 2.0,
+                descent: -px / 2.0,
+                line_gap: 0.0,
+                new_line_size: px,
+            }))
+        } else {
+            font.horizontal_line_metrics(px)
+        };
+        if let Some(metrics) = line_metrics {
+            self.current_ascent = ceil(metrics.ascent);
+            self.current_new_line = ceil(metrics.new_line_size);
+            self.current_descent = ceil(metrics.descent);
+            self.current_line_gap = ceil(metrics.line_gap);
+            if let Some(line) = self.line_metrics.last_mut() {
+                if self.current_ascent > line.max_ascent {
+                    line.max_ascent = self.current_ascent;
+                }
+                if self.current_descent < line.min_descent {
+                    line.min_descent = self.current_descent;
+                }
+                if self.current_line_gap > line.max_line_gap {
+                    line.max_line_gap = self.current_line_gap;
+                }
+                if self.current_new_line > line.max_new_line_size {
+                    line.max_new_line_size = self.current_new_line;
+                }
+            }
+        }
     }
 
     fn finalize(&mut self) {
@@ -529,8 +1365,14 @@ impl<'a, U: Copy + Clone> Layout<U> {
             return;
         }
 
-        unsafe { self.output.set_len(0) };
-        self.output.reserve(self.glyphs.len());
+        if self.vertical {
+            self.finalized_lines = 0;
+            self.finalized_from = 0;
+            self.output.clear();
+            self.output.reserve(self.glyphs.len());
+            self.finalize_vertical();
+            return;
+        }
 
         let dir = if self.flip {
             -1.0 // PositiveYDown
@@ -538,30 +1380,432 @@ impl<'a, U: Copy + Clone> Layout<U> {
             1.0 // PositiveYUp
         };
 
-        let mut baseline_y = self.y - dir * floor((self.max_height - self.height()) * self.vertical_align);
-        let mut idx = 0;
-        for line in &mut self.line_metrics {
+        // Complete lines keep their position unless it depends on the size of the whole block, like
+        // with vertical alignment or right to left mirroring, so only the lines after them are
+        // finalized again.
+        let incremental = self.vertical_align == 0.0 && self.settings.direction == TextDirection::LeftToRight;
+        if !incremental {
+            self.finalized_lines = 0;
+        }
+        let mut baseline_y = match self.finalized_lines.checked_sub(1) {
+            Some(last) => {
+                let line = &self.line_metrics[last];
+                line.baseline_y - dir * (self.line_height.line_size(line.max_new_line_size) - line.max_ascent)
+            }
+            None => self.y - dir * floor((self.max_height - self.height()) * self.vertical_align),
+        };
+        let mut idx = self.line_metrics[self.finalized_lines].glyph_start;
+        self.finalized_from = idx;
+        self.output.truncate(idx);
+        self.output.reserve(self.glyphs.len() - idx);
+        for line in &mut self.line_metrics[self.finalized_lines..] {
             let x_padding = self.x - line.tracking_x + floor(line.padding * self.horizontal_align);
             baseline_y -= dir * line.max_ascent;
             line.baseline_y = baseline_y;
             while idx <= line.glyph_end {
                 let mut glyph = self.glyphs[idx];
                 glyph.x += x_padding;
+                glyph.pen_x += x_padding;
                 glyph.y += baseline_y;
+                glyph.baseline_y = baseline_y;
                 self.output.push(glyph);
                 idx += 1;
             }
-            baseline_y -= dir * (line.max_new_line_size * self.line_height - line.max_ascent);
+            baseline_y -= dir * (self.line_height.line_size(line.max_new_line_size) - line.max_ascent);
+        }
+        if incremental {
+            self.finalized_lines = self.line_metrics.len() - 1;
+        }
+
+        if self.settings.direction == TextDirection::RightToLeft && !self.vertical {
+            // Mirror the glyphs within the text region. Without a max width, the region is as wide
+            // as the widest line.
+            let width = if self.settings.max_width.is_some() {
+                self.max_width
+            } else {
+                self.output
+                    .iter()
+                    .fold(0.0f32, |width, glyph| width.max(glyph.pen_x + glyph.advance - self.x))
+            };
+            for glyph in &mut self.output {
+                let pen_x = 2.0 * self.x + width - glyph.pen_x - glyph.advance;
+                glyph.x += pen_x - glyph.pen_x;
+                glyph.pen_x = pen_x;
+            }
         }
     }
 
-    /// Gets the currently laid out glyphs.
+    fn finalize_vertical(&mut self) {
+        // Columns are placed right to left, starting from the right side of the block of columns.
+        let width = self.height();
+        let mut baseline_x = self.x + floor((self.max_height - width) * self.vertical_align) + width;
+        let mut idx = 0;
+        for line in &mut self.line_metrics {
+            let padding = floor(line.padding * self.horizontal_align) - line.tracking_x;
+            baseline_x -= line.max_ascent;
+            line.baseline_y = baseline_x;
+            while idx <= line.glyph_end {
+                let mut glyph = self.glyphs[idx];
+                let along = glyph.x + padding;
+                let pen = glyph.pen_x + padding;
+                glyph.x = baseline_x + glyph.y;
+                glyph.baseline_y = baseline_x;
+                if self.flip {
+                    glyph.y = self.y + along; // PositiveYDown
+                    glyph.pen_x = self.y + pen;
+                } else {
+                    glyph.y = self.y - along - glyph.height as f32; // PositiveYUp
+                    glyph.pen_x = self.y - pen;
+                }
+                self.output.push(glyph);
+                idx += 1;
+            }
+            baseline_x -= self.line_height.line_size(line.max_new_line_size) - line.max_ascent;
+        }
+    }
+
+    /// Gets the currently laid out glyphs. Glyphs are in logical order, the order of the source
+    /// text, even when `LayoutSettings::direction` places them right to left. See
+    /// `Layout::glyphs_visual` for the order they appear in.
     pub fn glyphs(&'a self) -> &'a Vec<GlyphPosition<U>> {
         &self.output
     }
 
+    /// Gets the currently laid out glyphs in visual order, line by line, with each line's glyphs in
+    /// the order they appear from left to right. For left to right text and vertical text this is
+    /// the same as `Layout::glyphs`. For right to left text, each line is reversed, but combining
+    /// marks stay after their base so they can still be drawn over it. Cursor movement and
+    /// selection should use the logical order of `Layout::glyphs`.
+    pub fn glyphs_visual(&self) -> Vec<GlyphPosition<U>> {
+        if self.settings.direction == TextDirection::LeftToRight || self.vertical {
+            return self.output.clone();
+        }
+        let mut visual = Vec::with_capacity(self.output.len());
+        for line in &self.line_metrics {
+            let glyphs = self.output.get(line.glyph_start..=line.glyph_end).unwrap_or(&[]);
+            let mut end = glyphs.len();
+            while end > 0 {
+                // Marks follow their base, so a cluster runs back from its last mark to its base.
+                let mut start = end - 1;
+                while start > 0 && glyphs[start].char_data.is_mark() {
+                    start -= 1;
+                }
+                visual.extend_from_slice(&glyphs[start..end]);
+                end = start;
+            }
+        }
+        visual
+    }
+
+    /// Finds the laid out glyph generated by the character at the given byte offset. If the offset
+    /// points into the middle of a character, that character's glyph is returned. Byte offsets are
+    /// relative to the text of the append call that created the glyph, so this expects the laid
+    /// out glyphs to be from a single append call, or to otherwise have increasing byte offsets.
+    /// If there are no glyphs at or before the offset, this returns none.
+    pub fn glyph_at_byte(&'a self, offset: usize) -> Option<&'a GlyphPosition<U>> {
+        let index = self.output.partition_point(|glyph| glyph.byte_offset <= offset);
+        self.output.get(index.checked_sub(1)?)
+    }
+
+    /// Computes the bounding box of the glyphs on a laid out line. Glyphs without a bitmap, like
+    /// spaces, are ignored.
+    /// # Arguments
+    ///
+    /// * `line_index` - The index of the line in `Layout::lines`.
+    /// # Returns
+    ///
+    /// * `Option<(f32, f32, f32, f32)>` - The min x, min y, max x, and max y of the glyphs on the
+    /// line in pixels, or none if the line doesn't exist or has no glyphs with a bitmap.
+    pub fn line_bounds(&self, line_index: usize) -> Option<(f32, f32, f32, f32)> {
+        if self.output.is_empty() {
+            return None;
+        }
+        let line = self.line_metrics.get(line_index)?;
+        Self::glyph_bounds(self.output.get(line.glyph_start..=line.glyph_end)?)
+    }
+
+    /// Gets the laid out glyphs on lines that overlap a vertical range, like the visible part of a
+    /// scrolled document. Lines are found with a binary search, so this is cheap even for huge
+    /// layouts. A line covers the space from its max ascent to its min descent. Vertical layouts
+    /// return every glyph.
+    /// # Arguments
+    ///
+    /// * `y_min` - The smaller y coordinate of the range, in pixels.
+    /// * `y_max` - The larger y coordinate of the range, in pixels.
+    /// # Returns
+    ///
+    /// * `&[GlyphPosition<U>]` - The glyphs of every line overlapping the range, in the same order
+    /// as `Layout::glyphs`.
+    pub fn visible_glyphs(&self, y_min: f32, y_max: f32) -> &[GlyphPosition<U>] {
+        if self.output.is_empty() || self.vertical {
+            return &self.output;
+        }
+        let lines = &self.line_metrics;
+        // Lines move down the page, so their y coordinates are increasing if Y is down and
+        // decreasing if Y is up.
+        let (first, last) = if self.flip {
+            (
+                lines.partition_point(|line| line.baseline_y - line.min_descent < y_min),
+                lines.partition_point(|line| line.baseline_y - line.max_ascent <= y_max),
+            )
+        } else {
+            (
+                lines.partition_point(|line| line.baseline_y + line.min_descent > y_max),
+                lines.partition_point(|line| line.baseline_y + line.max_ascent >= y_min),
+            )
+        };
+        if first >= last {
+            return &[];
+        }
+        self.output.get(lines[first].glyph_start..=lines[last - 1].glyph_end).unwrap_or(&[])
+    }
+
+    /// Computes the bounding box of all the laid out glyphs, which is useful for centering a block
+    /// of text. Glyphs without a bitmap, like spaces, are ignored.
+    /// # Returns
+    ///
+    /// * `Option<(f32, f32, f32, f32)>` - The min x, min y, max x, and max y of the glyphs in
+    /// pixels, or none if there are no glyphs with a bitmap.
+    pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        Self::glyph_bounds(&self.output)
+    }
+
+    /// Computes the bounding box of the given glyphs' bitmaps.
+    fn glyph_bounds(glyphs: &[GlyphPosition<U>]) -> Option<(f32, f32, f32, f32)> {
+        let mut bounds: Option<(f32, f32, f32, f32)> = None;
+        for glyph in glyphs.iter().filter(|glyph| glyph.width > 0 && glyph.height > 0) {
+            let (x0, y0) = (glyph.x, glyph.y);
+            let (x1, y1) = (glyph.x + glyph.width as f32, glyph.y + glyph.height as f32);
+            bounds = Some(match bounds {
+                Some((xmin, ymin, xmax, ymax)) => (xmin.min(x0), ymin.min(y0), xmax.max(x1), ymax.max(y1)),
+                None => (x0, y0, x1, y1),
+            });
+        }
+        bounds
+    }
+
+    /// Computes the rectangles covering a selected range of the laid out text, one for each line
+    /// the selection spans. Each rectangle spans the advances of the selected glyphs along its
+    /// line, and the full size of the line across it. Byte offsets are relative to the
+    /// text of the append call that created the glyphs, so this expects the laid out glyphs to be
+    /// from a single append call, or to otherwise have increasing byte offsets.
+    /// # Arguments
+    ///
+    /// * `start` - The byte offset of the start of the selection, inclusive.
+    /// * `end` - The byte offset of the end of the selection, exclusive.
+    /// # Returns
+    ///
+    /// * `Vec<(f32, f32, f32, f32)>` - The x, y, width, and height of each rectangle in pixels,
+    /// ordered by line. Like `GlyphPosition`, y is the bottom of the rectangle if the coordinate
+    /// system is PositiveYUp and the top if it's PositiveYDown, so (y + height) is the other bound.
+    pub fn selection_rects(&self, start: usize, end: usize) -> Vec<(f32, f32, f32, f32)> {
+        let mut rects = Vec::new();
+        if self.output.is_empty() || start >= end {
+            return rects;
+        }
+        for line in &self.line_metrics {
+            let glyphs = self.output.get(line.glyph_start..=line.glyph_end).unwrap_or(&[]);
+            let mut selected =
+                glyphs.iter().filter(|glyph| glyph.byte_offset >= start && glyph.byte_offset < end);
+            let first = match selected.next() {
+                Some(first) => first,
+                None => continue,
+            };
+            // The pen moves down in PositiveYUp vertical layouts.
+            let span = |glyph: &GlyphPosition<U>| {
+                if self.vertical && !self.flip {
+                    (glyph.pen_x - glyph.advance, glyph.pen_x)
+                } else {
+                    (glyph.pen_x, glyph.pen_x + glyph.advance)
+                }
+            };
+            let (mut min, mut max) = span(first);
+            for glyph in selected {
+                let (start, end) = span(glyph);
+                min = min.min(start);
+                max = max.max(end);
+            }
+            let size = self.line_height.line_size(line.max_new_line_size);
+            if self.vertical {
+                rects.push((line.baseline_y + line.max_ascent - size, min, size, max - min));
+            } else {
+                let y = if self.flip {
+                    line.baseline_y - line.max_ascent // PositiveYDown
+                } else {
+                    line.baseline_y + line.max_ascent - size // PositiveYUp
+                };
+                rects.push((min, y, max - min, size));
+            }
+        }
+        rects
+    }
+
+    /// Computes a hash of everything that affects the output of laying out the given style with the
+    /// given settings, suitable as a cache key for memoizing entire layouts. This includes the
+    /// hashes and default glyphs of the fonts, the text, the px size, the fallback fonts, the color,
+    /// the layout settings, and this layout's coordinate system. The font hashes cover character
+    /// mapping overrides made with `Font::override_glyph`. User data is not included. This is not a
+    /// cryptographic hash.
+    /// # Arguments
+    ///
+    /// * `fonts` - The fonts the text would be laid out with.
+    /// * `style` - The text style that would be appended.
+    /// * `settings` - The settings the layout would be reset with.
+    pub fn input_hash<T: Borrow<Font>>(
+        &self,
+        fonts: &[T],
+        style: &TextStyle<U>,
+        settings: &LayoutSettings,
+    ) -> u64 {
+        let mut state = hash(style.text.as_bytes());
+        for font in fonts {
+            state = hash_continue(state, &font.borrow().file_hash().to_le_bytes());
+            state = hash_continue(state, &font.borrow().default_glyph().to_le_bytes());
+        }
+        let max_width = settings.max_width.map_or(u64::MAX, |width| width.to_bits() as u64);
+        let max_height = settings.max_height.map_or(u64::MAX, |height| height.to_bits() as u64);
+        let words = [
+            style.text.len() as u64,
+            fonts.len() as u64,
+            style.font_index as u64,
+            style.fallbacks.len() as u64,
+            style.px.to_bits() as u64,
+            settings.x.to_bits() as u64,
+            settings.y.to_bits() as u64,
+            max_width,
+            max_height,
+            settings.horizontal_align as u64,
+            settings.vertical_align as u64,
+            match settings.line_height {
+                LineHeight::Relative(multiplier) => multiplier.to_bits() as u64,
+                LineHeight::Absolute(size) => 1 << 32 | size.to_bits() as u64,
+            },
+            settings.wrap_style as u64,
+            settings.wrap_hard_breaks as u64,
+            settings.direction as u64,
+            settings.writing_mode as u64,
+            settings.tab_width.to_bits() as u64,
+            settings.letter_spacing.to_bits() as u64,
+            settings.word_spacing.to_bits() as u64,
+            settings.subpixel_positioning as u64,
+            settings.control_handling as u64,
+            settings.missing_glyph as u64,
+            settings.trim_trailing_whitespace as u64,
+            settings.min_advance.to_bits() as u64,
+            self.flip as u64,
+        ];
+        for word in words {
+            state = hash_continue(state, &word.to_le_bytes());
+        }
+        for &fallback in style.fallbacks {
+            state = hash_continue(state, &(fallback as u64).to_le_bytes());
+        }
+        state = hash_continue(state, &style.color);
+        state as u64
+    }
+
+    /// Rasterizes the laid out glyphs into a single grayscale image. The image is sized to the
+    /// bounds of the rasterized glyphs, so its top left pixel is the top left corner of those
+    /// bounds. Overlapping glyphs keep the highest coverage of either glyph. Images that would have
+    /// more pixels than any of the fonts' `FontSettings::max_bitmap_pixels` are returned empty, with
+    /// a width and height of 0.
+    /// # Arguments
+    ///
+    /// * `fonts` - The fonts the text was laid out with.
+    /// # Returns
+    ///
+    /// * `usize` - The width of the image in pixels.
+    /// * `usize` - The height of the image in pixels.
+    /// * `Vec<u8>` - Coverage vector for the image. Coverage is a linear scale where 0 represents
+    /// 0% coverage of that pixel by a glyph and 255 represents 100% coverage. The vec starts at the
+    /// top left corner of the image, and rows are stored top to bottom.
+    pub fn render_grayscale<T: Borrow<Font>>(&self, fonts: &[T]) -> (usize, usize, Vec<u8>) {
+        // The image is top down, so glyph tops are measured with the Y axis pointing down.
+        // Bounds are measured in 64 bits, so huge layouts can't overflow them before they're checked.
+        let top = |glyph: &GlyphPosition<U>| {
+            if self.flip {
+                glyph.y as i64
+            } else {
+                (glyph.y as i64).saturating_add(glyph.height as i64).saturating_neg()
+            }
+        };
+        let mut xmin = i64::MAX;
+        let mut ymin = i64::MAX;
+        let mut xmax = i64::MIN;
+        let mut ymax = i64::MIN;
+        for glyph in self.output.iter().filter(|glyph| glyph.width > 0 && glyph.height > 0) {
+            xmin = xmin.min(glyph.x as i64);
+            ymin = ymin.min(top(glyph));
+            xmax = xmax.max((glyph.x as i64).saturating_add(glyph.width as i64));
+            ymax = ymax.max(top(glyph).saturating_add(glyph.height as i64));
+        }
+        if xmin > xmax {
+            return (0, 0, Vec::new());
+        }
+
+        let span = |min: i64, max: i64| usize::try_from(max.saturating_sub(min)).unwrap_or(usize::MAX);
+        let width = span(xmin, xmax);
+        let height = span(ymin, ymax);
+        if !fonts.iter().all(|font| font.borrow().fits_bitmap(width, height)) {
+            return (0, 0, Vec::new());
+        }
+        let mut image = vec![0u8; width * height];
+        for glyph in self.output.iter().filter(|glyph| glyph.width > 0 && glyph.height > 0) {
+            let (metrics, bitmap) = fonts[glyph.font_index].borrow().rasterize_config(glyph.key);
+            let x = (glyph.x as i64 - xmin) as usize;
+            let y = (top(glyph) - ymin) as usize;
+            for row in 0..metrics.height {
+                let src = &bitmap[row * metrics.width..(row + 1) * metrics.width];
+                let start = (y + row) * width + x;
+                let dst = &mut image[start..start + metrics.width];
+                for (dst, &src) in dst.iter_mut().zip(src) {
+                    *dst = (*dst).max(src);
+                }
+            }
+        }
+        (width, height, image)
+    }
+
     /// Gets the settings currently being used for layout.
     pub fn settings(&self) -> &LayoutSettings {
         &self.settings
     }
 }
+
+impl<U: Copy + Clone + Send + Sync> Layout<U> {
+    /// Lays out many independent blocks of text, like labels. Each block is laid out as if its
+    /// styles were appended in order to a layout reset with its settings. With the `parallel`
+    /// feature enabled, the blocks are laid out on separate threads.
+    /// # Arguments
+    ///
+    /// * `coordinate_system` - The direction that the Y coordinate increases in.
+    /// * `fonts` - The fonts the styles of every block index into.
+    /// * `blocks` - The settings and styles of each block of text.
+    /// # Returns
+    ///
+    /// * `Vec<Vec<GlyphPosition<U>>>` - The laid out glyphs of each block, in the order the blocks
+    /// were given.
+    pub fn layout_batch<T: Borrow<Font> + Sync>(
+        coordinate_system: CoordinateSystem,
+        fonts: &[T],
+        blocks: &[(LayoutSettings, Vec<TextStyle<U>>)],
+    ) -> Vec<Vec<GlyphPosition<U>>> {
+        let layout_block =
+            |layout: &mut Layout<U>, (settings, styles): &(LayoutSettings, Vec<TextStyle<U>>)| {
+                layout.reset(settings);
+                for style in styles {
+                    layout.append(fonts, style);
+                }
+                layout.output.clone()
+            };
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut layout = Layout::new(coordinate_system);
+            blocks.iter().map(|block| layout_block(&mut layout, block)).collect()
+        }
+
+        #[cfg(feature = "parallel")]
+        blocks.par_iter().map_init(|| Layout::new(coordinate_system), layout_block).collect()
+    }
+}
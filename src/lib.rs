@@ -10,17 +10,24 @@
 
 extern crate alloc;
 
+mod atlas;
 mod font;
 mod hash;
 /// Tools for laying out strings of text.
 pub mod layout;
 mod math;
+mod once;
 mod platform;
 mod raster;
+mod sdf;
+mod serialize;
+mod stroke;
 mod table;
 mod unicode;
 
+pub use crate::atlas::*;
 pub use crate::font::*;
+pub use crate::hash::{FxBuildHasher, FxHasher};
 
 #[cfg(feature = "hashbrown")]
 pub(crate) use hashbrown::{HashMap, HashSet};
@@ -29,3 +36,10 @@ pub(crate) use std::collections::{HashMap, HashSet};
 
 /// Alias for Result<T, &'static str>.
 pub type FontResult<T> = Result<T, &'static str>;
+
+/// Checks if the rasterizer was compiled with its SIMD code paths. These are used on x86 and
+/// x86_64 targets when the `simd` feature is enabled, and the scalar fallbacks are used otherwise.
+/// This is useful for ruling out the fallbacks when comparing performance across machines.
+pub const fn simd_enabled() -> bool {
+    cfg!(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))
+}
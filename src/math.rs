@@ -1,5 +1,6 @@
 use crate::platform::{self, abs, atan2, f32x4, sqrt};
-use crate::{Glyph, OutlineBounds};
+use crate::{Glyph, OutlineBounds, OutlineCommand, OutlinePoint};
+use alloc::boxed::Box;
 use alloc::vec;
 use alloc::vec::*;
 
@@ -310,16 +311,48 @@ impl Line {
     }
 }
 
+/// Moves lines to be relative to the top left of the bounds. Moving the points can round lines
+/// that were nearly horizontal or vertical onto the axis, which the raster can't draw as diagonal
+/// lines. Horizontal lines never add coverage so they're dropped, and vertical ones are drawn as
+/// vertical lines instead.
+fn reposition(v_lines: &mut Vec<Line>, m_lines: &mut Vec<Line>, bounds: AABB, reverse: bool) {
+    for line in v_lines.iter_mut() {
+        line.reposition(bounds, reverse);
+    }
+    m_lines.retain_mut(|line| {
+        line.reposition(bounds, reverse);
+        let (x0, y0, x1, y1) = line.coords.copied();
+        if y0 == y1 {
+            false
+        } else if x0 == x1 {
+            v_lines.push(*line);
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Glyphs rasterized at or below this fraction of their flattening scale use the coarse level of
+/// detail instead, which is flattened for that fraction of the scale.
+pub const COARSE_SCALE_RATIO: f32 = 0.25;
+
+/// The default error allowed when flattening curves into lines, in pixels at the flattening scale.
+pub const DEFAULT_CURVE_TOLERANCE: f32 = 3.0;
+
 #[derive(Clone)]
 pub struct Geometry {
     v_lines: Vec<Line>,
     m_lines: Vec<Line>,
+    coarse_v_lines: Vec<Line>,
+    coarse_m_lines: Vec<Line>,
     effective_bounds: AABB,
     start_point: Point,
     previous_point: Point,
     area: f32,
     reverse_points: bool,
     max_area: f32,
+    coarse_max_area: f32,
 }
 
 struct Segment {
@@ -350,6 +383,7 @@ impl ttf_parser::OutlineBuilder for Geometry {
     fn line_to(&mut self, x0: f32, y0: f32) {
         let next_point = Point::new(x0, y0);
         self.push(self.previous_point, next_point);
+        self.push_coarse(self.previous_point, next_point);
         self.previous_point = next_point;
     }
 
@@ -358,20 +392,8 @@ impl ttf_parser::OutlineBuilder for Geometry {
         let next_point = Point::new(x1, y1);
 
         let curve = QuadCurve::new(self.previous_point, control_point, next_point);
-        let mut stack = vec![Segment::new(self.previous_point, 0.0, next_point, 1.0)];
-        while let Some(seg) = stack.pop() {
-            let bt = (seg.at + seg.ct) * 0.5;
-            let b = curve.point(bt);
-            // This is twice the triangle area
-            let area = (b.x - seg.a.x) * (seg.c.y - seg.a.y) - (seg.c.x - seg.a.x) * (b.y - seg.a.y);
-            if platform::abs(area) > self.max_area {
-                stack.push(Segment::new(seg.a, seg.at, b, bt));
-                stack.push(Segment::new(b, bt, seg.c, seg.ct));
-            } else {
-                self.push(seg.a, seg.c);
-            }
-        }
-
+        self.flatten(next_point, |t| curve.point(t), false);
+        self.flatten(next_point, |t| curve.point(t), true);
         self.previous_point = next_point;
     }
 
@@ -381,25 +403,15 @@ impl ttf_parser::OutlineBuilder for Geometry {
         let next_point = Point::new(x2, y2);
 
         let curve = CubeCurve::new(self.previous_point, first_control, second_control, next_point);
-        let mut stack = vec![Segment::new(self.previous_point, 0.0, next_point, 1.0)];
-        while let Some(seg) = stack.pop() {
-            let bt = (seg.at + seg.ct) * 0.5;
-            let b = curve.point(bt);
-            // This is twice the triangle area
-            let area = (b.x - seg.a.x) * (seg.c.y - seg.a.y) - (seg.c.x - seg.a.x) * (b.y - seg.a.y);
-            if platform::abs(area) > self.max_area {
-                stack.push(Segment::new(seg.a, seg.at, b, bt));
-                stack.push(Segment::new(b, bt, seg.c, seg.ct));
-            } else {
-                self.push(seg.a, seg.c);
-            }
-        }
+        self.flatten(next_point, |t| curve.point(t), false);
+        self.flatten(next_point, |t| curve.point(t), true);
         self.previous_point = next_point;
     }
 
     fn close(&mut self) {
         if self.start_point != self.previous_point {
             self.push(self.previous_point, self.start_point);
+            self.push_coarse(self.previous_point, self.start_point);
         }
         self.previous_point = self.start_point;
     }
@@ -407,13 +419,14 @@ impl ttf_parser::OutlineBuilder for Geometry {
 
 impl Geometry {
     // Artisanal bespoke hand carved curves
-    pub fn new(scale: f32, units_per_em: f32) -> Geometry {
-        const ERROR_THRESHOLD: f32 = 3.0; // In pixels.
-        let max_area = ERROR_THRESHOLD * 2.0 * (units_per_em / scale);
+    pub fn new(scale: f32, units_per_em: f32, curve_tolerance: f32) -> Geometry {
+        let max_area = curve_tolerance * 2.0 * (units_per_em / scale); // Tolerance is in pixels.
 
         Geometry {
             v_lines: Vec::new(),
             m_lines: Vec::new(),
+            coarse_v_lines: Vec::new(),
+            coarse_m_lines: Vec::new(),
             effective_bounds: AABB {
                 xmin: core::f32::MAX,
                 xmax: core::f32::MIN,
@@ -425,6 +438,31 @@ impl Geometry {
             area: 0.0,
             reverse_points: false,
             max_area,
+            coarse_max_area: max_area / COARSE_SCALE_RATIO,
+        }
+    }
+
+    /// Flattens the curve from the previous point to the next point into lines.
+    fn flatten<F: Fn(f32) -> Point>(&mut self, next_point: Point, point: F, coarse: bool) {
+        let max_area = if coarse {
+            self.coarse_max_area
+        } else {
+            self.max_area
+        };
+        let mut stack = vec![Segment::new(self.previous_point, 0.0, next_point, 1.0)];
+        while let Some(seg) = stack.pop() {
+            let bt = (seg.at + seg.ct) * 0.5;
+            let b = point(bt);
+            // This is twice the triangle area
+            let area = (b.x - seg.a.x) * (seg.c.y - seg.a.y) - (seg.c.x - seg.a.x) * (b.y - seg.a.y);
+            if platform::abs(area) > max_area {
+                stack.push(Segment::new(seg.a, seg.at, b, bt));
+                stack.push(Segment::new(b, bt, seg.c, seg.ct));
+            } else if coarse {
+                self.push_coarse(seg.a, seg.c);
+            } else {
+                self.push(seg.a, seg.c);
+            }
         }
     }
 
@@ -442,25 +480,54 @@ impl Geometry {
         }
     }
 
+    /// Pushes a line to the coarse level of detail. The coarse points are a subset of the regular
+    /// points, so they never contribute to the area or bounds.
+    fn push_coarse(&mut self, start: Point, end: Point) {
+        if start.y.to_bits() != end.y.to_bits() {
+            if start.x.to_bits() == end.x.to_bits() {
+                self.coarse_v_lines.push(Line::new(start, end));
+            } else {
+                self.coarse_m_lines.push(Line::new(start, end));
+            }
+        }
+    }
+
     pub(crate) fn finalize(mut self, glyph: &mut Glyph) {
         if self.v_lines.is_empty() && self.m_lines.is_empty() {
             self.effective_bounds = AABB::default();
         } else {
             self.reverse_points = self.area > 0.0;
-            for line in self.v_lines.iter_mut().chain(self.m_lines.iter_mut()) {
-                line.reposition(self.effective_bounds, self.reverse_points);
-            }
+            reposition(&mut self.v_lines, &mut self.m_lines, self.effective_bounds, self.reverse_points);
             self.v_lines.shrink_to_fit();
             self.m_lines.shrink_to_fit();
         }
-        glyph.v_lines = self.v_lines;
-        glyph.m_lines = self.m_lines;
         glyph.bounds = OutlineBounds {
             xmin: self.effective_bounds.xmin,
             ymin: self.effective_bounds.ymin,
             width: self.effective_bounds.xmax - self.effective_bounds.xmin,
             height: self.effective_bounds.ymax - self.effective_bounds.ymin,
         };
+
+        // Only keep the coarse level of detail if it meaningfully reduces the work to raster.
+        let lines = self.v_lines.len() + self.m_lines.len();
+        let coarse_lines = self.coarse_v_lines.len() + self.coarse_m_lines.len();
+        if lines > 0 && coarse_lines * 4 <= lines * 3 {
+            reposition(
+                &mut self.coarse_v_lines,
+                &mut self.coarse_m_lines,
+                self.effective_bounds,
+                self.reverse_points,
+            );
+            self.coarse_v_lines.shrink_to_fit();
+            self.coarse_m_lines.shrink_to_fit();
+            let mut coarse = Glyph::default();
+            coarse.v_lines = self.coarse_v_lines;
+            coarse.m_lines = self.coarse_m_lines;
+            coarse.bounds = glyph.bounds;
+            glyph.coarse = Some(Box::new(coarse));
+        }
+        glyph.v_lines = self.v_lines;
+        glyph.m_lines = self.m_lines;
     }
 
     fn recalculate_bounds(bounds: &mut AABB, x: f32, y: f32) {
@@ -478,3 +545,129 @@ impl Geometry {
         }
     }
 }
+
+/// Slants an outline by moving every point right by its height above the baseline times the
+/// factor. Negative factors slant it backwards.
+pub fn shear(commands: &mut [OutlineCommand], factor: f32) {
+    let shear = |p: &mut OutlinePoint| p.x += p.y * factor;
+    for command in commands {
+        match command {
+            OutlineCommand::MoveTo(p) | OutlineCommand::LineTo(p) => shear(p),
+            OutlineCommand::QuadTo(c, p) => {
+                shear(c);
+                shear(p);
+            }
+            OutlineCommand::CubicTo(c0, c1, p) => {
+                shear(c0);
+                shear(c1);
+                shear(p);
+            }
+            OutlineCommand::Close => {}
+        }
+    }
+}
+
+/// Rotates an outline counterclockwise around the origin, given the sine and cosine of the angle.
+pub fn rotate(commands: &mut [OutlineCommand], sin: f32, cos: f32) {
+    let rotate = |p: &mut OutlinePoint| *p = OutlinePoint::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos);
+    for command in commands {
+        match command {
+            OutlineCommand::MoveTo(p) | OutlineCommand::LineTo(p) => rotate(p),
+            OutlineCommand::QuadTo(c, p) => {
+                rotate(c);
+                rotate(p);
+            }
+            OutlineCommand::CubicTo(c0, c1, p) => {
+                rotate(c0);
+                rotate(c1);
+                rotate(p);
+            }
+            OutlineCommand::Close => {}
+        }
+    }
+}
+
+/// The smallest value of one plus the dot product of the normals at a point while emboldening.
+/// Limits how far points at sharp corners are moved.
+const EMBOLDEN_MIN_MITER: f32 = 0.25;
+
+/// Thickens an outline by moving every point away from the filled area by the given amount, then
+/// moves the outline right by the same amount so its left side bearing is kept. Negative amounts
+/// thin the outline instead. Control points are moved along with the points on the curve, which
+/// keeps the offset curves close to parallel to the original ones.
+pub fn embolden(commands: &[OutlineCommand], amount: f32) -> Vec<OutlineCommand> {
+    // Gather the points of each contour in order, including control points.
+    let mut contours: Vec<Vec<OutlinePoint>> = Vec::new();
+    for command in commands {
+        let points: &[OutlinePoint] = match command {
+            OutlineCommand::MoveTo(p) => {
+                contours.push(Vec::new());
+                core::slice::from_ref(p)
+            }
+            OutlineCommand::LineTo(p) => core::slice::from_ref(p),
+            OutlineCommand::QuadTo(c, p) => &[*c, *p],
+            OutlineCommand::CubicTo(c0, c1, p) => &[*c0, *c1, *p],
+            OutlineCommand::Close => &[],
+        };
+        if let Some(contour) = contours.last_mut() {
+            contour.extend_from_slice(points);
+        }
+    }
+
+    // The filled area is on the left of counter clockwise outlines, and on the right of clockwise
+    // ones. Points move to the other side.
+    let mut area = 0.0;
+    for contour in &contours {
+        for (i, a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+            area += a.x * b.y - b.x * a.y;
+        }
+    }
+    let side = if area > 0.0 {
+        1.0
+    } else {
+        -1.0
+    };
+    let normal = |from: OutlinePoint, to: OutlinePoint| {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let length = sqrt(dx * dx + dy * dy);
+        (dy / length * side, -dx / length * side)
+    };
+
+    let mut shifted: Vec<Vec<OutlinePoint>> = Vec::with_capacity(contours.len());
+    for contour in &contours {
+        let count = contour.len();
+        let mut points = Vec::with_capacity(count);
+        for (i, &point) in contour.iter().enumerate() {
+            // Neighbors are the nearest points that aren't at the same position.
+            let prev = (1..count).map(|step| contour[(i + count - step) % count]).find(|p| *p != point);
+            let next = (1..count).map(|step| contour[(i + step) % count]).find(|p| *p != point);
+            let (dx, dy) = match (prev, next) {
+                (Some(prev), Some(next)) => {
+                    let (ax, ay) = normal(prev, point);
+                    let (bx, by) = normal(point, next);
+                    let factor = amount / (1.0 + ax * bx + ay * by).max(EMBOLDEN_MIN_MITER);
+                    ((ax + bx) * factor, (ay + by) * factor)
+                }
+                _ => (0.0, 0.0),
+            };
+            points.push(OutlinePoint::new(point.x + dx + amount, point.y + dy));
+        }
+        shifted.push(points);
+    }
+
+    // Rebuild the commands from the moved points, which are in the same order as the commands.
+    let mut points = shifted.into_iter().flatten();
+    let mut next = || points.next().unwrap_or_default();
+    let mut output = Vec::with_capacity(commands.len());
+    for command in commands.iter().skip_while(|command| !matches!(command, OutlineCommand::MoveTo(_))) {
+        output.push(match command {
+            OutlineCommand::MoveTo(_) => OutlineCommand::MoveTo(next()),
+            OutlineCommand::LineTo(_) => OutlineCommand::LineTo(next()),
+            OutlineCommand::QuadTo(_, _) => OutlineCommand::QuadTo(next(), next()),
+            OutlineCommand::CubicTo(_, _, _) => OutlineCommand::CubicTo(next(), next(), next()),
+            OutlineCommand::Close => OutlineCommand::Close,
+        });
+    }
+    output
+}
@@ -0,0 +1,76 @@
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// A thread safe cell which can be written to only once. Initialization races are resolved by
+/// keeping the first value stored, any other concurrently initialized values are dropped. This
+/// keeps the cell lock free and usable without the standard library.
+pub(crate) struct OnceBox<T> {
+    inner: AtomicPtr<T>,
+    ghost: PhantomData<Option<Box<T>>>,
+}
+
+unsafe impl<T: Sync + Send> Sync for OnceBox<T> {}
+unsafe impl<T: Send> Send for OnceBox<T> {}
+
+impl<T> OnceBox<T> {
+    /// Creates a new empty cell.
+    pub const fn new() -> OnceBox<T> {
+        OnceBox {
+            inner: AtomicPtr::new(ptr::null_mut()),
+            ghost: PhantomData,
+        }
+    }
+
+    /// Gets a reference to the underlying value if the cell has been initialized.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        let ptr = self.inner.load(Ordering::Acquire);
+        // Safe because the pointer is either null, or a leaked box owned by this cell that is only
+        // released on drop.
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Gets a reference to the underlying value, initializing it with `f` if the cell is empty.
+    #[inline]
+    pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = Box::into_raw(Box::new(f()));
+        match self.inner.compare_exchange(ptr::null_mut(), value, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => unsafe { &*value },
+            Err(existing) => {
+                // Another thread won the race, drop our copy and use theirs.
+                drop(unsafe { Box::from_raw(value) });
+                unsafe { &*existing }
+            }
+        }
+    }
+}
+
+impl<T> Default for OnceBox<T> {
+    fn default() -> Self {
+        OnceBox::new()
+    }
+}
+
+impl<T: Clone> Clone for OnceBox<T> {
+    fn clone(&self) -> Self {
+        let cell = OnceBox::new();
+        if let Some(value) = self.get() {
+            cell.inner.store(Box::into_raw(Box::new(value.clone())), Ordering::Release);
+        }
+        cell
+    }
+}
+
+impl<T> Drop for OnceBox<T> {
+    fn drop(&mut self) {
+        let ptr = *self.inner.get_mut();
+        if !ptr.is_null() {
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+}
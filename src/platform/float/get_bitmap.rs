@@ -1,42 +1,65 @@
 use alloc::vec::*;
 
 #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd")))]
-pub fn get_bitmap(a: &Vec<f32>, length: usize) -> Vec<u8> {
+pub fn get_bitmap(a: &Vec<f32>, length: usize, output: &mut Vec<u8>) {
+    get_bitmap_scalar(a, length, output)
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
+pub fn get_bitmap(a: &Vec<f32>, length: usize, output: &mut Vec<u8>) {
+    get_bitmap_simd(a, length, output)
+}
+
+/// Scalar implementation of get_bitmap. The coverage is accumulated 4 lanes at a time in the same
+/// order as the SIMD implementation so both produce byte identical output. The output is cleared
+/// and reused.
+pub fn get_bitmap_scalar(a: &Vec<f32>, length: usize, output: &mut Vec<u8>) {
     use crate::platform::{abs, clamp};
-    use alloc::vec;
-    let mut height = 0.0;
     assert!(length <= a.len());
-    let mut output = vec![0; length];
-    for i in 0..length {
-        unsafe {
-            height += a.get_unchecked(i);
-            // Clamping because as u8 is undefined outside of its range in rustc.
-            *(output.get_unchecked_mut(i)) = clamp(abs(height) * 255.9, 0.0, 255.0) as u8;
+    output.clear();
+    output.resize(length, 0);
+    let mut offset = 0.0;
+    for i in (0..length).step_by(4) {
+        // Lanes past the end are zeroed, they only contribute to lanes that are never written.
+        let lane = |n: usize| {
+            if i + n < length {
+                unsafe { *a.get_unchecked(i + n) }
+            } else {
+                0.0
+            }
+        };
+        let (x0, x1, x2, x3) = (lane(0), lane(1), lane(2), lane(3));
+        // x += (0.0, x[0], x[1], x[2])
+        let (x1, x2, x3) = (x1 + x0, x2 + x1, x3 + x2);
+        // x += (0.0, 0.0, x[0], x[1])
+        let (x2, x3) = (x2 + x0, x3 + x1);
+        // x += offset
+        let x = [x0 + offset, x1 + offset, x2 + offset, x3 + offset];
+        for n in 0..4 {
+            if i + n < length {
+                // Clamping because as u8 is undefined outside of its range in rustc.
+                unsafe { *(output.get_unchecked_mut(i + n)) = clamp(abs(x[n]) * 255.9, 0.0, 255.0) as u8 };
+            }
         }
+        offset = x[3];
     }
-    output
 }
 
+/// SIMD implementation of get_bitmap. The output is cleared and reused.
 #[allow(clippy::uninit_vec)]
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
-pub fn get_bitmap(a: &Vec<f32>, length: usize) -> Vec<u8> {
+pub fn get_bitmap_simd(a: &Vec<f32>, length: usize, output: &mut Vec<u8>) {
     #[cfg(target_arch = "x86")]
     use core::arch::x86::*;
     #[cfg(target_arch = "x86_64")]
     use core::arch::x86_64::*;
 
     unsafe {
-        // Allocate a 4 byte aligned vector of bytes, and skip zeroing it. Turns out zeroing takes a
-        // while on very large sizes.
-        let mut output = {
-            // Aligned length is ceil(length / 4).
-            let aligned_length = (length + 3) >> 2;
-            let mut aligned: Vec<u32> = Vec::with_capacity(aligned_length);
-            let ptr = aligned.as_mut_ptr();
-            let cap = aligned.capacity() << 2;
-            core::mem::forget(aligned);
-            Vec::from_raw_parts(ptr as *mut u8, aligned_length << 2, cap)
-        };
+        // Size the output to a multiple of 4 bytes, and skip zeroing it. Turns out zeroing takes a
+        // while on very large sizes. Every byte is written below before it's read.
+        output.clear();
+        output.reserve(((length + 3) >> 2) << 2);
+        output.set_len(((length + 3) >> 2) << 2);
         // offset = Zeroed out lanes
         let mut offset = _mm_setzero_ps();
         // Negative zero is important here.
@@ -61,13 +84,12 @@ pub fn get_bitmap(a: &Vec<f32>, length: usize) -> Vec<u8> {
             // the first 4 bytes of y.
             y = _mm_packus_epi16(_mm_packs_epi32(y, nzero), nzero);
 
-            // Store the first 4 u8s from y in output.
-            let pointer: &mut i32 = core::mem::transmute::<&mut u8, &mut i32>(output.get_unchecked_mut(i));
-            *pointer = core::mem::transmute::<__m128i, [i32; 4]>(y)[0];
+            // Store the first 4 u8s from y in output. The output isn't necessarily 4 byte aligned.
+            let pointer = output.get_unchecked_mut(i) as *mut u8 as *mut i32;
+            pointer.write_unaligned(core::mem::transmute::<__m128i, [i32; 4]>(y)[0]);
             // offset = (x[3], x[3], x[3], x[3])
             offset = _mm_set1_ps(core::mem::transmute::<__m128, [f32; 4]>(x)[3]);
         }
         output.truncate(length);
-        output
     }
 }
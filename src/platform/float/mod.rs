@@ -5,6 +5,7 @@ mod ceil;
 mod floor;
 mod fract;
 mod get_bitmap;
+mod sin_cos;
 mod sqrt;
 mod trunc;
 
@@ -15,6 +16,7 @@ pub use ceil::*;
 pub use floor::*;
 pub use fract::*;
 pub use get_bitmap::*;
+pub use sin_cos::*;
 pub use sqrt::*;
 #[allow(unused_imports)]
 pub use trunc::*;
@@ -0,0 +1,54 @@
+/*
+ * origin: FreeBSD /usr/src/lib/msun/src/k_sinf.c, k_cosf.c
+ *
+ * Conversion to float by Ian Lance Taylor, Cygnus Support, ian@cygnus.com.
+ * Optimized by Bruce D. Evans.
+ *
+ * ====================================================
+ * Copyright (C) 1993 by Sun Microsystems, Inc. All rights reserved.
+ *
+ * Developed at SunPro, a Sun Microsystems, Inc. business.
+ * Permission to use, copy, modify, and distribute this
+ * software is freely granted, provided that this notice
+ * is preserved.
+ * ====================================================
+ */
+/// The sine and cosine of an angle in radians. The angle is reduced to within a quarter turn of
+/// zero in double precision, which is plenty for angles within a few turns.
+pub fn sin_cos(x: f32) -> (f32, f32) {
+    /* |sin(x)/x - s(x)| < 2**-37.5 (~[-4.89e-12, 4.824e-12]). */
+    const S1: f64 = -0.166666666416265235595; /* -0x15555554cbac77.0p-55 */
+    const S2: f64 = 0.0083333293858894631756; /*  0x111110896efbb2.0p-59 */
+    const S3: f64 = -0.000198393348360966317347; /* -0x1a00f9e2cae774.0p-65 */
+    const S4: f64 = 0.0000027183114939898219064; /*  0x16cd878c3b46a7.0p-71 */
+    /* |cos(x) - c(x)| < 2**-34.1 (~[-5.37e-11, 5.295e-11]). */
+    const C0: f64 = -0.499999997251031003120; /* -0x1ffffffd0c5e81.0p-54 */
+    const C1: f64 = 0.0416666233237390631894; /*  0x155553e1053a42.0p-57 */
+    const C2: f64 = -0.00138867637746099294692; /* -0x16c087e80f1e27.0p-62 */
+    const C3: f64 = 0.0000243904487962774090654; /*  0x199342e0ee5069.0p-68 */
+
+    if !x.is_finite() {
+        return (core::f32::NAN, core::f32::NAN);
+    }
+    // Reduce to [-pi/4, pi/4] around the nearest multiple of pi/2.
+    let x = x as f64 % (2.0 * core::f64::consts::PI);
+    let quadrant = x * core::f64::consts::FRAC_2_PI;
+    let n = if quadrant < 0.0 {
+        (quadrant - 0.5) as i32
+    } else {
+        (quadrant + 0.5) as i32
+    };
+    let x = x - n as f64 * core::f64::consts::FRAC_PI_2;
+
+    let z = x * x;
+    let w = z * z;
+    let sin = (x + z * x * (S1 + z * S2)) + z * x * w * (S3 + z * S4);
+    let cos = ((1.0 + z * C0) + w * C1) + (w * z) * (C2 + z * C3);
+    let (sin, cos) = (sin as f32, cos as f32);
+    match n & 3 {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
@@ -17,11 +17,10 @@ mod tests {
 
     #[test]
     fn platform_ceil_test() {
-        use core::mem::transmute;
         let mut y = 3.0;
         while y < 9.0 {
             assert_eq!(ceil(y), f32::ceil(y));
-            y = unsafe { transmute::<u32, f32>(transmute::<f32, u32>(y) + 1) };
+            y = f32::from_bits(y.to_bits() + 1);
         }
 
         assert_eq!(ceil(-1.5), -1.0);
@@ -35,11 +34,10 @@ mod tests {
 
     #[test]
     fn platform_floor_test() {
-        use core::mem::transmute;
         let mut y = -3.0;
         while y > -9.0 {
             assert_eq!(ceil(y), f32::ceil(y));
-            y = unsafe { transmute::<u32, f32>(transmute::<f32, u32>(y) + 1) };
+            y = f32::from_bits(y.to_bits() + 1);
         }
 
         assert_eq!(floor(-1.5), -2.0);
@@ -72,4 +70,23 @@ mod tests {
         assert_eq!(trunc(1.0), 1.0);
         assert_eq!(trunc(1.5), 1.0);
     }
+
+    #[test]
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
+    fn platform_get_bitmap_test() {
+        // Pseudo random coverage deltas, similar to what the raster accumulates.
+        let mut seed = 0x1234_5678u32;
+        let mut a = Vec::new();
+        for _ in 0..1027 {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            a.push(((seed >> 8) as f32 / (1 << 24) as f32 - 0.5) * 0.75);
+        }
+        let mut scalar = Vec::new();
+        let mut simd = Vec::new();
+        for length in [0, 1, 3, 4, 5, 6, 7, 1023, 1024] {
+            get_bitmap_scalar(&a, length, &mut scalar);
+            get_bitmap_simd(&a, length, &mut simd);
+            assert_eq!(scalar, simd);
+        }
+    }
 }
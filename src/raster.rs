@@ -6,7 +6,7 @@
 
 use crate::math::Line;
 use crate::platform::{abs, as_i32, copysign, f32x4, fract};
-use crate::Glyph;
+use crate::{FontSettings, Glyph};
 use alloc::vec;
 use alloc::vec::*;
 
@@ -25,6 +25,23 @@ impl Raster {
         }
     }
 
+    /// Creates a raster without any allocation. It must be reset before it's drawn to.
+    pub const fn empty() -> Raster {
+        Raster {
+            w: 0,
+            h: 0,
+            a: Vec::new(),
+        }
+    }
+
+    /// Clears the raster and resizes it, reusing its allocation where possible.
+    pub fn reset(&mut self, w: usize, h: usize) {
+        self.w = w;
+        self.h = h;
+        self.a.clear();
+        self.a.resize(w * h + 3, 0.0);
+    }
+
     pub(crate) fn draw(&mut self, glyph: &Glyph, scale_x: f32, scale_y: f32, offset_x: f32, offset_y: f32) {
         let params = f32x4::new(1.0 / scale_x, 1.0 / scale_y, scale_x, scale_y);
         let scale = f32x4::new(scale_x, scale_y, scale_x, scale_y);
@@ -119,7 +136,78 @@ impl Raster {
     }
 
     #[inline(always)]
-    pub fn get_bitmap(&self) -> Vec<u8> {
-        crate::platform::get_bitmap(&self.a, self.w * self.h)
+    pub fn get_bitmap(&self, settings: &FontSettings) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.get_bitmap_into(&mut output, settings);
+        output
+    }
+
+    /// Writes the bitmap into the given buffer, reusing its allocation where possible. Coverage
+    /// below the font's coverage floor is snapped to zero, and coverage is snapped to either zero
+    /// or full if the font isn't anti-aliased.
+    ///
+    /// The accumulated area is the winding number of each pixel, so taking its absolute value and
+    /// clamping it to full coverage fills with the non-zero rule. Overlapping contours wound the
+    /// same way stay solid, and only contours wound against them cut holes.
+    #[inline(always)]
+    pub fn get_bitmap_into(&self, output: &mut Vec<u8>, settings: &FontSettings) {
+        crate::platform::get_bitmap(&self.a, self.w * self.h, output);
+        if !settings.antialias {
+            let threshold = settings.coverage_floor.max(128);
+            for coverage in output.iter_mut() {
+                *coverage = if *coverage >= threshold {
+                    255
+                } else {
+                    0
+                };
+            }
+        } else if settings.coverage_floor > 0 {
+            for coverage in output.iter_mut() {
+                if *coverage < settings.coverage_floor {
+                    *coverage = 0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Geometry;
+    use ttf_parser::OutlineBuilder;
+
+    fn rect(geometry: &mut Geometry, x0: f32, y0: f32, x1: f32, y1: f32) {
+        geometry.move_to(x0, y0);
+        geometry.line_to(x1, y0);
+        geometry.line_to(x1, y1);
+        geometry.line_to(x0, y1);
+        geometry.close();
+    }
+
+    fn fill(geometry: Geometry, w: usize, h: usize) -> Vec<u8> {
+        let mut glyph = Glyph::default();
+        geometry.finalize(&mut glyph);
+        let mut raster = Raster::new(w, h);
+        raster.draw(&glyph, 1.0, 1.0, 0.0, 0.0);
+        raster.get_bitmap(&FontSettings::default())
+    }
+
+    #[test]
+    fn raster_fills_with_nonzero_winding() {
+        // Overlapping contours wound the same way, like fonts using OVERLAP_SIMPLE.
+        let mut geometry = Geometry::new(1.0, 1.0, 1.0);
+        rect(&mut geometry, 0.0, 0.0, 10.0, 10.0);
+        rect(&mut geometry, 5.0, 0.0, 15.0, 10.0);
+        let bitmap = fill(geometry, 15, 10);
+        assert!(bitmap.iter().all(|&coverage| coverage == 255));
+
+        // A contour wound against the outer one cuts a hole.
+        let mut geometry = Geometry::new(1.0, 1.0, 1.0);
+        rect(&mut geometry, 0.0, 0.0, 10.0, 10.0);
+        rect(&mut geometry, 7.0, 3.0, 3.0, 7.0);
+        let bitmap = fill(geometry, 10, 10);
+        assert_eq!(bitmap[5 * 10 + 5], 0);
+        assert_eq!(bitmap[5 * 10 + 1], 255);
     }
 }
@@ -0,0 +1,170 @@
+use crate::platform::{ceil, clamp, sqrt};
+use crate::{OutlineCommand, OutlinePoint};
+use alloc::vec;
+use alloc::vec::*;
+
+/// The longest a flattened curve segment is allowed to be, in pixels.
+const MAX_SEGMENT_LENGTH: f32 = 1.0;
+
+pub struct Segment {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+}
+
+impl Segment {
+    /// The squared distance from the point to the closest point on the segment.
+    #[inline(always)]
+    fn distance_squared(&self, x: f32, y: f32) -> f32 {
+        let dx = self.x1 - self.x0;
+        let dy = self.y1 - self.y0;
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared > 0.0 {
+            clamp(((x - self.x0) * dx + (y - self.y0) * dy) / length_squared, 0.0, 1.0)
+        } else {
+            0.0
+        };
+        let px = self.x0 + t * dx - x;
+        let py = self.y0 + t * dy - y;
+        px * px + py * py
+    }
+
+    /// The nonzero winding contribution of the segment for a ray cast from the point towards +X.
+    #[inline(always)]
+    fn winding(&self, x: f32, y: f32) -> i32 {
+        if self.y0 <= y {
+            if self.y1 > y && self.side(x, y) > 0.0 {
+                return 1;
+            }
+        } else if self.y1 <= y && self.side(x, y) < 0.0 {
+            return -1;
+        }
+        0
+    }
+
+    /// Positive if the point is left of the segment, negative if it's right of it.
+    #[inline(always)]
+    fn side(&self, x: f32, y: f32) -> f32 {
+        (self.x1 - self.x0) * (y - self.y0) - (x - self.x0) * (self.y1 - self.y0)
+    }
+}
+
+/// Flattens outline commands into line segments. Unlike the geometry used for rasterization, this
+/// keeps horizontal segments, since they still define distances to the outline.
+pub fn flatten(commands: &[OutlineCommand]) -> Vec<Segment> {
+    fn length(a: OutlinePoint, b: OutlinePoint) -> f32 {
+        sqrt((b.x - a.x) * (b.x - a.x) + (b.y - a.y) * (b.y - a.y))
+    }
+
+    let mut segments = Vec::new();
+    let mut start = OutlinePoint::default();
+    let mut previous = OutlinePoint::default();
+    let push = |segments: &mut Vec<Segment>, a: OutlinePoint, b: OutlinePoint| {
+        segments.push(Segment {
+            x0: a.x,
+            y0: a.y,
+            x1: b.x,
+            y1: b.y,
+        })
+    };
+    for command in commands {
+        match *command {
+            OutlineCommand::MoveTo(p) => {
+                start = p;
+                previous = p;
+            }
+            OutlineCommand::LineTo(p) => {
+                push(&mut segments, previous, p);
+                previous = p;
+            }
+            OutlineCommand::QuadTo(c, p) => {
+                let steps = ceil((length(previous, c) + length(c, p)) / MAX_SEGMENT_LENGTH).max(1.0) as usize;
+                let mut last = previous;
+                for step in 1..=steps {
+                    let t = step as f32 / steps as f32;
+                    let u = 1.0 - t;
+                    let next = OutlinePoint::new(
+                        u * u * previous.x + 2.0 * u * t * c.x + t * t * p.x,
+                        u * u * previous.y + 2.0 * u * t * c.y + t * t * p.y,
+                    );
+                    push(&mut segments, last, next);
+                    last = next;
+                }
+                previous = p;
+            }
+            OutlineCommand::CubicTo(c0, c1, p) => {
+                let polygon = length(previous, c0) + length(c0, c1) + length(c1, p);
+                let steps = ceil(polygon / MAX_SEGMENT_LENGTH).max(1.0) as usize;
+                let mut last = previous;
+                for step in 1..=steps {
+                    let t = step as f32 / steps as f32;
+                    let u = 1.0 - t;
+                    let next = OutlinePoint::new(
+                        u * u * u * previous.x
+                            + 3.0 * u * u * t * c0.x
+                            + 3.0 * u * t * t * c1.x
+                            + t * t * t * p.x,
+                        u * u * u * previous.y
+                            + 3.0 * u * u * t * c0.y
+                            + 3.0 * u * t * t * c1.y
+                            + t * t * t * p.y,
+                    );
+                    push(&mut segments, last, next);
+                    last = next;
+                }
+                previous = p;
+            }
+            OutlineCommand::Close => {
+                if previous != start {
+                    push(&mut segments, previous, start);
+                }
+                previous = start;
+            }
+        }
+    }
+    segments
+}
+
+/// Computes a signed distance field for an outline in pixels. Each value is the distance from the
+/// center of the pixel to the outline, positive inside, mapped so 128 is on the outline and the
+/// spread in either direction reaches 0 or 255.
+/// # Arguments
+///
+/// * `commands` - The outline, in pixels with the Y axis increasing upwards.
+/// * `xmin` - The x coordinate of the left side of the field.
+/// * `ymax` - The y coordinate of the top side of the field.
+/// * `width` - The width of the field in pixels.
+/// * `height` - The height of the field in pixels.
+/// * `spread` - The distance in pixels mapped to the full range on either side of the outline.
+pub fn distance_field(
+    commands: &[OutlineCommand],
+    xmin: f32,
+    ymax: f32,
+    width: usize,
+    height: usize,
+    spread: f32,
+) -> Vec<u8> {
+    let segments = flatten(commands);
+    let mut field = vec![0u8; width * height];
+    let scale = 127.0 / spread.max(core::f32::EPSILON);
+    for row in 0..height {
+        let y = ymax - (row as f32 + 0.5);
+        for column in 0..width {
+            let x = xmin + column as f32 + 0.5;
+            let mut distance = core::f32::MAX;
+            let mut winding = 0;
+            for segment in &segments {
+                distance = distance.min(segment.distance_squared(x, y));
+                winding += segment.winding(x, y);
+            }
+            let distance = if winding != 0 {
+                sqrt(distance)
+            } else {
+                -sqrt(distance)
+            };
+            field[row * width + column] = clamp(128.0 + distance * scale, 0.0, 255.0) as u8;
+        }
+    }
+    field
+}
@@ -0,0 +1,174 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+// The header of serialized fonts. Serialized fonts are only meant to be read by the same version of
+// the crate, so the header holds the crate's version rather than a separate format version.
+const MAGIC: [u8; 4] = *b"FDUE";
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Writes values in the crate's little endian serialization format.
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    /// Creates a writer, starting with the format's header.
+    pub fn new() -> Writer {
+        let mut writer = Writer {
+            bytes: Vec::new(),
+        };
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer.write_bytes(VERSION.as_bytes());
+        writer
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i16(&mut self, value: i16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    /// Writes a length, which is stored as a u32.
+    pub fn write_len(&mut self, len: usize) {
+        self.write_u32(len as u32);
+    }
+
+    /// Writes a length prefixed byte slice.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_len(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Writes if the value is present, followed by the value itself if it is.
+    pub fn write_option<T>(&mut self, value: Option<T>, write: impl FnOnce(&mut Writer, T)) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            write(self, value);
+        }
+    }
+}
+
+/// Reads values written by a `Writer`. Every read returns None if the data is truncated or
+/// malformed.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader, returning None if the data doesn't start with the format's header.
+    pub fn new(bytes: &'a [u8]) -> Option<Reader<'a>> {
+        let mut reader = Reader {
+            bytes,
+            offset: 0,
+        };
+        if reader.read_slice(4)? != MAGIC || reader.read_bytes()? != VERSION.as_bytes() {
+            return None;
+        }
+        Some(reader)
+    }
+
+    /// The number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    fn read_slice(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.read_slice(N)?.try_into().ok()
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        match self.read_u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn read_u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i16(&mut self) -> Option<i16> {
+        Some(i16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f32(&mut self) -> Option<f32> {
+        Some(f32::from_bits(self.read_u32()?))
+    }
+
+    /// Reads a length written by `Writer::write_len`.
+    pub fn read_len(&mut self) -> Option<usize> {
+        Some(self.read_u32()? as usize)
+    }
+
+    /// Reads a length prefixed byte slice.
+    pub fn read_bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.read_len()?;
+        self.read_slice(len)
+    }
+
+    /// Reads a value written by `Writer::write_option`.
+    pub fn read_option<T>(&mut self, read: impl FnOnce(&mut Reader<'a>) -> Option<T>) -> Option<Option<T>> {
+        if self.read_bool()? {
+            Some(Some(read(self)?))
+        } else {
+            Some(None)
+        }
+    }
+
+    /// Reads a length prefixed list of values. The initial allocation is bounded by the remaining
+    /// data, so malformed lengths can't reserve huge amounts of memory.
+    pub fn read_vec<T>(&mut self, mut read: impl FnMut(&mut Reader<'a>) -> Option<T>) -> Option<Vec<T>> {
+        let len = self.read_len()?;
+        let mut values = Vec::with_capacity(len.min(self.remaining()));
+        for _ in 0..len {
+            values.push(read(self)?);
+        }
+        Some(values)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::font::Glyph;
+use crate::math::{Geometry, DEFAULT_CURVE_TOLERANCE};
+use crate::platform::sqrt;
+use crate::sdf::flatten;
+use crate::OutlineCommand;
+use ttf_parser::OutlineBuilder;
+
+/// The number of sides of the polygons approximating round joins.
+const JOIN_SIDES: usize = 16;
+/// The cosine and sine of the angle between the corners of a join.
+const JOIN_COS: f32 = 0.923_879_5;
+const JOIN_SIN: f32 = 0.382_683_43;
+
+/// Builds the geometry for a stroke centered on an outline. Every flattened segment of the outline
+/// becomes a band as wide as the stroke, and every point between segments a round join. All of
+/// the pieces wind the same way, so the raster's clamped coverage fills their union.
+/// # Arguments
+///
+/// * `commands` - The outline, in pixels with the Y axis increasing upwards.
+/// * `width` - The width of the stroke in pixels.
+pub fn stroke(commands: &[OutlineCommand], width: f32) -> Glyph {
+    let half = width / 2.0;
+    let mut geometry = Geometry::new(1.0, 1.0, DEFAULT_CURVE_TOLERANCE);
+    for segment in flatten(commands) {
+        let dx = segment.x1 - segment.x0;
+        let dy = segment.y1 - segment.y0;
+        let length = sqrt(dx * dx + dy * dy);
+        if length > 0.0 {
+            let nx = -dy / length * half;
+            let ny = dx / length * half;
+            geometry.move_to(segment.x0 + nx, segment.y0 + ny);
+            geometry.line_to(segment.x1 + nx, segment.y1 + ny);
+            geometry.line_to(segment.x1 - nx, segment.y1 - ny);
+            geometry.line_to(segment.x0 - nx, segment.y0 - ny);
+            geometry.close();
+        }
+
+        // The join at the start of the segment, wound clockwise like the bands.
+        let (mut x, mut y) = (half, 0.0);
+        geometry.move_to(segment.x0 + x, segment.y0 + y);
+        for _ in 1..JOIN_SIDES {
+            let next_x = x * JOIN_COS + y * JOIN_SIN;
+            y = y * JOIN_COS - x * JOIN_SIN;
+            x = next_x;
+            geometry.line_to(segment.x0 + x, segment.y0 + y);
+        }
+        geometry.close();
+    }
+    let mut glyph = Glyph::default();
+    geometry.finalize(&mut glyph);
+    glyph
+}
@@ -0,0 +1,93 @@
+use crate::HashSet;
+use alloc::vec::*;
+use ttf_parser::colr::{ClipBox, CompositeMode, Paint, Painter};
+use ttf_parser::{Face, GlyphId, RgbaColor, Transform};
+
+/// Collects the layers of a color glyph as glyph outlines filled with solid colors. Paints that
+/// can't be expressed that way, like gradients and transforms, mark the glyph as unsupported.
+struct ColorLayers {
+    outline: u16,
+    layers: Vec<(u16, RgbaColor)>,
+    supported: bool,
+}
+
+impl<'a> Painter<'a> for ColorLayers {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        self.outline = glyph_id.0;
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        match paint {
+            Paint::Solid(color) => self.layers.push((self.outline, color)),
+            _ => self.supported = false,
+        }
+    }
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, _: ClipBox) {}
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, mode: CompositeMode) {
+        if mode != CompositeMode::SourceOver {
+            self.supported = false;
+        }
+    }
+
+    fn pop_layer(&mut self) {}
+
+    fn push_translate(&mut self, _: f32, _: f32) {
+        self.supported = false;
+    }
+
+    fn push_scale(&mut self, _: f32, _: f32) {
+        self.supported = false;
+    }
+
+    fn push_rotate(&mut self, _: f32) {
+        self.supported = false;
+    }
+
+    fn push_skew(&mut self, _: f32, _: f32) {
+        self.supported = false;
+    }
+
+    fn push_transform(&mut self, _: Transform) {
+        self.supported = false;
+    }
+
+    fn pop_transform(&mut self) {}
+}
+
+/// Gets the layers of the color glyph at the given index from the default palette, back to front.
+/// Each layer is a glyph index and the color to fill it with. Layers using the foreground color
+/// are filled with the given foreground. Returns none if the glyph isn't a color glyph, or uses
+/// paints other than solid colors.
+pub fn color_layers(face: &Face, index: u16, foreground: RgbaColor) -> Option<Vec<(u16, RgbaColor)>> {
+    let mut painter = ColorLayers {
+        outline: 0,
+        layers: Vec::new(),
+        supported: true,
+    };
+    face.paint_color_glyph(GlyphId(index), 0, foreground, &mut painter)?;
+    if !painter.supported || painter.layers.is_empty() {
+        return None;
+    }
+    Some(painter.layers)
+}
+
+/// Adds the glyphs used as layers by any of the color glyphs already marked to load.
+pub fn load_colr(face: &Face, indices_to_load: &mut HashSet<u16>) {
+    if face.tables().colr.is_none() {
+        return;
+    }
+    let indices: Vec<u16> = indices_to_load.iter().copied().collect();
+    for index in indices {
+        if let Some(layers) = color_layers(face, index, RgbaColor::new(0, 0, 0, 255)) {
+            for (layer, _) in layers {
+                indices_to_load.insert(layer);
+            }
+        }
+    }
+}
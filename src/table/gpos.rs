@@ -0,0 +1,277 @@
+use crate::table::parse::*;
+use crate::HashMap;
+use alloc::vec::*;
+
+// Microsoft: https://docs.microsoft.com/en-us/typography/opentype/spec/gpos
+
+const LOOKUP_PAIR_ADJUSTMENT: u16 = 2;
+const LOOKUP_EXTENSION: u16 = 9;
+const VALUE_X_ADVANCE: u16 = 0x0004;
+
+/// Pair adjustment kerning from the GPOS table, in the same layout as the kern table's mappings.
+#[derive(Debug)]
+pub struct TableGpos {
+    pub horizontal_mappings: HashMap<u32, i16>,
+}
+
+impl TableGpos {
+    /// Reads the horizontal advance adjustments from the pair adjustment lookups used by the
+    /// `kern` feature. When several subtables adjust the same pair, the first one wins, matching
+    /// how shapers apply them. Class based pairs are only expanded for the given glyphs, since
+    /// expanding them for every glyph in large fonts takes a lot of memory.
+    pub fn new(gpos: &[u8], class_glyphs: &[u16]) -> Option<TableGpos> {
+        let mut stream = Stream::new(gpos);
+        let version_major = stream.read_u16()?;
+        if version_major != 1 {
+            return None; // Font.gpos: Unsupported GPOS table version.
+        }
+        stream.skip(4); // minorVersion: u16, scriptListOffset: u16
+        let feature_list = gpos.get(stream.read_u16()? as usize..)?;
+        let lookup_list = gpos.get(stream.read_u16()? as usize..)?;
+
+        let mut mappings = HashMap::new();
+        for lookup_index in Self::read_kern_lookups(feature_list)? {
+            if let Some(lookup) = Self::read_offset16(lookup_list, 2 + 2 * lookup_index as usize) {
+                Self::read_lookup(lookup, class_glyphs, &mut mappings);
+            }
+        }
+
+        if mappings.is_empty() {
+            return None;
+        }
+        Some(TableGpos {
+            horizontal_mappings: mappings,
+        })
+    }
+
+    /// Gets the sorted, unique lookup indices of every `kern` feature.
+    fn read_kern_lookups(feature_list: &[u8]) -> Option<Vec<u16>> {
+        let mut stream = Stream::new(feature_list);
+        let feature_count = stream.read_u16()?;
+        let mut lookups = Vec::new();
+        for _ in 0..feature_count {
+            let tag = stream.read_tag()?;
+            let offset = stream.read_u16()? as usize;
+            if &tag != b"kern" {
+                continue;
+            }
+            let mut feature = Stream::new(feature_list.get(offset..)?);
+            feature.skip(2); // featureParamsOffset: u16
+            let lookup_count = feature.read_u16()?;
+            for _ in 0..lookup_count {
+                lookups.push(feature.read_u16()?);
+            }
+        }
+        lookups.sort_unstable();
+        lookups.dedup();
+        Some(lookups)
+    }
+
+    fn read_lookup(lookup: &[u8], class_glyphs: &[u16], mappings: &mut HashMap<u32, i16>) -> Option<()> {
+        let mut stream = Stream::new(lookup);
+        let lookup_type = stream.read_u16()?;
+        stream.skip(2); // lookupFlag: u16
+        let subtable_count = stream.read_u16()?;
+        for index in 0..subtable_count {
+            let subtable = match Self::read_offset16(lookup, 6 + 2 * index as usize) {
+                Some(subtable) => subtable,
+                None => continue,
+            };
+            let subtable = match lookup_type {
+                LOOKUP_PAIR_ADJUSTMENT => subtable,
+                LOOKUP_EXTENSION => {
+                    let mut extension = Stream::new(subtable);
+                    extension.skip(2); // posFormat: u16
+                    if extension.read_u16()? != LOOKUP_PAIR_ADJUSTMENT {
+                        continue;
+                    }
+                    subtable.get(extension.read_u32()? as usize..)?
+                }
+                _ => return None,
+            };
+            let mut format = Stream::new(subtable);
+            match format.read_u16()? {
+                1 => Self::read_format1(subtable, mappings),
+                2 => Self::read_format2(subtable, class_glyphs, mappings),
+                _ => None,
+            };
+        }
+        Some(())
+    }
+
+    /// Reads pair adjustments listed by glyph.
+    fn read_format1(subtable: &[u8], mappings: &mut HashMap<u32, i16>) -> Option<()> {
+        let mut stream = Stream::new(subtable);
+        stream.skip(2); // posFormat: u16
+        let coverage = read_coverage(subtable.get(stream.read_u16()? as usize..)?)?;
+        let value_format1 = stream.read_u16()?;
+        let value_format2 = stream.read_u16()?;
+        let record_size = 2 + value_record_size(value_format1) + value_record_size(value_format2);
+        let pair_set_count = stream.read_u16()?;
+        if value_format1 & VALUE_X_ADVANCE == 0 {
+            return Some(());
+        }
+        let advance_offset = x_advance_offset(value_format1);
+        for (left, coverage_index) in coverage {
+            if coverage_index >= pair_set_count {
+                continue;
+            }
+            let pair_set = match Self::read_offset16(subtable, 10 + 2 * coverage_index as usize) {
+                Some(pair_set) => pair_set,
+                None => continue,
+            };
+            let mut pairs = Stream::new(pair_set);
+            let pair_count = pairs.read_u16()?;
+            for _ in 0..pair_count {
+                let start = pairs.offset();
+                let right = pairs.read_u16()?;
+                pairs.seek(start + 2 + advance_offset);
+                let value = pairs.read_i16()?;
+                pairs.seek(start + record_size);
+                mappings.entry(u32::from(left) << 16 | u32::from(right)).or_insert(value);
+            }
+        }
+        Some(())
+    }
+
+    /// Reads pair adjustments defined between classes of glyphs.
+    fn read_format2(subtable: &[u8], class_glyphs: &[u16], mappings: &mut HashMap<u32, i16>) -> Option<()> {
+        let mut stream = Stream::new(subtable);
+        stream.skip(2); // posFormat: u16
+        let coverage = read_coverage(subtable.get(stream.read_u16()? as usize..)?)?;
+        let value_format1 = stream.read_u16()?;
+        let value_format2 = stream.read_u16()?;
+        let class_def1 = subtable.get(stream.read_u16()? as usize..)?;
+        let class_def2 = subtable.get(stream.read_u16()? as usize..)?;
+        let class1_count = stream.read_u16()? as usize;
+        let class2_count = stream.read_u16()? as usize;
+        let records = stream.offset();
+        if value_format1 & VALUE_X_ADVANCE == 0 {
+            return Some(());
+        }
+        let record_size = value_record_size(value_format1) + value_record_size(value_format2);
+        let advance_offset = x_advance_offset(value_format1);
+
+        // Group the glyphs by their second class. Glyphs in class 0 are everything not otherwise
+        // classified, which are skipped since fonts rarely adjust them.
+        let mut classes: Vec<Vec<u16>> = Vec::new();
+        classes.resize_with(class2_count, Vec::new);
+        for &glyph in class_glyphs {
+            let class = read_class(class_def2, glyph) as usize;
+            if class != 0 && class < class2_count {
+                classes[class].push(glyph);
+            }
+        }
+
+        for (left, _) in coverage {
+            let class1 = read_class(class_def1, left) as usize;
+            if class1 >= class1_count {
+                continue;
+            }
+            for (class2, rights) in classes.iter().enumerate() {
+                if rights.is_empty() {
+                    continue;
+                }
+                let mut value = Stream::new(subtable);
+                value.seek(records + (class1 * class2_count + class2) * record_size + advance_offset);
+                let value = value.read_i16()?;
+                if value == 0 {
+                    continue;
+                }
+                for &right in rights {
+                    mappings.entry(u32::from(left) << 16 | u32::from(right)).or_insert(value);
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Reads the 16 bit offset at the given position in the data, and gets the data it points to.
+    fn read_offset16(data: &[u8], position: usize) -> Option<&[u8]> {
+        let mut stream = Stream::new(data);
+        stream.seek(position);
+        let offset = stream.read_u16()? as usize;
+        if offset == 0 {
+            return None;
+        }
+        data.get(offset..)
+    }
+}
+
+/// The size in bytes of a value record with the given format.
+fn value_record_size(format: u16) -> usize {
+    (format & 0x00FF).count_ones() as usize * 2
+}
+
+/// The offset in bytes of the x advance in a value record with the given format.
+fn x_advance_offset(format: u16) -> usize {
+    (format & 0x0003).count_ones() as usize * 2
+}
+
+/// Reads a coverage table into a list of glyphs and their coverage indices.
+fn read_coverage(coverage: &[u8]) -> Option<Vec<(u16, u16)>> {
+    let mut stream = Stream::new(coverage);
+    let format = stream.read_u16()?;
+    let count = stream.read_u16()?;
+    let mut glyphs = Vec::new();
+    match format {
+        1 => {
+            for index in 0..count {
+                glyphs.push((stream.read_u16()?, index));
+            }
+        }
+        2 => {
+            for _ in 0..count {
+                let start = stream.read_u16()?;
+                let end = stream.read_u16()?;
+                let start_index = stream.read_u16()?;
+                for glyph in start..=end.max(start) {
+                    glyphs.push((glyph, start_index.wrapping_add(glyph - start)));
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(glyphs)
+}
+
+/// Reads the class of a glyph from a class definition table. Unclassified glyphs are class 0.
+fn read_class(class_def: &[u8], glyph: u16) -> u16 {
+    let mut stream = Stream::new(class_def);
+    match stream.read_u16() {
+        Some(1) => {
+            let (start, count) = match (stream.read_u16(), stream.read_u16()) {
+                (Some(start), Some(count)) => (start, count),
+                _ => return 0,
+            };
+            if glyph < start || glyph - start >= count {
+                return 0;
+            }
+            stream.skip(2 * (glyph - start) as usize);
+            stream.read_u16().unwrap_or(0)
+        }
+        Some(2) => {
+            let count = stream.read_u16().unwrap_or(0);
+            let ranges = match stream.read_u16_slice(count as usize * 3) {
+                Some(ranges) => ranges,
+                None => return 0,
+            };
+            // Ranges are sorted by start glyph.
+            let (mut low, mut high) = (0, count as usize);
+            while low < high {
+                let mid = (low + high) / 2;
+                let start = ranges.get(mid * 3).unwrap_or(0);
+                let end = ranges.get(mid * 3 + 1).unwrap_or(0);
+                if glyph < start {
+                    high = mid;
+                } else if glyph > end {
+                    low = mid + 1;
+                } else {
+                    return ranges.get(mid * 3 + 2).unwrap_or(0);
+                }
+            }
+            0
+        }
+        _ => 0,
+    }
+}
@@ -0,0 +1,27 @@
+use crate::table::parse::*;
+
+// Microsoft: https://docs.microsoft.com/en-us/typography/opentype/spec/hdmx
+
+/// Reads the advance width in whole pixels of a glyph at the given size in pixels per em from the
+/// horizontal device metrics table. Returns None if the table doesn't have a record for the size.
+pub fn device_advance(hdmx: &[u8], glyph_count: u16, index: u16, ppem: u8) -> Option<u8> {
+    let mut stream = Stream::new(hdmx);
+    if stream.read_u16()? != 0 {
+        return None; // Font.hdmx: Unsupported hdmx table version.
+    }
+    let record_count = stream.read_i16()?.max(0) as usize;
+    let record_size = stream.read_u32()? as usize;
+    if index >= glyph_count || record_size < 2 + glyph_count as usize {
+        return None;
+    }
+    // Records are sorted by size, but there are only ever a handful of them.
+    for record in 0..record_count {
+        let start = 8 + record * record_size;
+        stream.seek(start);
+        if stream.read_u8()? == ppem {
+            stream.seek(start + 2 + index as usize); // pixelSize: u8, maxWidth: u8
+            return stream.read_u8();
+        }
+    }
+    None
+}
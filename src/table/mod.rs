@@ -1,6 +1,12 @@
+mod colr;
+mod gpos;
 mod gsub;
+mod hdmx;
 mod kern;
 pub mod parse;
 
+pub use self::colr::{color_layers, load_colr};
+pub use self::gpos::*;
 pub use self::gsub::load_gsub;
+pub use self::hdmx::device_advance;
 pub use self::kern::*;
@@ -2,6 +2,7 @@ mod tables;
 
 use crate::unicode::tables::*;
 use alloc::string::String;
+use core::cmp::Ordering;
 
 const CONT_MASK: u8 = 0b0011_1111;
 
@@ -159,6 +160,7 @@ impl CharacterData {
     const WHITESPACE: u8 = 0b0000_0001;
     const CONTROL: u8 = 0b0000_0010;
     const MISSING: u8 = 0b0000_0100;
+    const MARK: u8 = 0b0000_1000;
 
     /// Classifies a character given its index in the font.
     pub fn classify(c: char, index: u16) -> CharacterData {
@@ -174,6 +176,9 @@ impl CharacterData {
             '\0'..='\x1F' | '\x7F' => class |= CharacterData::CONTROL,
             _ => {}
         }
+        if is_combining_mark(c) {
+            class |= CharacterData::MARK;
+        }
         CharacterData {
             bits: class,
         }
@@ -182,7 +187,7 @@ impl CharacterData {
     /// A heuristic for if the glpyh this was classified from should be rasterized. Missing glyphs,
     /// whitespace, and control characters will return false.
     pub fn rasterize(&self) -> bool {
-        self.bits == 0
+        self.bits & !CharacterData::MARK == 0
     }
 
     /// Marks if the character is an ASCII whitespace character.
@@ -199,4 +204,40 @@ impl CharacterData {
     pub fn is_missing(&self) -> bool {
         self.bits & CharacterData::MISSING != 0
     }
+
+    /// Marks if the character is a nonspacing or enclosing combining mark, or a zero width joiner.
+    /// These join the preceding character's grapheme cluster.
+    pub fn is_mark(&self) -> bool {
+        self.bits & CharacterData::MARK != 0
+    }
+}
+
+/// Gets the mirror image of a character for right to left text, like ')' for '('. Characters
+/// without a mirror image are returned unchanged.
+pub fn mirror(c: char) -> char {
+    match BIDI_MIRRORING_TABLE.binary_search_by(|&(character, _)| character.cmp(&c)) {
+        Ok(index) => BIDI_MIRRORING_TABLE[index].1,
+        Err(_) => c,
+    }
+}
+
+/// Checks if the character is a nonspacing or enclosing combining mark, or a zero width joiner.
+fn is_combining_mark(c: char) -> bool {
+    if c < '\u{300}' {
+        return false;
+    }
+    if c == '\u{200D}' {
+        return true;
+    }
+    NONSPACING_MARK_TABLE
+        .binary_search_by(|&(start, end)| {
+            if end < c {
+                Ordering::Less
+            } else if start > c {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .is_ok()
 }